@@ -79,6 +79,9 @@ impl Mul for QuarticExtension<GoldilocksField> {
 impl Extendable<5> for GoldilocksField {
     type Extension = QuinticExtension<Self>;
 
+    // `X^5 - 3` is irreducible, since 5 divides `p - 1` and 3 is not a fifth power, i.e. the
+    // `DTH_ROOT` below is not one. Verifiable in Sage with
+    // `R.<x> = GF(p)[]; assert (x^5 - 3).is_irreducible()`.
     const W: Self = Self(3);
 
     // DTH_ROOT = W^((ORDER - 1)/5)
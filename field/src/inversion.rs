@@ -74,7 +74,8 @@ pub(crate) fn try_inverse_u64<F: PrimeField64>(x: &F) -> Option<F> {
     let mut c = 1i128;
     let mut d = 0i128;
 
-    if f == 0 {
+    // `f` may be a non-canonical encoding of zero, which must not reach the loop below.
+    if x.is_zero() {
         return None;
     }
 
@@ -131,6 +131,7 @@ macro_rules! test_prime_field_arithmetic {
                 let modulus = <$field>::ORDER;
 
                 assert_eq!(zero.try_inverse(), None);
+                assert_eq!(<$field>::from_noncanonical_u64(modulus).try_inverse(), None);
 
                 let inputs = $crate::prime_field_testing::test_inputs(modulus);
 
@@ -1,5 +1,9 @@
+#[cfg(feature = "serde_json")]
+use alloc::string::String;
 use alloc::vec::Vec;
 
+use serde::{Deserialize, Serialize};
+
 use crate::fri::reduction_strategies::FriReductionStrategy;
 
 mod challenges;
@@ -9,7 +13,7 @@ pub mod prover;
 pub mod recursive_verifier;
 pub mod reduction_strategies;
 pub mod structure;
-mod validate_shape;
+pub(crate) mod validate_shape;
 pub mod verifier;
 pub mod witness_util;
 
@@ -52,6 +56,12 @@ impl FriConfig {
     pub fn num_cap_elements(&self) -> usize {
         1 << self.cap_height
     }
+
+    /// The conjectured security of the FRI query phase, in bits, ignoring the size of the field;
+    /// see the ethSTARK paper.
+    pub fn query_security_bits(&self) -> usize {
+        self.num_query_rounds * self.rate_bits + self.proof_of_work_bits as usize
+    }
 }
 
 /// FRI parameters, including generated parameters which are specific to an instance size, in
@@ -99,3 +109,53 @@ impl FriParams {
         1 << self.final_poly_bits()
     }
 }
+
+/// A self-contained description of the FRI parameters of a circuit, for verifiers implemented
+/// outside of this crate. With the `serde_json` feature, it can be exported as JSON.
+#[derive(Debug, Clone, Eq, PartialEq, Serialize, Deserialize)]
+pub struct FriParamsExport {
+    /// `rate = 2^{-rate_bits}`.
+    pub rate_bits: usize,
+
+    /// Height of Merkle tree caps.
+    pub cap_height: usize,
+
+    /// Number of query rounds to perform.
+    pub num_query_rounds: usize,
+
+    pub proof_of_work_bits: u32,
+
+    /// The arity of each FRI reduction step, expressed as the log2 of the actual arity.
+    pub reduction_arity_bits: Vec<usize>,
+
+    /// The degree of the purported codeword, measured in bits.
+    pub degree_bits: usize,
+
+    /// Whether Merkle tree leaves are salted.
+    pub hiding: bool,
+}
+
+#[cfg(feature = "serde_json")]
+impl FriParamsExport {
+    pub fn to_json(&self) -> String {
+        serde_json::to_string(self).expect("FriParamsExport is always serializable")
+    }
+
+    pub fn from_json(json: &str) -> serde_json::Result<Self> {
+        serde_json::from_str(json)
+    }
+}
+
+impl From<&FriParams> for FriParamsExport {
+    fn from(params: &FriParams) -> Self {
+        Self {
+            rate_bits: params.config.rate_bits,
+            cap_height: params.config.cap_height,
+            num_query_rounds: params.config.num_query_rounds,
+            proof_of_work_bits: params.config.proof_of_work_bits,
+            reduction_arity_bits: params.reduction_arity_bits.clone(),
+            degree_bits: params.degree_bits,
+            hiding: params.hiding,
+        }
+    }
+}
@@ -10,7 +10,7 @@ use crate::field::packed::PackedField;
 use crate::field::polynomial::{PolynomialCoeffs, PolynomialValues};
 use crate::field::types::Field;
 use crate::fri::proof::FriProof;
-use crate::fri::prover::fri_proof;
+use crate::fri::prover::fri_proof_with_query_indices;
 use crate::fri::structure::{FriBatchInfo, FriInstanceInfo};
 use crate::fri::FriParams;
 use crate::hash::hash_types::RichField;
@@ -165,6 +165,21 @@ impl<F: RichField + Extendable<D>, C: GenericConfig<D, F = F>, const D: usize>
         challenger: &mut Challenger<F, C::Hasher>,
         fri_params: &FriParams,
         timing: &mut TimingTree,
+    ) -> FriProof<F, C::Hasher, D> {
+        Self::prove_openings_with_query_indices(
+            instance, oracles, challenger, fri_params, None, timing,
+        )
+    }
+
+    /// Like `prove_openings`, but with the FRI query indices optionally fixed in advance; see
+    /// `fri_proof_with_query_indices`.
+    pub(crate) fn prove_openings_with_query_indices(
+        instance: &FriInstanceInfo<F, D>,
+        oracles: &[&Self],
+        challenger: &mut Challenger<F, C::Hasher>,
+        fri_params: &FriParams,
+        fixed_query_indices: Option<&[usize]>,
+        timing: &mut TimingTree,
     ) -> FriProof<F, C::Hasher, D> {
         assert!(D > 1, "Not implemented for D=1.");
         let alpha = challenger.get_extension_challenge::<D>();
@@ -205,7 +220,7 @@ impl<F: RichField + Extendable<D>, C: GenericConfig<D, F = F>, const D: usize>
             lde_final_poly.coset_fft(F::coset_shift().into())
         );
 
-        let fri_proof = fri_proof::<F, C, D>(
+        let fri_proof = fri_proof_with_query_indices::<F, C, D>(
             &oracles
                 .par_iter()
                 .map(|c| &c.merkle_tree)
@@ -214,6 +229,7 @@ impl<F: RichField + Extendable<D>, C: GenericConfig<D, F = F>, const D: usize>
             lde_final_values,
             challenger,
             fri_params,
+            fixed_query_indices,
             timing,
         );
 
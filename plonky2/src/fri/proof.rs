@@ -134,6 +134,16 @@ pub struct CompressedFriProof<F: RichField + Extendable<D>, H: Hasher<F>, const
 }
 
 impl<F: RichField + Extendable<D>, H: Hasher<F>, const D: usize> FriProof<F, H, D> {
+    /// The length of the Merkle paths authenticating the initial evaluations of each query round,
+    /// i.e. `degree_bits + rate_bits - cap_height`. All initial oracles are committed to with trees
+    /// of the same height, so each round has a single length.
+    pub fn query_path_lengths(&self) -> Vec<usize> {
+        self.query_round_proofs
+            .iter()
+            .map(|round| round.initial_trees_proof.evals_proofs[0].1.len())
+            .collect()
+    }
+
     /// Compress all the Merkle paths in the FRI proof and remove duplicate indices.
     pub fn compress<C: GenericConfig<D, F = F, Hasher = H>>(
         self,
@@ -26,6 +26,32 @@ pub fn fri_proof<F: RichField + Extendable<D>, C: GenericConfig<D, F = F>, const
     challenger: &mut Challenger<F, C::Hasher>,
     fri_params: &FriParams,
     timing: &mut TimingTree,
+) -> FriProof<F, C::Hasher, D> {
+    fri_proof_with_query_indices::<F, C, D>(
+        initial_merkle_trees,
+        lde_polynomial_coeffs,
+        lde_polynomial_values,
+        challenger,
+        fri_params,
+        None,
+        timing,
+    )
+}
+
+/// Like `fri_proof`, but if `fixed_query_indices` is given, the query rounds open those indices
+/// instead of ones derived from the challenger. A proof built with fixed indices is not sound.
+pub(crate) fn fri_proof_with_query_indices<
+    F: RichField + Extendable<D>,
+    C: GenericConfig<D, F = F>,
+    const D: usize,
+>(
+    initial_merkle_trees: &[&MerkleTree<F, C::Hasher>],
+    lde_polynomial_coeffs: PolynomialCoeffs<F::Extension>,
+    lde_polynomial_values: PolynomialValues<F::Extension>,
+    challenger: &mut Challenger<F, C::Hasher>,
+    fri_params: &FriParams,
+    fixed_query_indices: Option<&[usize]>,
+    timing: &mut TimingTree,
 ) -> FriProof<F, C::Hasher, D> {
     let n = lde_polynomial_values.len();
     assert_eq!(lde_polynomial_coeffs.len(), n);
@@ -50,8 +76,14 @@ pub fn fri_proof<F: RichField + Extendable<D>, C: GenericConfig<D, F = F>, const
     );
 
     // Query phase
-    let query_round_proofs =
-        fri_prover_query_rounds::<F, C, D>(initial_merkle_trees, &trees, challenger, n, fri_params);
+    let query_round_proofs = fri_prover_query_rounds::<F, C, D>(
+        initial_merkle_trees,
+        &trees,
+        challenger,
+        n,
+        fri_params,
+        fixed_query_indices,
+    );
 
     FriProof {
         commit_phase_merkle_caps: trees.iter().map(|t| t.cap.clone()).collect(),
@@ -169,12 +201,25 @@ fn fri_prover_query_rounds<
     challenger: &mut Challenger<F, C::Hasher>,
     n: usize,
     fri_params: &FriParams,
+    fixed_query_indices: Option<&[usize]>,
 ) -> Vec<FriQueryRound<F, C::Hasher, D>> {
-    challenger
-        .get_n_challenges(fri_params.config.num_query_rounds)
+    let x_indices = match fixed_query_indices {
+        Some(indices) => {
+            assert!(
+                indices.iter().all(|&x_index| x_index < n),
+                "Query index out of range of the LDE domain."
+            );
+            indices.to_vec()
+        }
+        None => challenger
+            .get_n_challenges(fri_params.config.num_query_rounds)
+            .into_iter()
+            .map(|rand| rand.to_canonical_u64() as usize % n)
+            .collect(),
+    };
+    x_indices
         .into_par_iter()
-        .map(|rand| {
-            let x_index = rand.to_canonical_u64() as usize % n;
+        .map(|x_index| {
             fri_prover_query_round::<F, C, D>(initial_merkle_trees, trees, x_index, fri_params)
         })
         .collect()
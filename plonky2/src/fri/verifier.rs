@@ -70,41 +70,16 @@ pub fn verify_fri_proof<
     proof: &FriProof<F, C::Hasher, D>,
     params: &FriParams,
 ) -> Result<()> {
-    validate_fri_proof_shape::<F, C, D>(proof, instance, params)?;
-
-    // Size of the LDE domain.
-    let n = params.lde_size();
-
-    // Check PoW.
-    fri_verify_proof_of_work(challenges.fri_pow_response, &params.config)?;
-
-    // Check that parameters are coherent.
-    ensure!(
-        params.config.num_query_rounds == proof.query_round_proofs.len(),
-        "Number of query rounds does not match config."
-    );
-
-    let precomputed_reduced_evals =
-        PrecomputedReducedOpenings::from_os_and_alpha(openings, challenges.fri_alpha);
-    for (&x_index, round_proof) in challenges
-        .fri_query_indices
-        .iter()
-        .zip(&proof.query_round_proofs)
-    {
-        fri_verifier_query_round::<F, C, D>(
-            instance,
-            challenges,
-            &precomputed_reduced_evals,
-            initial_merkle_caps,
-            proof,
-            x_index,
-            n,
-            round_proof,
-            params,
-        )?;
-    }
-
-    Ok(())
+    // The checks are those recorded by `trace_fri_proof`, so both share a single implementation.
+    trace_fri_proof::<F, C, D>(
+        instance,
+        openings,
+        challenges,
+        initial_merkle_caps,
+        proof,
+        params,
+    )?
+    .ensure_passed()
 }
 
 fn fri_verify_initial_proof<F: RichField, H: Hasher<F>>(
@@ -162,7 +137,134 @@ pub(crate) fn fri_combine_initial<
     sum * subgroup_x
 }
 
-fn fri_verifier_query_round<
+/// The intermediate values of one FRI folding step in a query round, as recorded by
+/// `trace_fri_proof`.
+#[derive(Clone, Debug)]
+pub struct FriFoldingStepTrace<F: RichField + Extendable<D>, const D: usize> {
+    pub arity_bits: usize,
+    /// The evaluation derived in the previous step, or from the initial openings.
+    pub expected_eval: F::Extension,
+    /// The evaluation at the same point in the coset opened by the prover.
+    pub committed_eval: F::Extension,
+    /// The evaluation of the folded polynomial, derived from the opened coset.
+    pub folded_eval: F::Extension,
+    pub merkle_proof_valid: bool,
+}
+
+impl<F: RichField + Extendable<D>, const D: usize> FriFoldingStepTrace<F, D> {
+    pub fn passed(&self) -> bool {
+        self.expected_eval == self.committed_eval && self.merkle_proof_valid
+    }
+}
+
+/// The intermediate values of one FRI query round, as recorded by `trace_fri_proof`.
+#[derive(Clone, Debug)]
+pub struct FriQueryRoundTrace<F: RichField + Extendable<D>, const D: usize> {
+    pub x_index: usize,
+    pub initial_merkle_proofs_valid: bool,
+    pub steps: Vec<FriFoldingStepTrace<F, D>>,
+    /// The evaluation derived by the last folding step.
+    pub folded_eval: F::Extension,
+    /// The evaluation of the final polynomial sent by the prover.
+    pub final_poly_eval: F::Extension,
+}
+
+impl<F: RichField + Extendable<D>, const D: usize> FriQueryRoundTrace<F, D> {
+    pub fn passed(&self) -> bool {
+        self.initial_merkle_proofs_valid
+            && self.steps.iter().all(|step| step.passed())
+            && self.folded_eval == self.final_poly_eval
+    }
+}
+
+/// The intermediate values of FRI verification, as recorded by `trace_fri_proof`.
+#[derive(Clone, Debug)]
+pub struct FriTrace<F: RichField + Extendable<D>, const D: usize> {
+    pub proof_of_work_valid: bool,
+    pub query_rounds: Vec<FriQueryRoundTrace<F, D>>,
+}
+
+impl<F: RichField + Extendable<D>, const D: usize> FriTrace<F, D> {
+    pub fn passed(&self) -> bool {
+        self.proof_of_work_valid && self.query_rounds.iter().all(|round| round.passed())
+    }
+
+    /// Returns an error for the first failed check, in the order in which a verifier stopping at
+    /// the first failure would perform them.
+    pub(crate) fn ensure_passed(&self) -> Result<()> {
+        ensure!(self.proof_of_work_valid, "Invalid proof of work witness.");
+        for round in &self.query_rounds {
+            ensure!(round.initial_merkle_proofs_valid, "Invalid Merkle proof.");
+            for step in &round.steps {
+                // Check consistency with our old evaluation from the previous round.
+                ensure!(
+                    step.expected_eval == step.committed_eval,
+                    "Inconsistent evaluation in FRI folding step."
+                );
+                ensure!(step.merkle_proof_valid, "Invalid Merkle proof.");
+            }
+            // Final check of FRI. After all the reductions, we check that the final polynomial is
+            // equal to the one sent by the prover.
+            ensure!(
+                round.folded_eval == round.final_poly_eval,
+                "Final polynomial evaluation is invalid."
+            );
+        }
+        Ok(())
+    }
+}
+
+/// Performs the checks of FRI verification, recording the outcome of each instead of stopping at the
+/// first failing one. Only a malformed proof results in an error.
+pub(crate) fn trace_fri_proof<
+    F: RichField + Extendable<D>,
+    C: GenericConfig<D, F = F>,
+    const D: usize,
+>(
+    instance: &FriInstanceInfo<F, D>,
+    openings: &FriOpenings<F, D>,
+    challenges: &FriChallenges<F, D>,
+    initial_merkle_caps: &[MerkleCap<F, C::Hasher>],
+    proof: &FriProof<F, C::Hasher, D>,
+    params: &FriParams,
+) -> Result<FriTrace<F, D>> {
+    validate_fri_proof_shape::<F, C, D>(proof, instance, params)?;
+    ensure!(
+        params.config.num_query_rounds == proof.query_round_proofs.len(),
+        "Number of query rounds does not match config."
+    );
+
+    let n = params.lde_size();
+    let precomputed_reduced_evals =
+        PrecomputedReducedOpenings::from_os_and_alpha(openings, challenges.fri_alpha);
+    let query_rounds = challenges
+        .fri_query_indices
+        .iter()
+        .zip(&proof.query_round_proofs)
+        .map(|(&x_index, round_proof)| {
+            trace_fri_query_round::<F, C, D>(
+                instance,
+                challenges,
+                &precomputed_reduced_evals,
+                initial_merkle_caps,
+                proof,
+                x_index,
+                n,
+                round_proof,
+                params,
+            )
+        })
+        .collect();
+
+    Ok(FriTrace {
+        proof_of_work_valid: fri_verify_proof_of_work(challenges.fri_pow_response, &params.config)
+            .is_ok(),
+        query_rounds,
+    })
+}
+
+/// Performs the checks of one FRI query round, recording the outcome of each.
+fn trace_fri_query_round<
     F: RichField + Extendable<D>,
     C: GenericConfig<D, F = F>,
     const D: usize,
@@ -172,16 +274,17 @@ fn fri_verifier_query_round<
     precomputed_reduced_evals: &PrecomputedReducedOpenings<F, D>,
     initial_merkle_caps: &[MerkleCap<F, C::Hasher>],
     proof: &FriProof<F, C::Hasher, D>,
-    mut x_index: usize,
+    x_index: usize,
     n: usize,
     round_proof: &FriQueryRound<F, C::Hasher, D>,
     params: &FriParams,
-) -> Result<()> {
-    fri_verify_initial_proof::<F, C::Hasher>(
+) -> FriQueryRoundTrace<F, D> {
+    let initial_merkle_proofs_valid = fri_verify_initial_proof::<F, C::Hasher>(
         x_index,
         &round_proof.initial_trees_proof,
         initial_merkle_caps,
-    )?;
+    )
+    .is_ok();
     // `subgroup_x` is `subgroup[x_index]`, i.e., the actual field element in the domain.
     let log_n = log2_strict(n);
     let mut subgroup_x = F::MULTIPLICATIVE_GROUP_GENERATOR
@@ -198,47 +301,52 @@ fn fri_verifier_query_round<
         params,
     );
 
+    let mut steps = Vec::with_capacity(params.reduction_arity_bits.len());
+    let mut index = x_index;
     for (i, &arity_bits) in params.reduction_arity_bits.iter().enumerate() {
         let arity = 1 << arity_bits;
         let evals = &round_proof.steps[i].evals;
 
         // Split x_index into the index of the coset x is in, and the index of x within that coset.
-        let coset_index = x_index >> arity_bits;
-        let x_index_within_coset = x_index & (arity - 1);
-
-        // Check consistency with our old evaluation from the previous round.
-        ensure!(evals[x_index_within_coset] == old_eval);
+        let coset_index = index >> arity_bits;
+        let x_index_within_coset = index & (arity - 1);
 
         // Infer P(y) from {P(x)}_{x^arity=y}.
-        old_eval = compute_evaluation(
+        let folded_eval = compute_evaluation(
             subgroup_x,
             x_index_within_coset,
             arity_bits,
             evals,
             challenges.fri_betas[i],
         );
-
-        verify_merkle_proof_to_cap::<F, C::Hasher>(
+        let merkle_proof_valid = verify_merkle_proof_to_cap::<F, C::Hasher>(
             flatten(evals),
             coset_index,
             &proof.commit_phase_merkle_caps[i],
             &round_proof.steps[i].merkle_proof,
-        )?;
+        )
+        .is_ok();
+        steps.push(FriFoldingStepTrace {
+            arity_bits,
+            expected_eval: old_eval,
+            committed_eval: evals[x_index_within_coset],
+            folded_eval,
+            merkle_proof_valid,
+        });
 
+        old_eval = folded_eval;
         // Update the point x to x^arity.
         subgroup_x = subgroup_x.exp_power_of_2(arity_bits);
-
-        x_index = coset_index;
+        index = coset_index;
     }
 
-    // Final check of FRI. After all the reductions, we check that the final polynomial is equal
-    // to the one sent by the prover.
-    ensure!(
-        proof.final_poly.eval(subgroup_x.into()) == old_eval,
-        "Final polynomial evaluation is invalid."
-    );
-
-    Ok(())
+    FriQueryRoundTrace {
+        x_index,
+        initial_merkle_proofs_valid,
+        steps,
+        folded_eval: old_eval,
+        final_poly_eval: proof.final_poly.eval(subgroup_x.into()),
+    }
 }
 
 /// For each opening point, holds the reduced (by `alpha`) evaluations of each polynomial that's
@@ -1,16 +1,27 @@
 use alloc::vec;
 use alloc::vec::Vec;
 use core::borrow::Borrow;
+use core::iter;
 
 use crate::field::extension::Extendable;
 use crate::field::types::Field64;
 use crate::gates::arithmetic_base::ArithmeticGate;
+use crate::gates::base_inverse::BaseInverseGate;
+use crate::gates::cube_add::CubeAddGate;
+use crate::gates::diff_of_squares::DiffOfSquaresGate;
+use crate::gates::dot_product::DotProductGate;
 use crate::gates::exponentiation::ExponentiationGate;
+use crate::gates::inv_sum::InvSumGate;
+use crate::gates::quadratic::QuadraticGate;
+use crate::gates::running_product::RunningProductGate;
+use crate::gates::shared_scalar_mul::SharedScalarMulGate;
+use crate::gates::sum::SumGate;
 use crate::hash::hash_types::RichField;
 use crate::iop::generator::{GeneratedValues, SimpleGenerator};
 use crate::iop::target::{BoolTarget, Target};
 use crate::iop::witness::{PartitionWitness, Witness, WitnessWrite};
 use crate::plonk::circuit_builder::CircuitBuilder;
+use crate::util::log2_ceil;
 
 impl<F: RichField + Extendable<D>, const D: usize> CircuitBuilder<F, D> {
     /// Computes `-x`.
@@ -164,6 +175,12 @@ impl<F: RichField + Extendable<D>, const D: usize> CircuitBuilder<F, D> {
         self.add(x, c)
     }
 
+    /// Computes `x + C` if `cond` is true, and `x` otherwise.
+    pub fn add_const_if(&mut self, cond: BoolTarget, x: Target, c: F) -> Target {
+        let one = self.one();
+        self.arithmetic(c, F::ONE, cond.target, one, x)
+    }
+
     /// Computes `C * x`.
     pub fn mul_const(&mut self, c: F, x: Target) -> Target {
         let c = self.constant(c);
@@ -198,6 +215,194 @@ impl<F: RichField + Extendable<D>, const D: usize> CircuitBuilder<F, D> {
             .fold(self.zero(), |acc, t| self.add(acc, *t.borrow()))
     }
 
+    /// Add `n` `Target`s using `SumGate`s. Each gate absorbs as many terms as it has addend wires;
+    /// larger sums are chained, with each gate taking the previous gate's output as its first
+    /// addend. Unused addend wires are connected to zero, so for a handful of terms `add_many` is
+    /// usually cheaper.
+    pub fn sum(&mut self, terms: &[Target]) -> Target {
+        let gate = SumGate::new_from_config(&self.config);
+        let zero = self.zero();
+
+        let Some((&first, rest)) = terms.split_first() else {
+            return zero;
+        };
+        rest.chunks(gate.num_addends - 1).fold(first, |acc, chunk| {
+            let row = self.add_gate(gate, vec![]);
+            let addends = iter::once(acc)
+                .chain(chunk.iter().copied())
+                .chain(iter::repeat(zero));
+            for (i, term) in addends.take(gate.num_addends).enumerate() {
+                self.connect(term, Target::wire(row, gate.wire_ith_addend(i)));
+            }
+            Target::wire(row, gate.wire_output())
+        })
+    }
+
+    /// Computes the sum of each window of `window` consecutive elements of `xs`, in order. Rather
+    /// than summing every window from scratch, a running sum is maintained: each window's sum is the
+    /// previous one, minus the element leaving the window, plus the element entering it.
+    pub fn windowed_sums(&mut self, xs: &[Target], window: usize) -> Vec<Target> {
+        assert!(window > 0, "Window must be non-empty");
+        assert!(
+            window <= xs.len(),
+            "Window of size {} is larger than the {} inputs",
+            window,
+            xs.len()
+        );
+        let mut sums = Vec::with_capacity(xs.len() - window + 1);
+        let mut sum = self.add_many(&xs[..window]);
+        sums.push(sum);
+        for (&leaving, &entering) in xs.iter().zip(&xs[window..]) {
+            let remaining = self.sub(sum, leaving);
+            sum = self.add(remaining, entering);
+            sums.push(sum);
+        }
+        sums
+    }
+
+    /// Computes `sum_i coeffs[i] * terms[i]`, where the coefficients are targets rather than
+    /// constants, by chaining `mul_add` operations through an accumulator. Returns zero for empty
+    /// inputs.
+    pub fn linear_combination(&mut self, coeffs: &[Target], terms: &[Target]) -> Target {
+        assert_eq!(
+            coeffs.len(),
+            terms.len(),
+            "Mismatched number of coefficients and terms"
+        );
+        let zero = self.zero();
+        coeffs
+            .iter()
+            .zip(terms)
+            .fold(zero, |acc, (&c, &t)| self.mul_add(c, t, acc))
+    }
+
+    /// Computes `matrix * x + bias` for a constant matrix and bias. Each output is accumulated
+    /// from its bias with `mul_const_add`; these share the `(1, 1)` constants of `ArithmeticGate`,
+    /// so they pack several to a row regardless of the matrix entries. Zero entries are skipped.
+    pub fn affine_const(&mut self, matrix: &[Vec<F>], x: &[Target], bias: &[F]) -> Vec<Target> {
+        assert_eq!(
+            matrix.len(),
+            bias.len(),
+            "Mismatched number of matrix rows and bias entries"
+        );
+        matrix
+            .iter()
+            .zip(bias)
+            .map(|(row, &b)| {
+                assert_eq!(
+                    row.len(),
+                    x.len(),
+                    "Mismatched number of matrix columns and inputs"
+                );
+                let b = self.constant(b);
+                row.iter()
+                    .zip(x)
+                    .filter(|(c, _)| c.is_nonzero())
+                    .fold(b, |acc, (&c, &t)| self.mul_const_add(c, t, acc))
+            })
+            .collect()
+    }
+
+    /// Computes the dot product `sum_i a[i] * b[i]` using `DotProductGate`s, each operation of
+    /// which adds two products to the running sum. Returns zero for empty inputs.
+    pub fn dot_product(&mut self, a: &[Target], b: &[Target]) -> Target {
+        assert_eq!(a.len(), b.len(), "Mismatched dot product input lengths");
+
+        let zero = self.zero();
+        let gate = DotProductGate::new_from_config(&self.config);
+        let mut acc = zero;
+        for (a_chunk, b_chunk) in a.chunks(2).zip(b.chunks(2)) {
+            let (row, i) = self.find_slot(gate.clone(), &[], &[]);
+            self.connect(
+                a_chunk[0],
+                Target::wire(row, DotProductGate::wire_ith_a_0(i)),
+            );
+            self.connect(
+                b_chunk[0],
+                Target::wire(row, DotProductGate::wire_ith_b_0(i)),
+            );
+            let (a_1, b_1) = match (a_chunk.get(1), b_chunk.get(1)) {
+                (Some(&a_1), Some(&b_1)) => (a_1, b_1),
+                _ => (zero, zero),
+            };
+            self.connect(a_1, Target::wire(row, DotProductGate::wire_ith_a_1(i)));
+            self.connect(b_1, Target::wire(row, DotProductGate::wire_ith_b_1(i)));
+            self.connect(acc, Target::wire(row, DotProductGate::wire_ith_addend(i)));
+            acc = Target::wire(row, DotProductGate::wire_ith_output(i));
+        }
+        acc
+    }
+
+    /// Computes `sum_i a[i] * b[i] mod modulus`, for entries with at most as many bits as
+    /// `modulus - 1`, which are range-checked. The dot product is range-checked as well before it
+    /// is reduced, so this panics if the vectors are too long, or the modulus too large, for the
+    /// dot product and its reduction to fit in the field.
+    pub fn inner_product_mod(&mut self, a: &[Target], b: &[Target], modulus: u64) -> Target {
+        assert!(modulus > 1, "Cannot reduce modulo {}", modulus);
+        let entry_bits = (u64::BITS - (modulus - 1).leading_zeros()) as usize;
+        let sum_bits = 2 * entry_bits + log2_ceil(a.len().max(1));
+        assert!(
+            sum_bits + entry_bits < F::BITS - 1,
+            "An inner product of {} entries modulo {} may overflow the field",
+            a.len(),
+            modulus
+        );
+        for &x in a.iter().chain(b) {
+            self.range_check(x, entry_bits);
+        }
+
+        let sum = self.dot_product(a, b);
+        self.range_check(sum, sum_bits);
+        self.reduce_mod_const(sum, modulus, sum_bits)
+    }
+
+    /// Computes `scalar * x` for each of `xs`, using `SharedScalarMulGate`s which route `scalar`
+    /// into each row only once.
+    pub fn scale_many_by(&mut self, scalar: Target, xs: &[Target]) -> Vec<Target> {
+        let gate = SharedScalarMulGate::new_from_config(&self.config);
+        let mut products = Vec::with_capacity(xs.len());
+        for chunk in xs.chunks(gate.num_ops) {
+            let row = self.add_gate(gate.clone(), vec![]);
+            self.connect(
+                scalar,
+                Target::wire(row, SharedScalarMulGate::wire_scalar()),
+            );
+            for i in 0..gate.num_ops {
+                // Unused operations multiply zero, so that their generators can run.
+                let x = chunk.get(i).copied().unwrap_or_else(|| self.zero());
+                self.connect(x, Target::wire(row, SharedScalarMulGate::wire_ith_input(i)));
+            }
+            products.extend(
+                (0..chunk.len())
+                    .map(|i| Target::wire(row, SharedScalarMulGate::wire_ith_output(i))),
+            );
+        }
+        products
+    }
+
+    /// Computes the product of `factors`, using `RunningProductGate`s chained across rows.
+    pub fn running_product(&mut self, factors: &[Target]) -> Target {
+        let gate = RunningProductGate::new_from_config(&self.config);
+        let mut product = self.one();
+        for chunk in factors.chunks(gate.num_ops) {
+            let row = self.add_gate(gate.clone(), vec![]);
+            self.connect(
+                product,
+                Target::wire(row, RunningProductGate::wire_initial()),
+            );
+            for i in 0..gate.num_ops {
+                // Unused operations multiply by one, so that their generators can run.
+                let factor = chunk.get(i).copied().unwrap_or_else(|| self.one());
+                self.connect(
+                    factor,
+                    Target::wire(row, RunningProductGate::wire_ith_factor(i)),
+                );
+            }
+            product = Target::wire(row, RunningProductGate::wire_ith_output(chunk.len() - 1));
+        }
+        product
+    }
+
     /// Computes `x - y`.
     pub fn sub(&mut self, x: Target, y: Target) -> Target {
         let one = self.one();
@@ -268,6 +473,22 @@ impl<F: RichField + Extendable<D>, const D: usize> CircuitBuilder<F, D> {
         self.exp_from_bits(base, exponent_bits.iter())
     }
 
+    /// Exponentiate `base` to the power of `exponent`, given by its little-endian bits, using
+    /// square-and-multiply with a `select` per bit. Unlike `exp_from_bits`, this uses only
+    /// arithmetic gates. An empty exponent yields 1.
+    pub fn exp_bits(&mut self, base: Target, exp_bits: &[BoolTarget]) -> Target {
+        let mut result = self.one();
+        let mut power = base;
+        for (i, bit) in exp_bits.iter().enumerate() {
+            let product = self.mul(result, power);
+            result = self.select(*bit, product, result);
+            if i + 1 < exp_bits.len() {
+                power = self.square(power);
+            }
+        }
+        result
+    }
+
     /// Like `exp_from_bits` but with a constant base.
     pub fn exp_from_bits_const_base(
         &mut self,
@@ -314,6 +535,24 @@ impl<F: RichField + Extendable<D>, const D: usize> CircuitBuilder<F, D> {
         self.exp_from_bits(base, exp_bits)
     }
 
+    /// Computes `1 + x + x^2 + ... + x^{n-1}`, using the closed form `(x^n - 1) / (x - 1)`. As
+    /// that is undefined for `x = 1`, the sum `n` is selected instead in that case.
+    pub fn geometric_sum(&mut self, x: Target, n: usize) -> Target {
+        let one = self.one();
+        let x_is_one = self.is_equal(x, one);
+
+        // Divide by one instead of zero when `x = 1`, so witness generation doesn't fail.
+        let x_minus_one = self.sub(x, one);
+        let denominator = self.select(x_is_one, one, x_minus_one);
+        let denominator_inv = self.inverse(denominator);
+        let x_to_n = self.exp_u64(x, n as u64);
+        let closed_form =
+            self.arithmetic(F::ONE, F::NEG_ONE, x_to_n, denominator_inv, denominator_inv);
+
+        let n = self.constant(F::from_canonical_usize(n));
+        self.select(x_is_one, n, closed_form)
+    }
+
     /// Computes `x / y`. Results in an unsatisfiable instance if `y = 0`.
     pub fn div(&mut self, x: Target, y: Target) -> Target {
         let x = self.convert_to_ext(x);
@@ -327,6 +566,79 @@ impl<F: RichField + Extendable<D>, const D: usize> CircuitBuilder<F, D> {
         self.inverse_extension(x_ext).0[0]
     }
 
+    /// Computes `1 / x` like `inverse`, but using a `BaseInverseGate`, which packs several
+    /// inversions per row. Witness generation panics if `x = 0`.
+    pub fn inverse_packed(&mut self, x: Target) -> Target {
+        let gate = BaseInverseGate::new_from_config(&self.config);
+        let (row, i) = self.find_slot(gate, &[], &[]);
+        self.connect(x, Target::wire(row, BaseInverseGate::wire_ith_input(i)));
+
+        Target::wire(row, BaseInverseGate::wire_ith_inverse(i))
+    }
+
+    /// Computes `1 / (a + b)` using an `InvSumGate`, which packs several such operations per row.
+    /// Witness generation panics if `a + b = 0`.
+    pub fn inv_sum(&mut self, a: Target, b: Target) -> Target {
+        let gate = InvSumGate::new_from_config(&self.config);
+        let (row, i) = self.find_slot(gate, &[], &[]);
+        self.connect(a, Target::wire(row, InvSumGate::wire_ith_addend_0(i)));
+        self.connect(b, Target::wire(row, InvSumGate::wire_ith_addend_1(i)));
+
+        Target::wire(row, InvSumGate::wire_ith_output(i))
+    }
+
+    /// Computes `a^2 - b^2` using a `DiffOfSquaresGate`, which packs several such operations per
+    /// row.
+    pub fn diff_of_squares(&mut self, a: Target, b: Target) -> Target {
+        let gate = DiffOfSquaresGate::new_from_config(&self.config);
+        let (row, i) = self.find_slot(gate, &[], &[]);
+        self.connect(a, Target::wire(row, DiffOfSquaresGate::wire_ith_a(i)));
+        self.connect(b, Target::wire(row, DiffOfSquaresGate::wire_ith_b(i)));
+
+        Target::wire(row, DiffOfSquaresGate::wire_ith_output(i))
+    }
+
+    /// Asserts that `a x^2 + b x y + c y^2 + d x + e y + f = 0`, where `coeffs = [a, b, c, d, e, f]`,
+    /// using a `QuadraticGate`. Relations with the same coefficients share a row. The gate stores
+    /// its coefficients as constants, so the config needs at least 6 of them.
+    pub fn assert_quadratic(&mut self, coeffs: [F; 6], x: Target, y: Target) {
+        let gate = QuadraticGate::new_from_config(&self.config);
+        let (row, i) = self.find_slot(gate, &coeffs, &coeffs);
+        self.connect(x, Target::wire(row, QuadraticGate::wire_ith_x(i)));
+        self.connect(y, Target::wire(row, QuadraticGate::wire_ith_y(i)));
+    }
+
+    /// Computes `const_0 * x * y * z + const_1 * w` using a `CubeAddGate`, which packs several
+    /// operations sharing the same constants per row.
+    pub fn cube_add(
+        &mut self,
+        const_0: F,
+        const_1: F,
+        x: Target,
+        y: Target,
+        z: Target,
+        w: Target,
+    ) -> Target {
+        let gate = CubeAddGate::new_from_config(&self.config);
+        let constants = vec![const_0, const_1];
+        let (row, i) = self.find_slot(gate, &constants, &constants);
+        self.connect(
+            x,
+            Target::wire(row, CubeAddGate::wire_ith_multiplicand_0(i)),
+        );
+        self.connect(
+            y,
+            Target::wire(row, CubeAddGate::wire_ith_multiplicand_1(i)),
+        );
+        self.connect(
+            z,
+            Target::wire(row, CubeAddGate::wire_ith_multiplicand_2(i)),
+        );
+        self.connect(w, Target::wire(row, CubeAddGate::wire_ith_addend(i)));
+
+        Target::wire(row, CubeAddGate::wire_ith_output(i))
+    }
+
     pub fn not(&mut self, b: BoolTarget) -> BoolTarget {
         let one = self.one();
         let res = self.sub(one, b.target);
@@ -337,6 +649,29 @@ impl<F: RichField + Extendable<D>, const D: usize> CircuitBuilder<F, D> {
         BoolTarget::new_unsafe(self.mul(b1.target, b2.target))
     }
 
+    /// Computes `!a || b` as `1 - a + a b`.
+    pub fn implies(&mut self, a: BoolTarget, b: BoolTarget) -> BoolTarget {
+        let not_a = self.not(a);
+        BoolTarget::new_unsafe(self.mul_add(a.target, b.target, not_a.target))
+    }
+
+    /// Computes the XOR of `bits`, accumulating `acc + b - 2 acc b` over the bits. The XOR of no
+    /// bits is false.
+    pub fn xor_reduce(&mut self, bits: &[BoolTarget]) -> BoolTarget {
+        let mut acc = self._false();
+        for &bit in bits {
+            let sum = self.add(acc.target, bit.target);
+            acc = BoolTarget::new_unsafe(self.arithmetic(
+                -F::TWO,
+                F::ONE,
+                acc.target,
+                bit.target,
+                sum,
+            ));
+        }
+        acc
+    }
+
     pub fn _if(&mut self, b: BoolTarget, x: Target, y: Target) -> Target {
         let not_b = self.not(b);
         let maybe_x = self.mul(b.target, x);
@@ -362,6 +697,18 @@ impl<F: RichField + Extendable<D>, const D: usize> CircuitBuilder<F, D> {
 
         equal
     }
+
+    /// Returns a square root `y` of `x`, i.e. with `y * y = x`, thereby asserting that `x` is a
+    /// quadratic residue. Witness generation panics if it isn't.
+    pub fn assert_sqrt(&mut self, x: Target) -> Target {
+        let sqrt = self.add_virtual_target();
+        self.add_simple_generator(SqrtGenerator { x, sqrt });
+
+        let square = self.square(sqrt);
+        self.connect(square, x);
+
+        sqrt
+    }
 }
 
 #[derive(Debug, Clone)]
@@ -388,6 +735,27 @@ impl<F: RichField> SimpleGenerator<F> for EqualityGenerator {
     }
 }
 
+#[derive(Debug, Clone)]
+struct SqrtGenerator {
+    x: Target,
+    sqrt: Target,
+}
+
+impl<F: RichField> SimpleGenerator<F> for SqrtGenerator {
+    fn dependencies(&self) -> Vec<Target> {
+        vec![self.x]
+    }
+
+    fn run_once(&self, witness: &PartitionWitness<F>, out_buffer: &mut GeneratedValues<F>) {
+        let x = witness.get_target(self.x);
+        let sqrt = x
+            .sqrt()
+            .unwrap_or_else(|| panic!("{} is not a quadratic residue", x));
+
+        out_buffer.set_target(self.sqrt, sqrt);
+    }
+}
+
 /// Represents a base arithmetic operation in the circuit. Used to memoize results.
 #[derive(Copy, Clone, Eq, PartialEq, Hash)]
 pub(crate) struct BaseArithmeticOperation<F: Field64> {
@@ -397,3 +765,782 @@ pub(crate) struct BaseArithmeticOperation<F: Field64> {
     multiplicand_1: Target,
     addend: Target,
 }
+
+#[cfg(test)]
+mod tests {
+    use alloc::vec::Vec;
+
+    use anyhow::Result;
+    use rand::rngs::OsRng;
+    use rand::Rng;
+
+    use crate::field::types::{Field, Field64, PrimeField, Sample};
+    use crate::gates::base_inverse::BaseInverseGate;
+    use crate::gates::cube_add::CubeAddGate;
+    use crate::gates::dot_product::DotProductGate;
+    use crate::gates::quadratic::QuadraticGate;
+    use crate::gates::running_product::RunningProductGate;
+    use crate::gates::sum::SumGate;
+    use crate::iop::witness::{PartialWitness, WitnessWrite};
+    use crate::plonk::circuit_builder::CircuitBuilder;
+    use crate::plonk::circuit_data::CircuitConfig;
+    use crate::plonk::config::{GenericConfig, PoseidonGoldilocksConfig};
+    use crate::plonk::verifier::verify;
+    use crate::util::ceil_div_usize;
+
+    #[test]
+    fn test_inverse_packed_many() -> Result<()> {
+        const D: usize = 2;
+        type C = PoseidonGoldilocksConfig;
+        type F = <C as GenericConfig<D>>::F;
+        let config = CircuitConfig::standard_recursion_config();
+        let mut pw = PartialWitness::<F>::new();
+        let mut builder = CircuitBuilder::<F, D>::new(config.clone());
+
+        let values = F::rand_vec(100);
+        let targets = builder.add_virtual_targets(values.len());
+        for (&t, &v) in targets.iter().zip(&values) {
+            pw.set_target(t, v);
+        }
+
+        let rows_before = builder.num_gates();
+        let inverses = targets
+            .iter()
+            .map(|&t| builder.inverse_packed(t))
+            .collect::<Vec<_>>();
+        let ops_per_row = BaseInverseGate::num_ops(&config);
+        assert_eq!(
+            builder.num_gates() - rows_before,
+            ceil_div_usize(values.len(), ops_per_row)
+        );
+
+        for (&inv, &v) in inverses.iter().zip(&values) {
+            let expected = builder.constant(v.inverse());
+            builder.connect(inv, expected);
+        }
+
+        let data = builder.build::<C>();
+        let proof = data.prove(pw)?;
+        verify(proof, &data.verifier_only, &data.common)
+    }
+
+    #[test]
+    #[should_panic(expected = "cannot invert zero")]
+    fn test_inverse_packed_zero() {
+        const D: usize = 2;
+        type C = PoseidonGoldilocksConfig;
+        type F = <C as GenericConfig<D>>::F;
+        let config = CircuitConfig::standard_recursion_config();
+        let mut pw = PartialWitness::<F>::new();
+        let mut builder = CircuitBuilder::<F, D>::new(config);
+
+        let x = builder.add_virtual_target();
+        pw.set_target(x, F::ZERO);
+        let inv = builder.inverse_packed(x);
+        builder.register_public_input(inv);
+
+        let data = builder.build::<C>();
+        let _ = data.prove(pw);
+    }
+
+    #[test]
+    fn test_geometric_sum() -> Result<()> {
+        const D: usize = 2;
+        type C = PoseidonGoldilocksConfig;
+        type F = <C as GenericConfig<D>>::F;
+        let config = CircuitConfig::standard_recursion_config();
+        let mut pw = PartialWitness::<F>::new();
+        let mut builder = CircuitBuilder::<F, D>::new(config);
+
+        let xs = [F::ZERO, F::ONE, F::TWO, F::NEG_ONE, F::rand()];
+        for x_value in xs {
+            let x = builder.add_virtual_target();
+            pw.set_target(x, x_value);
+            for n in [0, 1, 2, 7, 64] {
+                let expected = (0..n).map(|i| x_value.exp_u64(i)).sum::<F>();
+                let sum = builder.geometric_sum(x, n as usize);
+                let expected = builder.constant(expected);
+                builder.connect(sum, expected);
+            }
+        }
+
+        let data = builder.build::<C>();
+        let proof = data.prove(pw)?;
+        verify(proof, &data.verifier_only, &data.common)
+    }
+
+    #[test]
+    fn test_add_const_if() -> Result<()> {
+        const D: usize = 2;
+        type C = PoseidonGoldilocksConfig;
+        type F = <C as GenericConfig<D>>::F;
+        let config = CircuitConfig::standard_recursion_config();
+        let mut pw = PartialWitness::<F>::new();
+        let mut builder = CircuitBuilder::<F, D>::new(config);
+
+        let x_value = F::rand();
+        let c = F::rand();
+        let x = builder.add_virtual_target();
+        pw.set_target(x, x_value);
+        let true_target = builder._true();
+        let false_target = builder._false();
+        let incremented = builder.add_const_if(true_target, x, c);
+        let unchanged = builder.add_const_if(false_target, x, c);
+        let expected = builder.constant(x_value + c);
+        builder.connect(incremented, expected);
+        builder.connect(unchanged, x);
+
+        // Chain conditional increments of a counter, as for counting set flags.
+        let flags = [true, false, true, true, false];
+        let mut counter = builder.zero();
+        for flag in flags {
+            let cond = builder.add_virtual_bool_target_safe();
+            pw.set_bool_target(cond, flag);
+            counter = builder.add_const_if(cond, counter, F::ONE);
+        }
+        let expected = builder.constant(F::from_canonical_usize(3));
+        builder.connect(counter, expected);
+
+        let data = builder.build::<C>();
+        let proof = data.prove(pw)?;
+        verify(proof, &data.verifier_only, &data.common)
+    }
+
+    #[test]
+    fn test_inv_sum() -> Result<()> {
+        const D: usize = 2;
+        type C = PoseidonGoldilocksConfig;
+        type F = <C as GenericConfig<D>>::F;
+        let config = CircuitConfig::standard_recursion_config();
+        let mut pw = PartialWitness::<F>::new();
+        let mut builder = CircuitBuilder::<F, D>::new(config);
+
+        let mut pairs = vec![
+            (F::ONE, F::ZERO),
+            (F::ZERO, F::NEG_ONE),
+            (F::TWO, F::NEG_ONE),
+            (F::from_canonical_u64(5), F::from_canonical_u64(7)),
+        ];
+        pairs.extend((0..30).map(|_| (F::rand(), F::rand())));
+        for (a_value, b_value) in pairs {
+            let a = builder.add_virtual_target();
+            let b = builder.add_virtual_target();
+            pw.set_target(a, a_value);
+            pw.set_target(b, b_value);
+            let inv_sum = builder.inv_sum(a, b);
+            let expected = builder.constant((a_value + b_value).inverse());
+            builder.connect(inv_sum, expected);
+        }
+
+        let data = builder.build::<C>();
+        let proof = data.prove(pw)?;
+        verify(proof, &data.verifier_only, &data.common)
+    }
+
+    #[test]
+    #[should_panic(expected = "cannot invert a zero sum")]
+    fn test_inv_sum_zero() {
+        const D: usize = 2;
+        type C = PoseidonGoldilocksConfig;
+        type F = <C as GenericConfig<D>>::F;
+        let config = CircuitConfig::standard_recursion_config();
+        let mut pw = PartialWitness::<F>::new();
+        let mut builder = CircuitBuilder::<F, D>::new(config);
+
+        let a = builder.add_virtual_target();
+        let b = builder.add_virtual_target();
+        pw.set_target(a, F::from_canonical_u64(3));
+        pw.set_target(b, -F::from_canonical_u64(3));
+        let inv_sum = builder.inv_sum(a, b);
+        builder.register_public_input(inv_sum);
+
+        let data = builder.build::<C>();
+        let _ = data.prove(pw);
+    }
+
+    #[test]
+    fn test_diff_of_squares() -> Result<()> {
+        const D: usize = 2;
+        type C = PoseidonGoldilocksConfig;
+        type F = <C as GenericConfig<D>>::F;
+        let config = CircuitConfig::standard_recursion_config();
+        let mut pw = PartialWitness::<F>::new();
+        let mut builder = CircuitBuilder::<F, D>::new(config);
+
+        let mut pairs = vec![
+            (F::ZERO, F::ZERO),
+            (F::ONE, F::ZERO),
+            (F::ZERO, F::ONE),
+            (F::from_canonical_u64(5), F::from_canonical_u64(3)),
+            (F::NEG_ONE, F::ONE),
+        ];
+        pairs.extend((0..30).map(|_| (F::rand(), F::rand())));
+        for (a_value, b_value) in pairs {
+            let a = builder.add_virtual_target();
+            let b = builder.add_virtual_target();
+            pw.set_target(a, a_value);
+            pw.set_target(b, b_value);
+            let diff = builder.diff_of_squares(a, b);
+            let expected = builder.constant(a_value * a_value - b_value * b_value);
+            builder.connect(diff, expected);
+        }
+
+        let data = builder.build::<C>();
+        let proof = data.prove(pw)?;
+        verify(proof, &data.verifier_only, &data.common)
+    }
+
+    #[test]
+    fn test_cube_add() -> Result<()> {
+        const D: usize = 2;
+        type C = PoseidonGoldilocksConfig;
+        type F = <C as GenericConfig<D>>::F;
+        let config = CircuitConfig::standard_recursion_config();
+        let mut pw = PartialWitness::<F>::new();
+        let mut builder = CircuitBuilder::<F, D>::new(config.clone());
+
+        let num_ops = CubeAddGate::new_from_config(&config).num_ops;
+        let consts = [(F::ONE, F::ONE), (F::rand(), F::rand())];
+        let rows_before = builder.num_gates();
+        for i in 0..2 * num_ops {
+            let (const_0, const_1) = consts[i % 2];
+            let values = F::rand_vec(4);
+            let targets = builder.add_virtual_targets(4);
+            for (&t, &v) in targets.iter().zip(&values) {
+                pw.set_target(t, v);
+            }
+            let result = builder.cube_add(
+                const_0, const_1, targets[0], targets[1], targets[2], targets[3],
+            );
+            let expected =
+                builder.constant(const_0 * values[0] * values[1] * values[2] + const_1 * values[3]);
+            builder.connect(result, expected);
+        }
+        // Operations with the same constants share rows.
+        assert_eq!(builder.num_gates() - rows_before, 2);
+
+        let data = builder.build::<C>();
+        let proof = data.prove(pw)?;
+        verify(proof, &data.verifier_only, &data.common)
+    }
+
+    #[test]
+    fn test_assert_quadratic() -> Result<()> {
+        const D: usize = 2;
+        type C = PoseidonGoldilocksConfig;
+        type F = <C as GenericConfig<D>>::F;
+        let config = CircuitConfig {
+            num_constants: 6,
+            ..CircuitConfig::standard_recursion_config()
+        };
+        let mut pw = PartialWitness::<F>::new();
+        let mut builder = CircuitBuilder::<F, D>::new(config.clone());
+
+        // `(x - y - 1)(x + 2y + 3) = x^2 + xy - 2y^2 + 2x - 5y - 3` vanishes whenever `y = x - 1`.
+        let coeffs = [1, 1, -2, 2, -5, -3].map(F::from_noncanonical_i64);
+        // Enough points to fill one gate and partially use a second.
+        let num_points = QuadraticGate::new_from_config(&config).num_ops + 3;
+        for _ in 0..num_points {
+            let x_value = F::rand();
+            let x = builder.add_virtual_target();
+            let y = builder.add_virtual_target();
+            pw.set_target(x, x_value);
+            pw.set_target(y, x_value - F::ONE);
+            builder.assert_quadratic(coeffs, x, y);
+        }
+
+        let data = builder.build::<C>();
+        let proof = data.prove(pw)?;
+        verify(proof, &data.verifier_only, &data.common)
+    }
+
+    #[test]
+    fn test_sum() -> Result<()> {
+        const D: usize = 2;
+        type C = PoseidonGoldilocksConfig;
+        type F = <C as GenericConfig<D>>::F;
+        let config = CircuitConfig::standard_recursion_config();
+        let mut pw = PartialWitness::<F>::new();
+        let mut builder = CircuitBuilder::<F, D>::new(config.clone());
+
+        // Enough terms to chain several gates.
+        let values = F::rand_vec(200);
+        let expected = values.iter().fold(F::ZERO, |acc, &x| acc + x);
+        let targets = builder.add_virtual_targets(values.len());
+        for (&t, &v) in targets.iter().zip(&values) {
+            pw.set_target(t, v);
+        }
+
+        let sum = builder.sum(&targets);
+        let sum_rows = builder.num_gates();
+        let num_addends = SumGate::new_from_config(&config).num_addends;
+        assert_eq!(sum_rows, ceil_div_usize(values.len() - 1, num_addends - 1));
+        let expected_t = builder.constant(expected);
+        builder.connect(sum, expected_t);
+
+        let mut naive_builder = CircuitBuilder::<F, D>::new(config);
+        let naive_targets = naive_builder.add_virtual_targets(values.len());
+        naive_builder.add_many(&naive_targets);
+        assert!(sum_rows < naive_builder.num_gates());
+
+        let data = builder.build::<C>();
+        let proof = data.prove(pw)?;
+
+        verify(proof, &data.verifier_only, &data.common)
+    }
+
+    #[test]
+    fn test_windowed_sums() -> Result<()> {
+        const D: usize = 2;
+        type C = PoseidonGoldilocksConfig;
+        type F = <C as GenericConfig<D>>::F;
+        let config = CircuitConfig::standard_recursion_config();
+        let mut pw = PartialWitness::<F>::new();
+        let mut builder = CircuitBuilder::<F, D>::new(config);
+
+        let window = 3;
+        let values = F::rand_vec(10);
+        let targets = builder.add_virtual_targets(values.len());
+        for (&t, &v) in targets.iter().zip(&values) {
+            pw.set_target(t, v);
+        }
+
+        let sums = builder.windowed_sums(&targets, window);
+        let expected = values
+            .windows(window)
+            .map(|w| w.iter().fold(F::ZERO, |acc, &x| acc + x))
+            .collect::<Vec<_>>();
+        assert_eq!(sums.len(), expected.len());
+        for (&sum, &expected) in sums.iter().zip(&expected) {
+            let expected_t = builder.constant(expected);
+            builder.connect(sum, expected_t);
+        }
+
+        let data = builder.build::<C>();
+        let proof = data.prove(pw)?;
+
+        verify(proof, &data.verifier_only, &data.common)
+    }
+
+    #[test]
+    #[should_panic(expected = "is larger than the")]
+    fn test_windowed_sums_window_too_large() {
+        const D: usize = 2;
+        type C = PoseidonGoldilocksConfig;
+        type F = <C as GenericConfig<D>>::F;
+        let config = CircuitConfig::standard_recursion_config();
+        let mut builder = CircuitBuilder::<F, D>::new(config);
+
+        let targets = builder.add_virtual_targets(3);
+        builder.windowed_sums(&targets, 4);
+    }
+
+    #[test]
+    fn test_linear_combination() -> Result<()> {
+        const D: usize = 2;
+        type C = PoseidonGoldilocksConfig;
+        type F = <C as GenericConfig<D>>::F;
+        let config = CircuitConfig::standard_recursion_config();
+        let mut pw = PartialWitness::<F>::new();
+        let mut builder = CircuitBuilder::<F, D>::new(config.clone());
+
+        let coeffs = F::rand_vec(20);
+        let terms = F::rand_vec(20);
+        let expected = coeffs
+            .iter()
+            .zip(&terms)
+            .fold(F::ZERO, |acc, (&c, &t)| acc + c * t);
+        let coeff_targets = builder.add_virtual_targets(coeffs.len());
+        let term_targets = builder.add_virtual_targets(terms.len());
+        for (&t, &v) in coeff_targets.iter().zip(&coeffs) {
+            pw.set_target(t, v);
+        }
+        for (&t, &v) in term_targets.iter().zip(&terms) {
+            pw.set_target(t, v);
+        }
+
+        let result = builder.linear_combination(&coeff_targets, &term_targets);
+        let expected_t = builder.constant(expected);
+        builder.connect(result, expected_t);
+
+        let empty = builder.linear_combination(&[], &[]);
+        let zero = builder.zero();
+        assert_eq!(empty, zero);
+
+        let data = builder.build::<C>();
+        assert!(data.common.constraint_degree() <= config.max_quotient_degree_factor);
+        let proof = data.prove(pw)?;
+
+        verify(proof, &data.verifier_only, &data.common)
+    }
+
+    #[test]
+    #[should_panic(expected = "Mismatched number of coefficients and terms")]
+    fn test_linear_combination_length_mismatch() {
+        const D: usize = 2;
+        type C = PoseidonGoldilocksConfig;
+        type F = <C as GenericConfig<D>>::F;
+        let config = CircuitConfig::standard_recursion_config();
+        let mut builder = CircuitBuilder::<F, D>::new(config);
+
+        let coeffs = builder.add_virtual_targets(3);
+        let terms = builder.add_virtual_targets(2);
+        builder.linear_combination(&coeffs, &terms);
+    }
+
+    #[test]
+    fn test_affine_const() -> Result<()> {
+        const D: usize = 2;
+        type C = PoseidonGoldilocksConfig;
+        type F = <C as GenericConfig<D>>::F;
+        let config = CircuitConfig::standard_recursion_config();
+        let mut pw = PartialWitness::<F>::new();
+        let mut builder = CircuitBuilder::<F, D>::new(config);
+
+        let mut matrix = (0..3).map(|_| F::rand_vec(3)).collect::<Vec<_>>();
+        matrix[1][2] = F::ZERO;
+        let bias = F::rand_vec(3);
+        let x_values = F::rand_vec(3);
+        let x = builder.add_virtual_targets(3);
+        for (&t, &v) in x.iter().zip(&x_values) {
+            pw.set_target(t, v);
+        }
+
+        let outputs = builder.affine_const(&matrix, &x, &bias);
+        for ((row, &b), output) in matrix.iter().zip(&bias).zip(outputs) {
+            let expected = row
+                .iter()
+                .zip(&x_values)
+                .fold(b, |acc, (&c, &v)| acc + c * v);
+            let expected = builder.constant(expected);
+            builder.connect(output, expected);
+        }
+
+        let data = builder.build::<C>();
+        let proof = data.prove(pw)?;
+        verify(proof, &data.verifier_only, &data.common)
+    }
+
+    #[test]
+    #[should_panic(expected = "Mismatched number of matrix columns and inputs")]
+    fn test_affine_const_dimension_mismatch() {
+        const D: usize = 2;
+        type C = PoseidonGoldilocksConfig;
+        type F = <C as GenericConfig<D>>::F;
+        let config = CircuitConfig::standard_recursion_config();
+        let mut builder = CircuitBuilder::<F, D>::new(config);
+
+        let matrix = vec![F::rand_vec(3), F::rand_vec(2)];
+        let x = builder.add_virtual_targets(3);
+        builder.affine_const(&matrix, &x, &F::rand_vec(2));
+    }
+
+    #[test]
+    fn test_dot_product() -> Result<()> {
+        const D: usize = 2;
+        type C = PoseidonGoldilocksConfig;
+        type F = <C as GenericConfig<D>>::F;
+        let config = CircuitConfig::standard_recursion_config();
+        let mut pw = PartialWitness::<F>::new();
+        let mut builder = CircuitBuilder::<F, D>::new(config);
+
+        let len = 41;
+        let a = F::rand_vec(len);
+        let b = F::rand_vec(len);
+        let expected = a.iter().zip(&b).map(|(&x, &y)| x * y).sum::<F>();
+        let a_targets = builder.add_virtual_targets(len);
+        let b_targets = builder.add_virtual_targets(len);
+        for (&t, &v) in a_targets.iter().zip(&a).chain(b_targets.iter().zip(&b)) {
+            pw.set_target(t, v);
+        }
+
+        let zero = builder.zero();
+        let rows_before = builder.num_gates();
+        let result = builder.dot_product(&a_targets, &b_targets);
+        let num_ops = DotProductGate::new_from_config(&builder.config).num_ops;
+        assert_eq!(
+            builder.num_gates() - rows_before,
+            ceil_div_usize(ceil_div_usize(len, 2), num_ops)
+        );
+        assert_eq!(builder.dot_product(&[], &[]), zero);
+
+        let expected_t = builder.constant(expected);
+        builder.connect(result, expected_t);
+
+        let data = builder.build::<C>();
+        let proof = data.prove(pw)?;
+        verify(proof, &data.verifier_only, &data.common)
+    }
+
+    #[test]
+    #[should_panic(expected = "Mismatched")]
+    fn test_dot_product_length_mismatch() {
+        const D: usize = 2;
+        type C = PoseidonGoldilocksConfig;
+        type F = <C as GenericConfig<D>>::F;
+        let config = CircuitConfig::standard_recursion_config();
+        let mut builder = CircuitBuilder::<F, D>::new(config);
+
+        let a = builder.add_virtual_targets(4);
+        let b = builder.add_virtual_targets(5);
+        builder.dot_product(&a, &b);
+    }
+
+    #[test]
+    fn test_inner_product_mod() -> Result<()> {
+        const D: usize = 2;
+        type C = PoseidonGoldilocksConfig;
+        type F = <C as GenericConfig<D>>::F;
+        let config = CircuitConfig::standard_recursion_config();
+        let mut pw = PartialWitness::<F>::new();
+        let mut builder = CircuitBuilder::<F, D>::new(config);
+
+        let mut rng = OsRng;
+        for (modulus, len) in [(2, 8), (3329, 1), (3329, 16), (12289, 64), (1 << 16, 5)] {
+            let a = (0..len)
+                .map(|_| rng.gen_range(0..modulus))
+                .collect::<Vec<u64>>();
+            let b = (0..len)
+                .map(|_| rng.gen_range(0..modulus))
+                .collect::<Vec<u64>>();
+            let expected = a.iter().zip(&b).map(|(&x, &y)| x * y).sum::<u64>() % modulus;
+
+            let a_targets = builder.add_virtual_targets(len);
+            let b_targets = builder.add_virtual_targets(len);
+            for (&t, &v) in a_targets.iter().zip(&a).chain(b_targets.iter().zip(&b)) {
+                pw.set_target(t, F::from_canonical_u64(v));
+            }
+            let result = builder.inner_product_mod(&a_targets, &b_targets, modulus);
+            let expected = builder.constant(F::from_canonical_u64(expected));
+            builder.connect(result, expected);
+        }
+
+        let data = builder.build::<C>();
+        let proof = data.prove(pw)?;
+        verify(proof, &data.verifier_only, &data.common)
+    }
+
+    #[test]
+    #[should_panic(
+        expected = "An inner product of 4 entries modulo 4294967296 may overflow the field"
+    )]
+    fn test_inner_product_mod_too_wide() {
+        const D: usize = 2;
+        type C = PoseidonGoldilocksConfig;
+        type F = <C as GenericConfig<D>>::F;
+        let config = CircuitConfig::standard_recursion_config();
+        let mut builder = CircuitBuilder::<F, D>::new(config);
+
+        let a = builder.add_virtual_targets(4);
+        let b = builder.add_virtual_targets(4);
+        builder.inner_product_mod(&a, &b, 1 << 32);
+    }
+
+    #[test]
+    fn test_scale_many_by() -> Result<()> {
+        const D: usize = 2;
+        type C = PoseidonGoldilocksConfig;
+        type F = <C as GenericConfig<D>>::F;
+        let config = CircuitConfig::standard_recursion_config();
+        let mut pw = PartialWitness::<F>::new();
+        let mut builder = CircuitBuilder::<F, D>::new(config);
+
+        let scalar = F::rand();
+        let xs = F::rand_vec(10);
+        let scalar_t = builder.add_virtual_target();
+        let x_targets = builder.add_virtual_targets(xs.len());
+        pw.set_target(scalar_t, scalar);
+        for (&t, &x) in x_targets.iter().zip(&xs) {
+            pw.set_target(t, x);
+        }
+
+        let rows_before = builder.num_gates();
+        let products = builder.scale_many_by(scalar_t, &x_targets);
+        // All 10 products fit in a single gate.
+        assert_eq!(builder.num_gates() - rows_before, 1);
+        assert_eq!(products.len(), xs.len());
+        for (&product, &x) in products.iter().zip(&xs) {
+            let expected = builder.constant(scalar * x);
+            builder.connect(product, expected);
+        }
+
+        let data = builder.build::<C>();
+        let proof = data.prove(pw)?;
+        verify(proof, &data.verifier_only, &data.common)
+    }
+
+    #[test]
+    fn test_running_product() -> Result<()> {
+        const D: usize = 2;
+        type C = PoseidonGoldilocksConfig;
+        type F = <C as GenericConfig<D>>::F;
+        let config = CircuitConfig::standard_recursion_config();
+        let mut pw = PartialWitness::<F>::new();
+        let mut builder = CircuitBuilder::<F, D>::new(config.clone());
+
+        // 20 factors fit in one gate, while 100 need products chained across rows.
+        for num_factors in [0, 20, 100] {
+            let factors = F::rand_vec(num_factors);
+            let factor_targets = builder.add_virtual_targets(num_factors);
+            for (&t, &f) in factor_targets.iter().zip(&factors) {
+                pw.set_target(t, f);
+            }
+
+            let rows_before = builder.num_gates();
+            let product = builder.running_product(&factor_targets);
+            assert_eq!(
+                builder.num_gates() - rows_before,
+                ceil_div_usize(num_factors, RunningProductGate::num_ops(&config))
+            );
+            let expected = builder.constant(factors.iter().fold(F::ONE, |acc, &f| acc * f));
+            builder.connect(product, expected);
+        }
+
+        let data = builder.build::<C>();
+        let proof = data.prove(pw)?;
+        verify(proof, &data.verifier_only, &data.common)
+    }
+
+    #[test]
+    fn test_assert_sqrt() -> Result<()> {
+        const D: usize = 2;
+        type C = PoseidonGoldilocksConfig;
+        type F = <C as GenericConfig<D>>::F;
+        let config = CircuitConfig::standard_recursion_config();
+        let mut pw = PartialWitness::<F>::new();
+        let mut builder = CircuitBuilder::<F, D>::new(config);
+
+        let (a, b) = (F::rand(), F::rand());
+        for x in [F::ZERO, F::ONE, a * a, b * b] {
+            let x_t = builder.add_virtual_target();
+            pw.set_target(x_t, x);
+            let sqrt = builder.assert_sqrt(x_t);
+            if x == F::ZERO {
+                let zero = builder.zero();
+                builder.connect(sqrt, zero);
+            }
+        }
+
+        let data = builder.build::<C>();
+        let proof = data.prove(pw)?;
+        verify(proof, &data.verifier_only, &data.common)
+    }
+
+    #[test]
+    #[should_panic(expected = "is not a quadratic residue")]
+    fn test_assert_sqrt_non_residue() {
+        const D: usize = 2;
+        type C = PoseidonGoldilocksConfig;
+        type F = <C as GenericConfig<D>>::F;
+        let config = CircuitConfig::standard_recursion_config();
+        let mut pw = PartialWitness::<F>::new();
+        let mut builder = CircuitBuilder::<F, D>::new(config);
+
+        // A generator of the multiplicative group is never a square.
+        let x = F::MULTIPLICATIVE_GROUP_GENERATOR;
+        assert!(!x.is_quadratic_residue());
+        let x_t = builder.add_virtual_target();
+        pw.set_target(x_t, x);
+        builder.assert_sqrt(x_t);
+
+        let data = builder.build::<C>();
+        let _ = data.prove(pw);
+    }
+
+    #[test]
+    fn test_exp_bits() -> Result<()> {
+        const D: usize = 2;
+        type C = PoseidonGoldilocksConfig;
+        type F = <C as GenericConfig<D>>::F;
+        let config = CircuitConfig::standard_recursion_config();
+        let mut pw = PartialWitness::<F>::new();
+        let mut builder = CircuitBuilder::<F, D>::new(config);
+
+        let base = F::rand();
+        let base_t = builder.add_virtual_target();
+        pw.set_target(base_t, base);
+
+        // Exponents paired with their bit lengths; extra high-order bits are zero.
+        for (exponent, num_bits) in [(0u64, 0), (0, 4), (1, 1), (5, 3), (11, 16), (0xdead, 20)] {
+            let exp_bits = (0..num_bits)
+                .map(|i| {
+                    let bit = builder.add_virtual_bool_target_safe();
+                    pw.set_bool_target(bit, (exponent >> i) & 1 == 1);
+                    bit
+                })
+                .collect::<Vec<_>>();
+            let result = builder.exp_bits(base_t, &exp_bits);
+            let expected = builder.constant(base.exp_u64(exponent));
+            builder.connect(result, expected);
+        }
+
+        let data = builder.build::<C>();
+        let proof = data.prove(pw)?;
+
+        verify(proof, &data.verifier_only, &data.common)
+    }
+
+    #[test]
+    fn test_implies() -> Result<()> {
+        const D: usize = 2;
+        type C = PoseidonGoldilocksConfig;
+        type F = <C as GenericConfig<D>>::F;
+        let config = CircuitConfig::standard_recursion_config();
+        let mut pw = PartialWitness::<F>::new();
+        let mut builder = CircuitBuilder::<F, D>::new(config);
+
+        for (a_value, b_value) in [(false, false), (false, true), (true, false), (true, true)] {
+            let a = builder.add_virtual_bool_target_safe();
+            let b = builder.add_virtual_bool_target_safe();
+            pw.set_bool_target(a, a_value);
+            pw.set_bool_target(b, b_value);
+            let result = builder.implies(a, b);
+            builder.assert_bool(result);
+            let expected = builder.constant_bool(!a_value || b_value);
+            builder.connect(result.target, expected.target);
+        }
+
+        let data = builder.build::<C>();
+        let proof = data.prove(pw)?;
+        verify(proof, &data.verifier_only, &data.common)
+    }
+
+    #[test]
+    fn test_xor_reduce() -> Result<()> {
+        const D: usize = 2;
+        type C = PoseidonGoldilocksConfig;
+        type F = <C as GenericConfig<D>>::F;
+        let config = CircuitConfig::standard_recursion_config();
+        let mut pw = PartialWitness::<F>::new();
+        let mut builder = CircuitBuilder::<F, D>::new(config);
+
+        let random_bits = (0..50).map(|_| OsRng.gen()).collect::<Vec<bool>>();
+        let cases = [
+            vec![],
+            vec![false],
+            vec![true],
+            vec![true, true],
+            vec![true, false, true, true],
+            random_bits,
+        ];
+        for bit_values in cases {
+            let bits = bit_values
+                .iter()
+                .map(|&value| {
+                    let bit = builder.add_virtual_bool_target_safe();
+                    pw.set_bool_target(bit, value);
+                    bit
+                })
+                .collect::<Vec<_>>();
+            let parity = builder.xor_reduce(&bits);
+            builder.assert_bool(parity);
+            let expected =
+                builder.constant_bool(bit_values.iter().filter(|&&b| b).count() % 2 == 1);
+            builder.connect(parity.target, expected.target);
+        }
+
+        let data = builder.build::<C>();
+        let proof = data.prove(pw)?;
+        verify(proof, &data.verifier_only, &data.common)
+    }
+}
@@ -9,10 +9,12 @@ use crate::gates::multiplication_extension::MulExtensionGate;
 use crate::hash::hash_types::RichField;
 use crate::iop::ext_target::{ExtensionAlgebraTarget, ExtensionTarget};
 use crate::iop::generator::{GeneratedValues, SimpleGenerator};
-use crate::iop::target::Target;
+use crate::iop::target::{BoolTarget, Target};
 use crate::iop::witness::{PartitionWitness, Witness, WitnessWrite};
 use crate::plonk::circuit_builder::CircuitBuilder;
 use crate::util::bits_u64;
+use crate::util::generator_serialization::GeneratorId;
+use crate::util::serialization::{IoResult, Read, Write};
 
 impl<F: RichField + Extendable<D>, const D: usize> CircuitBuilder<F, D> {
     pub fn arithmetic_extension(
@@ -384,6 +386,17 @@ impl<F: RichField + Extendable<D>, const D: usize> CircuitBuilder<F, D> {
         self.arithmetic_extension(F::ONE, F::NEG_ONE, a_ext, b, c)
     }
 
+    /// Returns `-x` if `cond` is true, and `x` otherwise, computed as `(1 - 2 * cond) * x` with a
+    /// single arithmetic operation.
+    pub fn conditional_negate_ext(
+        &mut self,
+        cond: BoolTarget,
+        x: ExtensionTarget<D>,
+    ) -> ExtensionTarget<D> {
+        let cond_ext = self.convert_to_ext(cond.target);
+        self.arithmetic_extension(-F::TWO, F::ONE, cond_ext, x, x)
+    }
+
     /// Returns `a * b`, where `b` is in the extension field and `a` is in the base field.
     pub fn scalar_mul_ext(&mut self, a: Target, b: ExtensionTarget<D>) -> ExtensionTarget<D> {
         let a_ext = self.convert_to_ext(a);
@@ -491,10 +504,23 @@ impl<F: RichField + Extendable<D>, const D: usize> CircuitBuilder<F, D> {
         let one = self.one_extension();
         self.div_extension(one, x)
     }
+
+    /// Asserts that `a != b`, by witnessing an inverse of `a - b`. Witness generation panics if
+    /// `a = b`.
+    pub fn assert_not_equal_ext(&mut self, a: ExtensionTarget<D>, b: ExtensionTarget<D>) {
+        let diff_inv = self.add_virtual_extension_target();
+        self.add_simple_generator(DifferenceInverseGeneratorExtension { a, b, diff_inv });
+
+        // Enforce that `a - b` times its purported inverse equals 1.
+        let one = self.one_extension();
+        let diff = self.sub_extension(a, b);
+        let product = self.mul_extension(diff, diff_inv);
+        self.connect_extension(product, one);
+    }
 }
 
 #[derive(Debug, Clone)]
-struct QuotientGeneratorExtension<const D: usize> {
+pub(crate) struct QuotientGeneratorExtension<const D: usize> {
     numerator: ExtensionTarget<D>,
     denominator: ExtensionTarget<D>,
     quotient: ExtensionTarget<D>,
@@ -515,6 +541,55 @@ impl<F: RichField + Extendable<D>, const D: usize> SimpleGenerator<F>
         let quotient = num / dem;
         out_buffer.set_extension_target(self.quotient, quotient)
     }
+
+    fn id(&self) -> Option<GeneratorId> {
+        Some(GeneratorId::QuotientExtension)
+    }
+
+    fn serialize(&self, dst: &mut Vec<u8>) -> IoResult<()> {
+        dst.write_target_ext(self.numerator)?;
+        dst.write_target_ext(self.denominator)?;
+        dst.write_target_ext(self.quotient)
+    }
+}
+
+impl<const D: usize> QuotientGeneratorExtension<D> {
+    pub(crate) fn deserialize<R: Read + ?Sized>(src: &mut R) -> IoResult<Self> {
+        let numerator = src.read_target_ext()?;
+        let denominator = src.read_target_ext()?;
+        let quotient = src.read_target_ext()?;
+        Ok(Self {
+            numerator,
+            denominator,
+            quotient,
+        })
+    }
+}
+
+#[derive(Debug, Clone)]
+struct DifferenceInverseGeneratorExtension<const D: usize> {
+    a: ExtensionTarget<D>,
+    b: ExtensionTarget<D>,
+    diff_inv: ExtensionTarget<D>,
+}
+
+impl<F: RichField + Extendable<D>, const D: usize> SimpleGenerator<F>
+    for DifferenceInverseGeneratorExtension<D>
+{
+    fn dependencies(&self) -> Vec<Target> {
+        let mut deps = self.a.to_target_array().to_vec();
+        deps.extend(self.b.to_target_array());
+        deps
+    }
+
+    fn run_once(&self, witness: &PartitionWitness<F>, out_buffer: &mut GeneratedValues<F>) {
+        let a = witness.get_extension_target(self.a);
+        let b = witness.get_extension_target(self.b);
+        let diff_inv = (a - b)
+            .try_inverse()
+            .unwrap_or_else(|| panic!("assert_not_equal_ext: both values are {:?}", a));
+        out_buffer.set_extension_target(self.diff_inv, diff_inv)
+    }
 }
 
 /// An iterator over the powers of a certain base element `b`: `b^0, b^1, b^2, ...`.
@@ -571,7 +646,12 @@ mod tests {
     use anyhow::Result;
 
     use crate::field::extension::algebra::ExtensionAlgebra;
-    use crate::field::types::Sample;
+    use crate::field::extension::quartic::QuarticExtension;
+    use crate::field::extension::quintic::QuinticExtension;
+    use crate::field::extension::FieldExtension;
+    use crate::field::goldilocks_field::GoldilocksField;
+    use crate::field::types::{Field, Sample};
+    use crate::hash::poseidon::PoseidonHash;
     use crate::iop::ext_target::ExtensionAlgebraTarget;
     use crate::iop::witness::{PartialWitness, WitnessWrite};
     use crate::plonk::circuit_builder::CircuitBuilder;
@@ -579,6 +659,26 @@ mod tests {
     use crate::plonk::config::{GenericConfig, KeccakGoldilocksConfig, PoseidonGoldilocksConfig};
     use crate::plonk::verifier::verify;
 
+    /// Poseidon over the quartic extension of Goldilocks, to exercise gadgets with `D = 4`.
+    #[derive(Debug, Copy, Clone, Eq, PartialEq)]
+    struct PoseidonGoldilocksQuarticConfig;
+    impl GenericConfig<4> for PoseidonGoldilocksQuarticConfig {
+        type F = GoldilocksField;
+        type FE = QuarticExtension<Self::F>;
+        type Hasher = PoseidonHash;
+        type InnerHasher = PoseidonHash;
+    }
+
+    /// Poseidon over the quintic extension of Goldilocks, to exercise gadgets with `D = 5`.
+    #[derive(Debug, Copy, Clone, Eq, PartialEq)]
+    struct PoseidonGoldilocksQuinticConfig;
+    impl GenericConfig<5> for PoseidonGoldilocksQuinticConfig {
+        type F = GoldilocksField;
+        type FE = QuinticExtension<Self::F>;
+        type Hasher = PoseidonHash;
+        type InnerHasher = PoseidonHash;
+    }
+
     #[test]
     fn test_mul_many() -> Result<()> {
         const D: usize = 2;
@@ -642,6 +742,60 @@ mod tests {
         verify(proof, &data.verifier_only, &data.common)
     }
 
+    #[test]
+    fn test_assert_not_equal_ext() -> Result<()> {
+        const D: usize = 2;
+        type C = PoseidonGoldilocksConfig;
+        type F = <C as GenericConfig<D>>::F;
+        type FF = <C as GenericConfig<D>>::FE;
+
+        let config = CircuitConfig::standard_recursion_config();
+        let mut pw = PartialWitness::new();
+        let mut builder = CircuitBuilder::<F, D>::new(config);
+
+        // Values differing in only one coordinate must also be accepted.
+        let x = FF::rand();
+        let pairs = [
+            (x, FF::rand()),
+            (x, x + FF::ONE),
+            (x, x + FF::from_basefield_array([F::ZERO, F::ONE])),
+        ];
+        for (a_value, b_value) in pairs {
+            let a = builder.add_virtual_extension_target();
+            let b = builder.add_virtual_extension_target();
+            pw.set_extension_target(a, a_value);
+            pw.set_extension_target(b, b_value);
+            builder.assert_not_equal_ext(a, b);
+        }
+
+        let data = builder.build::<C>();
+        let proof = data.prove(pw)?;
+        verify(proof, &data.verifier_only, &data.common)
+    }
+
+    #[test]
+    #[should_panic(expected = "assert_not_equal_ext: both values are")]
+    fn test_assert_not_equal_ext_equal() {
+        const D: usize = 2;
+        type C = PoseidonGoldilocksConfig;
+        type F = <C as GenericConfig<D>>::F;
+        type FF = <C as GenericConfig<D>>::FE;
+
+        let config = CircuitConfig::standard_recursion_config();
+        let mut pw = PartialWitness::new();
+        let mut builder = CircuitBuilder::<F, D>::new(config);
+
+        let x = FF::rand();
+        let a = builder.add_virtual_extension_target();
+        let b = builder.add_virtual_extension_target();
+        pw.set_extension_target(a, x);
+        pw.set_extension_target(b, x);
+        builder.assert_not_equal_ext(a, b);
+
+        let data = builder.build::<C>();
+        let _ = data.prove(pw);
+    }
+
     #[test]
     fn test_mul_algebra() -> Result<()> {
         const D: usize = 2;
@@ -679,4 +833,76 @@ mod tests {
 
         verify(proof, &data.verifier_only, &data.common)
     }
+
+    fn test_conditional_negate_ext_with<C: GenericConfig<D>, const D: usize>(
+        cond: bool,
+    ) -> Result<()> {
+        let config = CircuitConfig::standard_recursion_config();
+
+        let mut pw = PartialWitness::new();
+        let mut builder = CircuitBuilder::<C::F, D>::new(config);
+
+        let x = C::FE::rand();
+        let expected = if cond { -x } else { x };
+
+        let cond_t = builder.add_virtual_bool_target_safe();
+        let xt = builder.add_virtual_extension_target();
+        let num_gates = builder.num_gates();
+        let yt = builder.conditional_negate_ext(cond_t, xt);
+        // The negation takes a single arithmetic operation, in one new gate.
+        assert_eq!(builder.num_gates(), num_gates + 1);
+        let expected_t = builder.constant_extension(expected);
+        builder.connect_extension(yt, expected_t);
+
+        pw.set_bool_target(cond_t, cond);
+        pw.set_extension_target(xt, x);
+
+        let data = builder.build::<C>();
+        let proof = data.prove(pw)?;
+
+        verify(proof, &data.verifier_only, &data.common)
+    }
+
+    #[test]
+    fn test_conditional_negate_ext() -> Result<()> {
+        for cond in [false, true] {
+            test_conditional_negate_ext_with::<PoseidonGoldilocksConfig, 2>(cond)?;
+            test_conditional_negate_ext_with::<PoseidonGoldilocksQuarticConfig, 4>(cond)?;
+            test_conditional_negate_ext_with::<PoseidonGoldilocksQuinticConfig, 5>(cond)?;
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn test_arithmetic_extension_quintic() -> Result<()> {
+        const D: usize = 5;
+        type C = PoseidonGoldilocksQuinticConfig;
+        type F = <C as GenericConfig<D>>::F;
+        type FF = <C as GenericConfig<D>>::FE;
+
+        let config = CircuitConfig::standard_recursion_config();
+
+        let mut pw = PartialWitness::<F>::new();
+        let mut builder = CircuitBuilder::<F, D>::new(config);
+
+        let [x, y, z] = [FF::rand(), FF::rand(), FF::rand()];
+        let [xt, yt, zt] = [(); 3].map(|_| builder.add_virtual_extension_target());
+        pw.set_extension_target(xt, x);
+        pw.set_extension_target(yt, y);
+        pw.set_extension_target(zt, z);
+
+        // `3 x y - 2 z` and `x / y`, each checked against the value computed natively.
+        let three = F::from_canonical_u64(3);
+        let result = builder.arithmetic_extension(three, -F::TWO, xt, yt, zt);
+        let expected = builder.constant_extension(FF::from_canonical_u64(3) * x * y - z.double());
+        builder.connect_extension(result, expected);
+        let quotient = builder.div_extension(xt, yt);
+        let expected = builder.constant_extension(x / y);
+        builder.connect_extension(quotient, expected);
+
+        let data = builder.build::<C>();
+        let proof = data.prove(pw)?;
+
+        verify(proof, &data.verifier_only, &data.common)
+    }
 }
@@ -0,0 +1,288 @@
+use alloc::vec::Vec;
+
+use crate::field::extension::Extendable;
+use crate::hash::hash_types::RichField;
+use crate::iop::target::{BoolTarget, Target};
+use crate::plonk::circuit_builder::CircuitBuilder;
+
+impl<F: RichField + Extendable<D>, const D: usize> CircuitBuilder<F, D> {
+    /// Returns whether the canonical value of `x` is less than `c`.
+    pub fn is_less_than_const(&mut self, x: Target, c: u64) -> BoolTarget {
+        let bits = self.split_le_canonical(x);
+        self.le_bits_less_than_const(&bits, c)
+    }
+
+    /// Returns `x` clamped to `[lo, hi]`, comparing canonical values. Values inside the range are
+    /// returned unchanged. Panics if `lo > hi`, or if `hi` is not a canonical field element.
+    pub fn clamp(&mut self, x: Target, lo: u64, hi: u64) -> Target {
+        assert!(lo <= hi, "Empty clamp range: {} > {}", lo, hi);
+        assert!(hi < F::ORDER, "{} is not a canonical field element", hi);
+
+        let bits = self.split_le_canonical(x);
+        let below_lo = self.le_bits_less_than_const(&bits, lo);
+        let at_most_hi = self.le_bits_less_than_const(&bits, hi + 1);
+
+        let lo = self.constant(F::from_canonical_u64(lo));
+        let hi = self.constant(F::from_canonical_u64(hi));
+        let clamped_above = self.select(at_most_hi, x, hi);
+        self.select(below_lo, lo, clamped_above)
+    }
+
+    /// Returns whether `a < b`, where both have at most `num_bits` bits.
+    ///
+    /// `a - b + 2^num_bits` is split into `num_bits + 1` bits; its top bit is set exactly when
+    /// `a >= b`.
+    pub fn is_less_than(&mut self, a: Target, b: Target, num_bits: usize) -> BoolTarget {
+        assert!(
+            num_bits < F::BITS - 1,
+            "{} bits are too many to compare",
+            num_bits
+        );
+
+        self.range_check(a, num_bits);
+        self.range_check(b, num_bits);
+        let difference = self.sub(a, b);
+        let shifted = self.add_const(difference, F::from_canonical_u64(1 << num_bits));
+        let bits = self.split_le(shifted, num_bits + 1);
+        self.not(bits[num_bits])
+    }
+
+    /// Returns the smaller of `a` and `b`, where both have at most `num_bits` bits.
+    pub fn min(&mut self, a: Target, b: Target, num_bits: usize) -> Target {
+        let a_lt_b = self.is_less_than(a, b, num_bits);
+        self.select(a_lt_b, a, b)
+    }
+
+    /// Returns the larger of `a` and `b`, where both have at most `num_bits` bits.
+    pub fn max(&mut self, a: Target, b: Target, num_bits: usize) -> Target {
+        let a_lt_b = self.is_less_than(a, b, num_bits);
+        self.select(a_lt_b, b, a)
+    }
+
+    /// Returns `a - b`, asserting that it does not underflow, where both have at most `num_bits`
+    /// bits. Since `a >= b`, the comparison itself bounds the difference to `num_bits` bits.
+    pub fn checked_sub(&mut self, a: Target, b: Target, num_bits: usize) -> Target {
+        let a_lt_b = self.is_less_than(a, b, num_bits);
+        self.assert_zero(a_lt_b.target);
+        self.sub(a, b)
+    }
+
+    /// Asserts that `xs` is sorted in non-decreasing order, where each element has at most
+    /// `num_bits` bits.
+    ///
+    /// Each difference `xs[i + 1] - xs[i]` is range-checked to `num_bits` bits. If `xs[i + 1]` were
+    /// smaller, the difference would wrap around to at least `p - 2^num_bits`, which is out of
+    /// range as long as `num_bits` is less than `F::BITS - 1`.
+    pub fn assert_sorted(&mut self, xs: &[Target], num_bits: usize) {
+        assert!(
+            num_bits < F::BITS - 1,
+            "{} bits are too many to compare",
+            num_bits
+        );
+
+        for &x in xs {
+            self.range_check(x, num_bits);
+        }
+        for pair in xs.windows(2) {
+            let difference = self.sub(pair[1], pair[0]);
+            self.range_check(difference, num_bits);
+        }
+    }
+
+    /// Splits `x` into the little-endian bits of its canonical value, i.e. the unique
+    /// decomposition which is less than the field order.
+    fn split_le_canonical(&mut self, x: Target) -> Vec<BoolTarget> {
+        let bits = self.split_le(x, F::BITS);
+        let canonical = self.le_bits_less_than_const(&bits, F::ORDER);
+        self.assert_one(canonical.target);
+        bits
+    }
+
+    /// Returns whether the number with little-endian bits `bits` is less than `c`.
+    fn le_bits_less_than_const(&mut self, bits: &[BoolTarget], c: u64) -> BoolTarget {
+        if bits.len() < 64 && c >> bits.len() != 0 {
+            return self._true();
+        }
+
+        // Scanning from the least significant bit, `lt` tracks whether the bits seen so far are
+        // less than the corresponding low bits of `c`. A differing bit overrides the lower ones.
+        let mut lt = self._false();
+        for (i, &bit) in bits.iter().enumerate() {
+            lt = if (c >> i) & 1 == 1 {
+                // `lt = !bit || lt`
+                let not_bit = self.not(bit);
+                BoolTarget::new_unsafe(self.mul_add(bit.target, lt.target, not_bit.target))
+            } else {
+                // `lt = !bit && lt`
+                BoolTarget::new_unsafe(self.arithmetic(
+                    F::NEG_ONE,
+                    F::ONE,
+                    bit.target,
+                    lt.target,
+                    lt.target,
+                ))
+            };
+        }
+        lt
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use anyhow::Result;
+
+    use crate::field::types::{Field, Field64};
+    use crate::iop::witness::{PartialWitness, WitnessWrite};
+    use crate::plonk::circuit_builder::CircuitBuilder;
+    use crate::plonk::circuit_data::CircuitConfig;
+    use crate::plonk::config::{GenericConfig, PoseidonGoldilocksConfig};
+    use crate::plonk::verifier::verify;
+
+    const D: usize = 2;
+    type C = PoseidonGoldilocksConfig;
+    type F = <C as GenericConfig<D>>::F;
+
+    #[test]
+    fn test_clamp() -> Result<()> {
+        let config = CircuitConfig::standard_recursion_config();
+        let mut pw = PartialWitness::new();
+        let mut builder = CircuitBuilder::<F, D>::new(config);
+
+        let (lo, hi) = (100, 1 << 40);
+        for v in [0, 99, 100, 12345, 1 << 40, (1 << 40) + 1, F::ORDER - 1] {
+            let x = builder.add_virtual_target();
+            pw.set_target(x, F::from_canonical_u64(v));
+            let clamped = builder.clamp(x, lo, hi);
+            let expected = builder.constant(F::from_canonical_u64(v.clamp(lo, hi)));
+            builder.connect(clamped, expected);
+
+            let lt = builder.is_less_than_const(x, 12345);
+            let expected = builder.constant_bool(v < 12345);
+            builder.connect(lt.target, expected.target);
+        }
+
+        let data = builder.build::<C>();
+        let proof = data.prove(pw)?;
+
+        verify(proof, &data.verifier_only, &data.common)
+    }
+
+    fn prove_min_max(pairs: &[(u64, u64)], num_bits: usize) -> Result<()> {
+        let config = CircuitConfig::standard_recursion_config();
+        let mut pw = PartialWitness::new();
+        let mut builder = CircuitBuilder::<F, D>::new(config);
+
+        for &(a_value, b_value) in pairs {
+            let a = builder.add_virtual_target();
+            let b = builder.add_virtual_target();
+            pw.set_target(a, F::from_canonical_u64(a_value));
+            pw.set_target(b, F::from_canonical_u64(b_value));
+
+            let min = builder.min(a, b, num_bits);
+            let max = builder.max(a, b, num_bits);
+            let expected_min = builder.constant(F::from_canonical_u64(a_value.min(b_value)));
+            let expected_max = builder.constant(F::from_canonical_u64(a_value.max(b_value)));
+            builder.connect(min, expected_min);
+            builder.connect(max, expected_max);
+        }
+
+        let data = builder.build::<C>();
+        let proof = data.prove(pw)?;
+        verify(proof, &data.verifier_only, &data.common)
+    }
+
+    #[test]
+    fn test_min_max() -> Result<()> {
+        let max_value = (1 << 32) - 1;
+        prove_min_max(
+            &[
+                (0, 0),
+                (3, 5),
+                (5, 3),
+                (7, 7),
+                (0, max_value),
+                (max_value, 1),
+                (max_value, max_value),
+                (0xdead_beef, 0xcafe_babe),
+            ],
+            32,
+        )
+    }
+
+    #[test]
+    #[should_panic(expected = "was set twice with different values")]
+    fn test_min_max_too_few_bits() {
+        prove_min_max(&[(3, 256)], 8).unwrap();
+    }
+
+    fn prove_checked_sub(pairs: &[(u64, u64)]) -> Result<()> {
+        let config = CircuitConfig::standard_recursion_config();
+        let mut pw = PartialWitness::new();
+        let mut builder = CircuitBuilder::<F, D>::new(config);
+
+        for &(a_value, b_value) in pairs {
+            let a = builder.add_virtual_target();
+            let b = builder.add_virtual_target();
+            pw.set_target(a, F::from_canonical_u64(a_value));
+            pw.set_target(b, F::from_canonical_u64(b_value));
+
+            let difference = builder.checked_sub(a, b, 32);
+            let expected = builder.constant(F::from_canonical_u64(a_value.wrapping_sub(b_value)));
+            builder.connect(difference, expected);
+        }
+
+        let data = builder.build::<C>();
+        let proof = data.prove(pw)?;
+        verify(proof, &data.verifier_only, &data.common)
+    }
+
+    #[test]
+    fn test_checked_sub() -> Result<()> {
+        let max_value = (1 << 32) - 1;
+        prove_checked_sub(&[(0, 0), (7, 7), (5, 3), (max_value, 0), (max_value, 1)])
+    }
+
+    #[test]
+    #[should_panic(expected = "was set twice with different values")]
+    fn test_checked_sub_underflow() {
+        prove_checked_sub(&[(3, 5)]).unwrap();
+    }
+
+    fn prove_sorted(values: &[u64]) -> Result<()> {
+        let config = CircuitConfig::standard_recursion_config();
+        let mut pw = PartialWitness::new();
+        let mut builder = CircuitBuilder::<F, D>::new(config);
+
+        let xs = builder.add_virtual_targets(values.len());
+        for (&x, &v) in xs.iter().zip(values) {
+            pw.set_target(x, F::from_canonical_u64(v));
+        }
+        builder.assert_sorted(&xs, 16);
+
+        let data = builder.build::<C>();
+        let proof = data.prove(pw)?;
+        verify(proof, &data.verifier_only, &data.common)
+    }
+
+    #[test]
+    fn test_assert_sorted() -> Result<()> {
+        prove_sorted(&[])?;
+        prove_sorted(&[7])?;
+        prove_sorted(&[0, 1, 1, 5, 300, 300, 65535])
+    }
+
+    #[test]
+    #[should_panic(expected = "was set twice with different values")]
+    fn test_assert_sorted_unsorted() {
+        prove_sorted(&[0, 5, 4, 10]).unwrap();
+    }
+
+    #[test]
+    #[should_panic(expected = "Empty clamp range")]
+    fn test_clamp_empty_range() {
+        let config = CircuitConfig::standard_recursion_config();
+        let mut builder = CircuitBuilder::<F, D>::new(config);
+        let x = builder.add_virtual_target();
+        builder.clamp(x, 2, 1);
+    }
+}
@@ -1,6 +1,7 @@
 use crate::field::extension::Extendable;
 use crate::hash::hash_types::{HashOutTarget, RichField};
 use crate::hash::hashing::SPONGE_WIDTH;
+use crate::hash::poseidon::PoseidonHash;
 use crate::iop::target::{BoolTarget, Target};
 use crate::plonk::circuit_builder::CircuitBuilder;
 use crate::plonk::config::AlgebraicHasher;
@@ -15,6 +16,13 @@ impl<F: RichField + Extendable<D>, const D: usize> CircuitBuilder<F, D> {
         self.permute_swapped::<H>(inputs, _false)
     }
 
+    /// A hiding commitment to `values`, computed as the Poseidon hash of `blinding` followed by
+    /// `values`. This matches the host-side `poseidon::commit`.
+    pub fn commit(&mut self, values: &[Target], blinding: Target) -> HashOutTarget {
+        let inputs = [&[blinding], values].concat();
+        self.hash_n_to_hash_no_pad::<PoseidonHash>(inputs)
+    }
+
     /// Conditionally swap two chunks of the inputs (useful in verifying Merkle proofs), then apply
     /// a cryptographic permutation.
     pub(crate) fn permute_swapped<H: AlgebraicHasher<F>>(
@@ -25,3 +33,51 @@ impl<F: RichField + Extendable<D>, const D: usize> CircuitBuilder<F, D> {
         H::permute_swapped(inputs, swap, self)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use anyhow::Result;
+
+    use crate::field::types::Sample;
+    use crate::hash::poseidon::commit;
+    use crate::iop::witness::{PartialWitness, WitnessWrite};
+    use crate::plonk::circuit_builder::CircuitBuilder;
+    use crate::plonk::circuit_data::CircuitConfig;
+    use crate::plonk::config::{GenericConfig, PoseidonGoldilocksConfig};
+
+    #[test]
+    fn test_commit() -> Result<()> {
+        const D: usize = 2;
+        type C = PoseidonGoldilocksConfig;
+        type F = <C as GenericConfig<D>>::F;
+        let config = CircuitConfig::standard_recursion_config();
+        let mut pw = PartialWitness::<F>::new();
+        let mut builder = CircuitBuilder::<F, D>::new(config);
+
+        let values = F::rand_vec(10);
+        let (blinding_a, blinding_b) = (F::rand(), F::rand());
+        let expected_a = commit(&values, blinding_a);
+        let expected_b = commit(&values, blinding_b);
+        assert_ne!(expected_a, expected_b);
+
+        let values_t = builder.add_virtual_targets(values.len());
+        let blinding_a_t = builder.add_virtual_target();
+        let blinding_b_t = builder.add_virtual_target();
+        for (&t, &v) in values_t.iter().zip(&values) {
+            pw.set_target(t, v);
+        }
+        pw.set_target(blinding_a_t, blinding_a);
+        pw.set_target(blinding_b_t, blinding_b);
+
+        let commitment_a = builder.commit(&values_t, blinding_a_t);
+        let commitment_b = builder.commit(&values_t, blinding_b_t);
+        let expected_a_t = builder.constant_hash(expected_a);
+        let expected_b_t = builder.constant_hash(expected_b);
+        builder.connect_hashes(commitment_a, expected_a_t);
+        builder.connect_hashes(commitment_b, expected_b_t);
+
+        let data = builder.build::<C>();
+        let proof = data.prove(pw)?;
+        data.verify(proof)
+    }
+}
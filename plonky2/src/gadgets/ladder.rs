@@ -0,0 +1,174 @@
+use alloc::vec::Vec;
+
+use crate::field::extension::Extendable;
+use crate::gates::gate::Gate;
+use crate::gates::ladder_step::LadderStepGate;
+use crate::hash::hash_types::RichField;
+use crate::iop::target::{BoolTarget, Target};
+use crate::plonk::circuit_builder::CircuitBuilder;
+
+impl<F: RichField + Extendable<D>, const D: usize> CircuitBuilder<F, D> {
+    /// Performs one Montgomery ladder step on `(r0, r1)`, using a `LadderStepGate`, for a group
+    /// whose elements are represented by `r0.len()` targets and whose law is given by `add` and
+    /// `double`. Returns `(2 r0, r0 + r1)` if `bit` is false, or `(r0 + r1, 2 r1)` if it is true.
+    /// Starting from `(identity, x)` and stepping through the bits of a scalar `k`, most
+    /// significant first, leaves `k x` in the first element.
+    pub fn ladder_step<A, Dbl>(
+        &mut self,
+        bit: BoolTarget,
+        r0: &[Target],
+        r1: &[Target],
+        add: A,
+        double: Dbl,
+    ) -> (Vec<Target>, Vec<Target>)
+    where
+        A: FnOnce(&mut Self, &[Target], &[Target]) -> Vec<Target>,
+        Dbl: FnOnce(&mut Self, &[Target]) -> Vec<Target>,
+    {
+        let num_limbs = r0.len();
+        assert_eq!(
+            r1.len(),
+            num_limbs,
+            "Both ladder elements need the same limbs"
+        );
+        let gate = LadderStepGate::new(num_limbs);
+        assert!(
+            <LadderStepGate as Gate<F, D>>::num_wires(&gate) <= self.config.num_routed_wires,
+            "{} limbs do not fit in the routed wires of a LadderStepGate",
+            num_limbs
+        );
+        let wires = |range: core::ops::Range<usize>, row: usize| -> Vec<Target> {
+            range.map(|wire| Target::wire(row, wire)).collect()
+        };
+
+        let row = self.add_gate(gate.clone(), vec![]);
+        self.connect(bit.target, Target::wire(row, LadderStepGate::wire_bit()));
+        for (&x, y) in r0.iter().zip(wires(gate.wires_r0(), row)) {
+            self.connect(x, y);
+        }
+        for (&x, y) in r1.iter().zip(wires(gate.wires_r1(), row)) {
+            self.connect(x, y);
+        }
+
+        let sum = add(self, r0, r1);
+        let doubled = double(self, &wires(gate.wires_to_double(), row));
+        assert_eq!(
+            sum.len(),
+            num_limbs,
+            "add returned the wrong number of limbs"
+        );
+        assert_eq!(
+            doubled.len(),
+            num_limbs,
+            "double returned the wrong number of limbs"
+        );
+        for (x, y) in sum.into_iter().zip(wires(gate.wires_sum(), row)) {
+            self.connect(x, y);
+        }
+        for (x, y) in doubled.into_iter().zip(wires(gate.wires_doubled(), row)) {
+            self.connect(x, y);
+        }
+
+        (
+            wires(gate.wires_new_r0(), row),
+            wires(gate.wires_new_r1(), row),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use anyhow::Result;
+
+    use crate::field::types::{Field, Sample};
+    use crate::iop::target::Target;
+    use crate::iop::witness::{PartialWitness, WitnessWrite};
+    use crate::plonk::circuit_builder::CircuitBuilder;
+    use crate::plonk::circuit_data::CircuitConfig;
+    use crate::plonk::config::{GenericConfig, PoseidonGoldilocksConfig};
+    use crate::plonk::verifier::verify;
+
+    #[test]
+    fn test_ladder_step_branches() -> Result<()> {
+        const D: usize = 2;
+        type C = PoseidonGoldilocksConfig;
+        type F = <C as GenericConfig<D>>::F;
+        let config = CircuitConfig::standard_recursion_config();
+        let mut pw = PartialWitness::new();
+        let mut builder = CircuitBuilder::<F, D>::new(config);
+
+        // The additive group of pairs of field elements.
+        let add = |builder: &mut CircuitBuilder<F, D>, x: &[Target], y: &[Target]| {
+            x.iter().zip(y).map(|(&a, &b)| builder.add(a, b)).collect()
+        };
+        let double = |builder: &mut CircuitBuilder<F, D>, x: &[Target]| {
+            x.iter().map(|&a| builder.add(a, a)).collect()
+        };
+
+        let r0_values = F::rand_vec(2);
+        let r1_values = F::rand_vec(2);
+        let r0 = builder.add_virtual_targets(2);
+        let r1 = builder.add_virtual_targets(2);
+        for i in 0..2 {
+            pw.set_target(r0[i], r0_values[i]);
+            pw.set_target(r1[i], r1_values[i]);
+        }
+        let sum = (0..2)
+            .map(|i| r0_values[i] + r1_values[i])
+            .collect::<Vec<_>>();
+        let double_r0 = r0_values.iter().map(|&x| x.double()).collect::<Vec<_>>();
+        let double_r1 = r1_values.iter().map(|&x| x.double()).collect::<Vec<_>>();
+
+        for (bit_value, expected_r0, expected_r1) in
+            [(false, &double_r0, &sum), (true, &sum, &double_r1)]
+        {
+            let bit = builder.constant_bool(bit_value);
+            let (new_r0, new_r1) = builder.ladder_step(bit, &r0, &r1, add, double);
+            for i in 0..2 {
+                let expected = builder.constant(expected_r0[i]);
+                builder.connect(new_r0[i], expected);
+                let expected = builder.constant(expected_r1[i]);
+                builder.connect(new_r1[i], expected);
+            }
+        }
+
+        let data = builder.build::<C>();
+        let proof = data.prove(pw)?;
+        verify(proof, &data.verifier_only, &data.common)
+    }
+
+    #[test]
+    fn test_ladder_exponentiation() -> Result<()> {
+        const D: usize = 2;
+        type C = PoseidonGoldilocksConfig;
+        type F = <C as GenericConfig<D>>::F;
+        let config = CircuitConfig::standard_recursion_config();
+        let mut pw = PartialWitness::new();
+        let mut builder = CircuitBuilder::<F, D>::new(config);
+
+        // A ladder in the multiplicative group computes `x^k`.
+        let x_value = F::rand();
+        let k = 0b1011_0010_1110_0101u64;
+        let x = builder.add_virtual_target();
+        pw.set_target(x, x_value);
+
+        let mut r0 = vec![builder.one()];
+        let mut r1 = vec![x];
+        for i in (0..16).rev() {
+            let bit = builder.constant_bool((k >> i) & 1 == 1);
+            (r0, r1) = builder.ladder_step(
+                bit,
+                &r0,
+                &r1,
+                |builder, a, b| vec![builder.mul(a[0], b[0])],
+                |builder, a| vec![builder.square(a[0])],
+            );
+        }
+        let expected = builder.constant(x_value.exp_u64(k));
+        builder.connect(r0[0], expected);
+
+        let data = builder.build::<C>();
+        let proof = data.prove(pw)?;
+        verify(proof, &data.verifier_only, &data.common)
+    }
+}
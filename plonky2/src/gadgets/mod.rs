@@ -1,6 +1,8 @@
 pub mod arithmetic;
 pub mod arithmetic_extension;
+pub mod comparison;
 pub mod hash;
+pub mod ladder;
 pub mod polynomial;
 pub mod random_access;
 pub mod range_check;
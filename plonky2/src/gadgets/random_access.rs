@@ -4,7 +4,7 @@ use crate::field::extension::Extendable;
 use crate::gates::random_access::RandomAccessGate;
 use crate::hash::hash_types::RichField;
 use crate::iop::ext_target::ExtensionTarget;
-use crate::iop::target::Target;
+use crate::iop::target::{BoolTarget, Target};
 use crate::plonk::circuit_builder::CircuitBuilder;
 use crate::util::log2_strict;
 
@@ -51,6 +51,39 @@ impl<F: RichField + Extendable<D>, const D: usize> CircuitBuilder<F, D> {
 
         ExtensionTarget(v.try_into().unwrap())
     }
+
+    /// Returns `table[index]`, where `index` is given by its little-endian `index_bits`, by
+    /// evaluating the multilinear extension of `table` at the bits. The table must have exactly
+    /// `2^index_bits.len()` entries, so shorter tables need to be padded by the caller.
+    pub fn const_rom(&mut self, index_bits: &[BoolTarget], table: &[F]) -> Target {
+        assert_eq!(
+            table.len(),
+            1 << index_bits.len(),
+            "A table indexed by {} bits needs {} entries",
+            index_bits.len(),
+            1 << index_bits.len()
+        );
+        let Some((&first_bit, other_bits)) = index_bits.split_first() else {
+            return self.constant(table[0]);
+        };
+
+        // Folding in the first bit only involves constants, so it costs a single operation per
+        // pair of entries.
+        let mut entries = table
+            .chunks(2)
+            .map(|pair| {
+                let low = self.constant(pair[0]);
+                self.mul_const_add(pair[1] - pair[0], first_bit.target, low)
+            })
+            .collect::<Vec<_>>();
+        for &bit in other_bits {
+            entries = entries
+                .chunks(2)
+                .map(|pair| self.select(bit, pair[1], pair[0]))
+                .collect();
+        }
+        entries[0]
+    }
 }
 
 #[cfg(test)]
@@ -59,7 +92,7 @@ mod tests {
 
     use super::*;
     use crate::field::types::{Field, Sample};
-    use crate::iop::witness::PartialWitness;
+    use crate::iop::witness::{PartialWitness, WitnessWrite};
     use crate::plonk::circuit_data::CircuitConfig;
     use crate::plonk::config::{GenericConfig, PoseidonGoldilocksConfig};
     use crate::plonk::verifier::verify;
@@ -96,4 +129,44 @@ mod tests {
         }
         Ok(())
     }
+
+    #[test]
+    fn test_const_rom() -> Result<()> {
+        const D: usize = 2;
+        type C = PoseidonGoldilocksConfig;
+        type F = <C as GenericConfig<D>>::F;
+        let config = CircuitConfig::standard_recursion_config();
+        let mut pw = PartialWitness::new();
+        let mut builder = CircuitBuilder::<F, D>::new(config);
+
+        let table = F::rand_vec(4);
+        for (index, &expected) in table.iter().enumerate() {
+            let index_bits = (0..2)
+                .map(|i| {
+                    let bit = builder.add_virtual_bool_target_safe();
+                    pw.set_bool_target(bit, (index >> i) & 1 == 1);
+                    bit
+                })
+                .collect::<Vec<_>>();
+            let entry = builder.const_rom(&index_bits, &table);
+            let expected = builder.constant(expected);
+            builder.connect(entry, expected);
+        }
+
+        let data = builder.build::<C>();
+        let proof = data.prove(pw)?;
+        verify(proof, &data.verifier_only, &data.common)
+    }
+
+    #[test]
+    #[should_panic(expected = "A table indexed by 2 bits needs 4 entries")]
+    fn test_const_rom_wrong_size() {
+        const D: usize = 2;
+        type C = PoseidonGoldilocksConfig;
+        type F = <C as GenericConfig<D>>::F;
+        let config = CircuitConfig::standard_recursion_config();
+        let mut builder = CircuitBuilder::<F, D>::new(config);
+        let index_bits = [builder.add_virtual_bool_target_safe(); 2];
+        builder.const_rom(&index_bits, &F::rand_vec(3));
+    }
 }
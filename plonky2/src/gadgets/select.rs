@@ -1,5 +1,7 @@
 use crate::field::extension::Extendable;
-use crate::hash::hash_types::RichField;
+use crate::gates::hash_select::HashSelectGate;
+use crate::gates::mux4::Mux4Gate;
+use crate::hash::hash_types::{HashOutTarget, RichField, NUM_HASH_OUT_ELTS};
 use crate::iop::ext_target::ExtensionTarget;
 use crate::iop::target::{BoolTarget, Target};
 use crate::plonk::circuit_builder::CircuitBuilder;
@@ -34,13 +36,87 @@ impl<F: RichField + Extendable<D>, const D: usize> CircuitBuilder<F, D> {
         let tmp = self.mul_sub(b.target, y, y);
         self.mul_sub(b.target, x, tmp)
     }
+
+    /// Computes `if b { h0 } else { h1 }`, like `select_hash`, but using a `HashSelectGate`, which
+    /// packs several such selections per row.
+    pub fn select_hash_packed(
+        &mut self,
+        b: BoolTarget,
+        h0: HashOutTarget,
+        h1: HashOutTarget,
+    ) -> HashOutTarget {
+        let gate = HashSelectGate::new_from_config(&self.config);
+        let (row, i) = self.find_slot(gate, &[], &[]);
+        self.connect(
+            b.target,
+            Target::wire(row, HashSelectGate::wire_ith_cond(i)),
+        );
+        for j in 0..NUM_HASH_OUT_ELTS {
+            self.connect(
+                h0.elements[j],
+                Target::wire(row, HashSelectGate::wire_ith_a(i, j)),
+            );
+            self.connect(
+                h1.elements[j],
+                Target::wire(row, HashSelectGate::wire_ith_b(i, j)),
+            );
+        }
+        HashOutTarget {
+            elements: core::array::from_fn(|j| {
+                Target::wire(row, HashSelectGate::wire_ith_output(i, j))
+            }),
+        }
+    }
+
+    /// Returns `inputs[s0 + 2 s1]`, using a `Mux4Gate`, which packs several such selections per
+    /// row.
+    pub fn mux4(&mut self, s0: BoolTarget, s1: BoolTarget, inputs: [Target; 4]) -> Target {
+        let gate = Mux4Gate::new_from_config(&self.config);
+        let (row, i) = self.find_slot(gate, &[], &[]);
+        self.connect(s0.target, Target::wire(row, Mux4Gate::wire_ith_s0(i)));
+        self.connect(s1.target, Target::wire(row, Mux4Gate::wire_ith_s1(i)));
+        for (j, input) in inputs.into_iter().enumerate() {
+            self.connect(input, Target::wire(row, Mux4Gate::wire_ith_input(i, j)));
+        }
+        Target::wire(row, Mux4Gate::wire_ith_output(i))
+    }
+
+    /// Returns `values[i]` for the first `i` such that `conds[i]` is true, or `default` if none
+    /// are true.
+    pub fn priority_select(
+        &mut self,
+        conds: &[BoolTarget],
+        values: &[Target],
+        default: Target,
+    ) -> Target {
+        assert_eq!(
+            conds.len(),
+            values.len(),
+            "priority_select needs one value per condition"
+        );
+
+        // `active` is 1 while no condition seen so far is true, so it masks out every value after
+        // the first selected one.
+        let mut active = self.one();
+        let mut result = self.zero();
+        for (&cond, &value) in conds.iter().zip(values) {
+            let take = self.mul(active, cond.target);
+            result = self.mul_add(take, value, result);
+            active = self.sub(active, take);
+        }
+        self.mul_add(active, default, result)
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use anyhow::Result;
 
-    use crate::field::types::Sample;
+    use crate::field::types::{Field, Sample};
+    use crate::hash::hash_types::HashOutTarget;
+    use crate::hash::merkle_tree::MerkleTree;
+    use crate::hash::poseidon::PoseidonHash;
+    use crate::iop::target::BoolTarget;
     use crate::iop::witness::{PartialWitness, WitnessWrite};
     use crate::plonk::circuit_builder::CircuitBuilder;
     use crate::plonk::circuit_data::CircuitConfig;
@@ -77,4 +153,143 @@ mod tests {
 
         verify(proof, &data.verifier_only, &data.common)
     }
+
+    #[test]
+    fn test_priority_select() -> Result<()> {
+        const D: usize = 2;
+        type C = PoseidonGoldilocksConfig;
+        type F = <C as GenericConfig<D>>::F;
+        let config = CircuitConfig::standard_recursion_config();
+        let mut pw = PartialWitness::<F>::new();
+        let mut builder = CircuitBuilder::<F, D>::new(config);
+
+        let values = F::rand_vec(3);
+        let default_value = F::rand();
+        let value_targets = builder.add_virtual_targets(3);
+        let default = builder.add_virtual_target();
+        for (&t, &v) in value_targets.iter().zip(&values) {
+            pw.set_target(t, v);
+        }
+        pw.set_target(default, default_value);
+
+        // First true, middle true (with a later one also true), and none true.
+        let cases = [
+            ([true, true, false], values[0]),
+            ([false, true, true], values[1]),
+            ([false, false, false], default_value),
+        ];
+        for (cond_values, expected) in cases {
+            let conds = cond_values.map(|_| builder.add_virtual_bool_target_safe());
+            for (&cond, value) in conds.iter().zip(cond_values) {
+                pw.set_bool_target(cond, value);
+            }
+            let selected = builder.priority_select(&conds, &value_targets, default);
+            let expected = builder.constant(expected);
+            builder.connect(selected, expected);
+        }
+
+        let selected = builder.priority_select(&[], &[], default);
+        builder.connect(selected, default);
+
+        let data = builder.build::<C>();
+        let proof = data.prove(pw)?;
+
+        verify(proof, &data.verifier_only, &data.common)
+    }
+
+    const D: usize = 2;
+    type C = PoseidonGoldilocksConfig;
+    type F = <C as GenericConfig<D>>::F;
+
+    type HashSelectFn =
+        fn(&mut CircuitBuilder<F, D>, BoolTarget, HashOutTarget, HashOutTarget) -> HashOutTarget;
+
+    /// Verifies the Merkle path of the leaf at `index` in a tree of `2^depth` random leaves,
+    /// ordering each pair of hashes with `select_hash`. Returns the number of gates used by the
+    /// circuit before it is built, and the result of verifying its proof.
+    fn prove_merkle_path(
+        depth: usize,
+        index: usize,
+        select_hash: HashSelectFn,
+    ) -> (usize, Result<()>) {
+        let leaves = (0..1 << depth).map(|_| F::rand_vec(7)).collect::<Vec<_>>();
+        let tree = MerkleTree::<F, PoseidonHash>::new(leaves.clone(), 0);
+        let proof = tree.prove(index);
+
+        let config = CircuitConfig::standard_recursion_config();
+        let mut pw = PartialWitness::new();
+        let mut builder = CircuitBuilder::<F, D>::new(config);
+
+        let leaf = builder.add_virtual_targets(leaves[index].len());
+        for (&t, &v) in leaf.iter().zip(&leaves[index]) {
+            pw.set_target(t, v);
+        }
+        let index_target = builder.add_virtual_target();
+        pw.set_target(index_target, F::from_canonical_usize(index));
+        let bits = builder.split_le(index_target, depth);
+
+        let mut current = builder.hash_or_noop::<PoseidonHash>(leaf);
+        for (&bit, &sibling) in bits.iter().zip(&proof.siblings) {
+            let sibling = builder.constant_hash(sibling);
+            let left = select_hash(&mut builder, bit, sibling, current);
+            let right = select_hash(&mut builder, bit, current, sibling);
+            let inputs = [left.elements, right.elements].concat();
+            current = builder.hash_n_to_hash_no_pad::<PoseidonHash>(inputs);
+        }
+        let root = builder.constant_hash(tree.cap.0[0]);
+        builder.connect_hashes(current, root);
+
+        let num_gates = builder.num_gates();
+        let data = builder.build::<C>();
+        let result = data
+            .prove(pw)
+            .and_then(|proof| verify(proof, &data.verifier_only, &data.common));
+        (num_gates, result)
+    }
+
+    #[test]
+    fn test_select_hash_merkle_path() -> Result<()> {
+        let (gate_rows, result) =
+            prove_merkle_path(8, 0b1011_0010, CircuitBuilder::select_hash_packed);
+        result?;
+
+        // Selecting each element of the hashes separately takes two arithmetic operations each.
+        let (elementwise_rows, result) =
+            prove_merkle_path(8, 0b1011_0010, CircuitBuilder::select_hash);
+        result?;
+
+        assert!(
+            gate_rows < elementwise_rows,
+            "{} rows with HashSelectGate, {} without",
+            gate_rows,
+            elementwise_rows
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_mux4() -> Result<()> {
+        let config = CircuitConfig::standard_recursion_config();
+        let mut pw = PartialWitness::<F>::new();
+        let mut builder = CircuitBuilder::<F, D>::new(config);
+
+        let input_values = F::rand_array::<4>();
+        let inputs = builder.add_virtual_target_arr::<4>();
+        pw.set_target_arr(inputs, input_values);
+
+        for index in 0..4 {
+            let s0 = builder.add_virtual_bool_target_safe();
+            let s1 = builder.add_virtual_bool_target_safe();
+            pw.set_bool_target(s0, index & 1 == 1);
+            pw.set_bool_target(s1, index & 2 == 2);
+            let selected = builder.mux4(s0, s1, inputs);
+            let expected = builder.constant(input_values[index]);
+            builder.connect(selected, expected);
+        }
+
+        let data = builder.build::<C>();
+        let proof = data.prove(pw)?;
+
+        verify(proof, &data.verifier_only, &data.common)
+    }
 }
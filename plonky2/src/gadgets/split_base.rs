@@ -77,6 +77,57 @@ impl<F: RichField + Extendable<D>, const D: usize> CircuitBuilder<F, D> {
 
         Target::wire(row, BaseSumGate::<2>::WIRE_SUM)
     }
+
+    /// Packs `bits` into the single field element `sum b_i * 2^i`, with a chain of arithmetic
+    /// operations. Panics if that many bits may overflow the field.
+    pub fn pack_bits(&mut self, bits: &[BoolTarget]) -> Target {
+        assert!(
+            bits.len() <= log_floor(F::ORDER, 2),
+            "{} bits may overflow the field",
+            bits.len()
+        );
+        let mut rev_bits = bits.iter().rev();
+        let mut acc = match rev_bits.next() {
+            Some(bit) => bit.target,
+            None => return self.zero(),
+        };
+        for bit in rev_bits {
+            acc = self.mul_const_add(F::TWO, acc, bit.target);
+        }
+        acc
+    }
+
+    /// The inverse of `pack_bits`: splits `x` into `num_bits` little-endian bits, which are range
+    /// checked to be boolean, and asserts that `x` fits in them. Panics if that many bits may
+    /// overflow the field.
+    pub fn unpack_bits(&mut self, x: Target, num_bits: usize) -> Vec<BoolTarget> {
+        assert!(
+            num_bits <= log_floor(F::ORDER, 2),
+            "{} bits may overflow the field",
+            num_bits
+        );
+        self.split_le(x, num_bits)
+    }
+
+    /// Asserts that `bits` is a little-endian bit decomposition of `x`, for bits which are already
+    /// known to be boolean. Bits beyond the size of the field must be zero. The recomposition is
+    /// reduced modulo the field order, so with `F::BITS` bits, a decomposition of `x + p` is
+    /// accepted as well.
+    pub fn assert_bits_of(&mut self, x: Target, bits: &[BoolTarget]) {
+        let (bits, high_bits) = bits.split_at(bits.len().min(F::BITS));
+        for bit in high_bits {
+            self.assert_zero(bit.target);
+        }
+        let mut rev_bits = bits.iter().rev();
+        let mut acc = match rev_bits.next() {
+            Some(bit) => bit.target,
+            None => self.zero(),
+        };
+        for bit in rev_bits {
+            acc = self.mul_const_add(F::TWO, acc, bit.target);
+        }
+        self.connect(acc, x);
+    }
 }
 
 #[derive(Debug, Clone)]
@@ -111,6 +162,8 @@ mod tests {
     use rand::Rng;
 
     use super::*;
+    use crate::field::goldilocks_field::GoldilocksField;
+    use crate::field::types::Field64;
     use crate::iop::witness::PartialWitness;
     use crate::plonk::circuit_data::CircuitConfig;
     use crate::plonk::config::{GenericConfig, PoseidonGoldilocksConfig};
@@ -178,4 +231,113 @@ mod tests {
 
         verify(proof, &data.verifier_only, &data.common)
     }
+
+    #[test]
+    fn test_pack_unpack_bits() -> Result<()> {
+        const D: usize = 2;
+        type C = PoseidonGoldilocksConfig;
+        type F = <C as GenericConfig<D>>::F;
+        let config = CircuitConfig::standard_recursion_config();
+        let mut pw = PartialWitness::new();
+        let mut builder = CircuitBuilder::<F, D>::new(config);
+
+        let n: u32 = OsRng.gen();
+        let host_bits = (0..32).map(|i| (n >> i) & 1 == 1).collect::<Vec<_>>();
+
+        let bits = host_bits
+            .iter()
+            .map(|&b| {
+                let t = builder.add_virtual_bool_target_safe();
+                pw.set_bool_target(t, b);
+                t
+            })
+            .collect::<Vec<_>>();
+        let packed = builder.pack_bits(&bits);
+        let expected = builder.constant(F::from_canonical_u32(n));
+        builder.connect(packed, expected);
+
+        let unpacked = builder.unpack_bits(packed, 32);
+        for (bit, host_bit) in unpacked.into_iter().zip(host_bits) {
+            let expected = builder.constant_bool(host_bit);
+            builder.connect(bit.target, expected.target);
+        }
+
+        let data = builder.build::<C>();
+        let proof = data.prove(pw)?;
+
+        verify(proof, &data.verifier_only, &data.common)
+    }
+
+    #[test]
+    #[should_panic(expected = "64 bits may overflow the field")]
+    fn test_pack_too_many_bits() {
+        const D: usize = 2;
+        type C = PoseidonGoldilocksConfig;
+        type F = <C as GenericConfig<D>>::F;
+        let config = CircuitConfig::standard_recursion_config();
+        let mut builder = CircuitBuilder::<F, D>::new(config);
+
+        let bits = (0..64)
+            .map(|_| builder.add_virtual_bool_target_safe())
+            .collect::<Vec<_>>();
+        builder.pack_bits(&bits);
+    }
+
+    fn prove_bits_of(x: u64, host_bits: &[bool]) -> Result<()> {
+        const D: usize = 2;
+        type C = PoseidonGoldilocksConfig;
+        type F = <C as GenericConfig<D>>::F;
+        let config = CircuitConfig::standard_recursion_config();
+        let mut pw = PartialWitness::new();
+        let mut builder = CircuitBuilder::<F, D>::new(config);
+
+        let x_target = builder.add_virtual_target();
+        pw.set_target(x_target, F::from_canonical_u64(x));
+        let bits = host_bits
+            .iter()
+            .map(|&b| {
+                let t = builder.add_virtual_bool_target_safe();
+                pw.set_bool_target(t, b);
+                t
+            })
+            .collect::<Vec<_>>();
+        builder.assert_bits_of(x_target, &bits);
+
+        let data = builder.build::<C>();
+        let proof = data.prove(pw)?;
+
+        verify(proof, &data.verifier_only, &data.common)
+    }
+
+    fn le_bits(x: u64, num_bits: usize) -> Vec<bool> {
+        (0..num_bits).map(|i| i < 64 && (x >> i) & 1 == 1).collect()
+    }
+
+    #[test]
+    fn test_assert_bits_of() -> Result<()> {
+        let x = OsRng.gen::<u32>() as u64;
+        prove_bits_of(x, &le_bits(x, 32))?;
+        // Bits beyond the size of the field are fine, as long as they are zero.
+        prove_bits_of(x, &le_bits(x, 70))?;
+        let x = GoldilocksField::ORDER - 1;
+        prove_bits_of(x, &le_bits(x, 64))
+    }
+
+    #[test]
+    #[should_panic(expected = "was set twice with different values")]
+    fn test_assert_bits_of_wrong_bit() {
+        let x = OsRng.gen::<u32>() as u64;
+        let mut bits = le_bits(x, 32);
+        bits[7] = !bits[7];
+        prove_bits_of(x, &bits).unwrap();
+    }
+
+    #[test]
+    #[should_panic(expected = "was set twice with different values")]
+    fn test_assert_bits_of_nonzero_high_bit() {
+        let x = OsRng.gen::<u32>() as u64;
+        let mut bits = le_bits(x, 70);
+        bits[66] = true;
+        prove_bits_of(x, &bits).unwrap();
+    }
 }
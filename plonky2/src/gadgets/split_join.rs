@@ -9,6 +9,8 @@ use crate::iop::target::{BoolTarget, Target};
 use crate::iop::witness::{PartitionWitness, Witness, WitnessWrite};
 use crate::plonk::circuit_builder::CircuitBuilder;
 use crate::util::ceil_div_usize;
+use crate::util::generator_serialization::GeneratorId;
+use crate::util::serialization::{IoResult, Read, Write};
 
 impl<F: RichField + Extendable<D>, const D: usize> CircuitBuilder<F, D> {
     /// Split the given integer into a list of wires, where each one represents a
@@ -83,7 +85,7 @@ impl<F: RichField> SimpleGenerator<F> for SplitGenerator {
 }
 
 #[derive(Debug, Clone)]
-struct WireSplitGenerator {
+pub(crate) struct WireSplitGenerator {
     integer: Target,
     gates: Vec<usize>,
     num_limbs: usize,
@@ -120,4 +122,27 @@ impl<F: RichField> SimpleGenerator<F> for WireSplitGenerator {
             self.gates.len()
         );
     }
+
+    fn id(&self) -> Option<GeneratorId> {
+        Some(GeneratorId::WireSplit)
+    }
+
+    fn serialize(&self, dst: &mut Vec<u8>) -> IoResult<()> {
+        dst.write_target(self.integer)?;
+        dst.write_usize_vec(&self.gates)?;
+        dst.write_u32(self.num_limbs as u32)
+    }
+}
+
+impl WireSplitGenerator {
+    pub(crate) fn deserialize<R: Read + ?Sized>(src: &mut R) -> IoResult<Self> {
+        let integer = src.read_target()?;
+        let gates = src.read_usize_vec()?;
+        let num_limbs = src.read_u32()? as usize;
+        Ok(Self {
+            integer,
+            gates,
+            num_limbs,
+        })
+    }
 }
@@ -19,6 +19,9 @@ use crate::plonk::vars::{
     EvaluationTargets, EvaluationVars, EvaluationVarsBase, EvaluationVarsBaseBatch,
     EvaluationVarsBasePacked,
 };
+use crate::util::gate_serialization::{read_gate_param, write_gate_param, GateId};
+use crate::util::generator_serialization::GeneratorId;
+use crate::util::serialization::{IoResult, Read, Write};
 
 /// A gate which can perform a weighted multiply-add, i.e. `result = c0 x y + c1 z`. If the config
 /// supports enough routed wires, it can support several such operations in one gate.
@@ -38,7 +41,7 @@ impl ArithmeticGate {
     /// Determine the maximum number of operations that can fit in one gate for the given config.
     pub(crate) fn num_ops(config: &CircuitConfig) -> usize {
         let wires_per_op = 4;
-        config.num_routed_wires / wires_per_op
+        config.arithmetic_ops_per_row(wires_per_op)
     }
 
     pub fn wire_ith_multiplicand_0(i: usize) -> usize {
@@ -53,6 +56,12 @@ impl ArithmeticGate {
     pub fn wire_ith_output(i: usize) -> usize {
         4 * i + 3
     }
+
+    pub(crate) fn deserialize<R: Read + ?Sized>(src: &mut R) -> IoResult<Self> {
+        Ok(Self {
+            num_ops: read_gate_param(src)?,
+        })
+    }
 }
 
 impl<F: RichField + Extendable<D>, const D: usize> Gate<F, D> for ArithmeticGate {
@@ -60,6 +69,14 @@ impl<F: RichField + Extendable<D>, const D: usize> Gate<F, D> for ArithmeticGate
         format!("{self:?}")
     }
 
+    fn serialization_id(&self) -> Option<GateId> {
+        Some(GateId::Arithmetic)
+    }
+
+    fn serialize(&self, dst: &mut Vec<u8>) -> IoResult<()> {
+        write_gate_param(dst, self.num_ops)
+    }
+
     fn export_circom_verification_code(&self) -> String {
         let mut template_str = format!(
             "template Arithmetic$NUM_OPS() {{
@@ -104,6 +121,12 @@ impl<F: RichField + Extendable<D>, const D: usize> Gate<F, D> for ArithmeticGate
         template_str = template_str.replace("$NUM_OPS", &*self.num_ops.to_string());
         template_str
     }
+    fn supports_circom_export(&self) -> bool {
+        true
+    }
+    fn supports_solidity_export(&self) -> bool {
+        true
+    }
 
     fn eval_unfiltered(&self, vars: EvaluationVars<F, D>) -> Vec<F::Extension> {
         let const_0 = vars.local_constants[0];
@@ -218,7 +241,7 @@ impl<F: RichField + Extendable<D>, const D: usize> PackedEvaluableBase<F, D> for
 }
 
 #[derive(Clone, Debug)]
-struct ArithmeticBaseGenerator<F: RichField + Extendable<D>, const D: usize> {
+pub(crate) struct ArithmeticBaseGenerator<F: RichField + Extendable<D>, const D: usize> {
     row: usize,
     const_0: F,
     const_1: F,
@@ -253,6 +276,32 @@ impl<F: RichField + Extendable<D>, const D: usize> SimpleGenerator<F>
 
         out_buffer.set_target(output_target, computed_output)
     }
+
+    fn id(&self) -> Option<GeneratorId> {
+        Some(GeneratorId::ArithmeticBase)
+    }
+
+    fn serialize(&self, dst: &mut Vec<u8>) -> IoResult<()> {
+        dst.write_u32(self.row as u32)?;
+        dst.write_field(self.const_0)?;
+        dst.write_field(self.const_1)?;
+        dst.write_u32(self.i as u32)
+    }
+}
+
+impl<F: RichField + Extendable<D>, const D: usize> ArithmeticBaseGenerator<F, D> {
+    pub(crate) fn deserialize<R: Read + ?Sized>(src: &mut R) -> IoResult<Self> {
+        let row = src.read_u32()? as usize;
+        let const_0 = src.read_field()?;
+        let const_1 = src.read_field()?;
+        let i = src.read_u32()? as usize;
+        Ok(Self {
+            row,
+            const_0,
+            const_1,
+            i,
+        })
+    }
 }
 
 #[cfg(test)]
@@ -261,7 +310,7 @@ mod tests {
 
     use crate::field::goldilocks_field::GoldilocksField;
     use crate::gates::arithmetic_base::ArithmeticGate;
-    use crate::gates::gate_testing::{test_eval_fns, test_low_degree};
+    use crate::gates::gate_testing::{fuzz_gate, test_eval_fns, test_low_degree};
     use crate::plonk::circuit_data::CircuitConfig;
     use crate::plonk::config::{GenericConfig, PoseidonGoldilocksConfig};
 
@@ -279,4 +328,13 @@ mod tests {
         let gate = ArithmeticGate::new_from_config(&CircuitConfig::standard_recursion_config());
         test_eval_fns::<F, C, _, D>(gate)
     }
+
+    #[test]
+    fn fuzz() -> Result<()> {
+        const D: usize = 2;
+        type C = PoseidonGoldilocksConfig;
+        type F = <C as GenericConfig<D>>::F;
+        let gate = ArithmeticGate::new_from_config(&CircuitConfig::standard_recursion_config());
+        fuzz_gate::<F, C, _, D>(gate, 100)
+    }
 }
@@ -5,7 +5,7 @@ use alloc::vec::Vec;
 use core::ops::Range;
 
 use crate::field::extension::{Extendable, FieldExtension};
-use crate::gates::gate::Gate;
+use crate::gates::gate::{Gate, GateCapacityError};
 use crate::gates::util::StridedConstraintConsumer;
 use crate::hash::hash_types::RichField;
 use crate::iop::ext_target::ExtensionTarget;
@@ -15,6 +15,9 @@ use crate::iop::witness::{PartitionWitness, Witness, WitnessWrite};
 use crate::plonk::circuit_builder::CircuitBuilder;
 use crate::plonk::circuit_data::CircuitConfig;
 use crate::plonk::vars::{EvaluationTargets, EvaluationVars, EvaluationVarsBase};
+use crate::util::gate_serialization::{read_gate_param, write_gate_param, GateId};
+use crate::util::generator_serialization::GeneratorId;
+use crate::util::serialization::{IoResult, Read, Write};
 
 /// A gate which can perform a weighted multiply-add, i.e. `result = c0 x y + c1 z`. If the config
 /// supports enough routed wires, it can support several such operations in one gate.
@@ -34,7 +37,26 @@ impl<const D: usize> ArithmeticExtensionGate<D> {
     /// Determine the maximum number of operations that can fit in one gate for the given config.
     pub(crate) fn num_ops(config: &CircuitConfig) -> usize {
         let wires_per_op = 4 * D;
-        config.num_routed_wires / wires_per_op
+        config.arithmetic_ops_per_row(wires_per_op)
+    }
+
+    /// The wires of the `i`th operation, as `[multiplicand_0, multiplicand_1, addend, output]`,
+    /// or an error if the gate has fewer than `i + 1` operations. Slots handed out by
+    /// `CircuitBuilder::find_slot` are always in range, so the builder indexes wires directly.
+    pub fn wires_ith_op(&self, i: usize) -> Result<[Range<usize>; 4], GateCapacityError> {
+        if i >= self.num_ops {
+            return Err(GateCapacityError {
+                gate_id: format!("{self:?}"),
+                index: i,
+                num_ops: self.num_ops,
+            });
+        }
+        Ok([
+            Self::wires_ith_multiplicand_0(i),
+            Self::wires_ith_multiplicand_1(i),
+            Self::wires_ith_addend(i),
+            Self::wires_ith_output(i),
+        ])
     }
 
     pub fn wires_ith_multiplicand_0(i: usize) -> Range<usize> {
@@ -49,6 +71,12 @@ impl<const D: usize> ArithmeticExtensionGate<D> {
     pub fn wires_ith_output(i: usize) -> Range<usize> {
         4 * D * i + 3 * D..4 * D * i + 4 * D
     }
+
+    pub(crate) fn deserialize<R: Read + ?Sized>(src: &mut R) -> IoResult<Self> {
+        Ok(Self {
+            num_ops: read_gate_param(src)?,
+        })
+    }
 }
 
 impl<F: RichField + Extendable<D>, const D: usize> Gate<F, D> for ArithmeticExtensionGate<D> {
@@ -56,6 +84,14 @@ impl<F: RichField + Extendable<D>, const D: usize> Gate<F, D> for ArithmeticExte
         format!("{self:?}")
     }
 
+    fn serialization_id(&self) -> Option<GateId> {
+        Some(GateId::ArithmeticExtension)
+    }
+
+    fn serialize(&self, dst: &mut Vec<u8>) -> IoResult<()> {
+        write_gate_param(dst, self.num_ops)
+    }
+
     fn export_circom_verification_code(&self) -> String {
         let mut template_str = format!(
             "template ArithmeticExtension$NUM_OPS() {{
@@ -106,6 +142,12 @@ impl<F: RichField + Extendable<D>, const D: usize> Gate<F, D> for ArithmeticExte
         template_str = template_str.replace("$NUM_OPS", &*self.num_ops.to_string());
         template_str
     }
+    fn supports_circom_export(&self) -> bool {
+        true
+    }
+    fn supports_solidity_export(&self) -> bool {
+        true
+    }
 
     fn eval_unfiltered(&self, vars: EvaluationVars<F, D>) -> Vec<F::Extension> {
         let const_0 = vars.local_constants[0];
@@ -208,7 +250,7 @@ impl<F: RichField + Extendable<D>, const D: usize> Gate<F, D> for ArithmeticExte
 }
 
 #[derive(Clone, Debug)]
-struct ArithmeticExtensionGenerator<F: RichField + Extendable<D>, const D: usize> {
+pub(crate) struct ArithmeticExtensionGenerator<F: RichField + Extendable<D>, const D: usize> {
     row: usize,
     const_0: F,
     const_1: F,
@@ -252,6 +294,32 @@ impl<F: RichField + Extendable<D>, const D: usize> SimpleGenerator<F>
 
         out_buffer.set_extension_target(output_target, computed_output)
     }
+
+    fn id(&self) -> Option<GeneratorId> {
+        Some(GeneratorId::ArithmeticExtension)
+    }
+
+    fn serialize(&self, dst: &mut Vec<u8>) -> IoResult<()> {
+        dst.write_u32(self.row as u32)?;
+        dst.write_field(self.const_0)?;
+        dst.write_field(self.const_1)?;
+        dst.write_u32(self.i as u32)
+    }
+}
+
+impl<F: RichField + Extendable<D>, const D: usize> ArithmeticExtensionGenerator<F, D> {
+    pub(crate) fn deserialize<R: Read + ?Sized>(src: &mut R) -> IoResult<Self> {
+        let row = src.read_u32()? as usize;
+        let const_0 = src.read_field()?;
+        let const_1 = src.read_field()?;
+        let i = src.read_u32()? as usize;
+        Ok(Self {
+            row,
+            const_0,
+            const_1,
+            i,
+        })
+    }
 }
 
 #[cfg(test)]
@@ -260,9 +328,13 @@ mod tests {
 
     use crate::field::goldilocks_field::GoldilocksField;
     use crate::gates::arithmetic_extension::ArithmeticExtensionGate;
-    use crate::gates::gate_testing::{test_eval_fns, test_low_degree};
+    use crate::gates::gate::GateCapacityError;
+    use crate::gates::gate_testing::{
+        fuzz_gate, test_eval_fns, test_gate_serialization, test_low_degree,
+    };
     use crate::plonk::circuit_data::CircuitConfig;
     use crate::plonk::config::{GenericConfig, PoseidonGoldilocksConfig};
+    use crate::util::gate_serialization::DefaultGateSerializer;
 
     #[test]
     fn low_degree() {
@@ -271,6 +343,35 @@ mod tests {
         test_low_degree::<GoldilocksField, _, 4>(gate);
     }
 
+    #[test]
+    fn wires_ith_op() {
+        const D: usize = 2;
+        let gate = ArithmeticExtensionGate::<D>::new_from_config(
+            &CircuitConfig::standard_recursion_config(),
+        );
+        let last = gate.num_ops - 1;
+        let [_, _, _, output] = gate.wires_ith_op(last).unwrap();
+        assert_eq!(output, ArithmeticExtensionGate::<D>::wires_ith_output(last));
+
+        let err = gate.wires_ith_op(gate.num_ops).unwrap_err();
+        assert_eq!(
+            err,
+            GateCapacityError {
+                gate_id: format!("{gate:?}"),
+                index: gate.num_ops,
+                num_ops: gate.num_ops,
+            }
+        );
+        assert_eq!(
+            err.to_string(),
+            format!(
+                "Operation {} does not fit in ArithmeticExtensionGate {{ num_ops: {} }}, which \
+                 has {} operation slots",
+                gate.num_ops, gate.num_ops, gate.num_ops
+            )
+        );
+    }
+
     #[test]
     fn eval_fns() -> Result<()> {
         const D: usize = 2;
@@ -280,4 +381,25 @@ mod tests {
             ArithmeticExtensionGate::new_from_config(&CircuitConfig::standard_recursion_config());
         test_eval_fns::<F, C, _, D>(gate)
     }
+
+    #[test]
+    fn fuzz() -> Result<()> {
+        const D: usize = 2;
+        type C = PoseidonGoldilocksConfig;
+        type F = <C as GenericConfig<D>>::F;
+        let gate =
+            ArithmeticExtensionGate::new_from_config(&CircuitConfig::standard_recursion_config());
+        fuzz_gate::<F, C, _, D>(gate, 100)
+    }
+
+    #[test]
+    fn serialization() -> Result<()> {
+        const D: usize = 2;
+        type C = PoseidonGoldilocksConfig;
+        type F = <C as GenericConfig<D>>::F;
+        let gate = ArithmeticExtensionGate::<D>::new_from_config(
+            &CircuitConfig::standard_recursion_config(),
+        );
+        test_gate_serialization::<F, _, _, D>(gate, &DefaultGateSerializer)
+    }
 }
@@ -22,7 +22,10 @@ use crate::plonk::vars::{
     EvaluationTargets, EvaluationVars, EvaluationVarsBase, EvaluationVarsBaseBatch,
     EvaluationVarsBasePacked,
 };
+use crate::util::gate_serialization::{read_gate_param, write_gate_param, GateId};
+use crate::util::generator_serialization::GeneratorId;
 use crate::util::log_floor;
+use crate::util::serialization::{IoResult, Read, Write};
 
 /// A gate which can decompose a number into base B little-endian limbs.
 #[derive(Copy, Clone, Debug)]
@@ -48,6 +51,10 @@ impl<const B: usize> BaseSumGate<B> {
     pub fn limbs(&self) -> Range<usize> {
         Self::START_LIMBS..Self::START_LIMBS + self.num_limbs
     }
+
+    pub(crate) fn deserialize<R: Read + ?Sized>(src: &mut R) -> IoResult<Self> {
+        Ok(Self::new(read_gate_param(src)?))
+    }
 }
 
 impl<F: RichField + Extendable<D>, const D: usize, const B: usize> Gate<F, D> for BaseSumGate<B> {
@@ -55,6 +62,15 @@ impl<F: RichField + Extendable<D>, const D: usize, const B: usize> Gate<F, D> fo
         format!("{self:?} + Base: {B}")
     }
 
+    fn serialization_id(&self) -> Option<GateId> {
+        Some(GateId::BaseSum)
+    }
+
+    fn serialize(&self, dst: &mut Vec<u8>) -> IoResult<()> {
+        dst.write_u32(B as u32)?;
+        write_gate_param(dst, self.num_limbs)
+    }
+
     fn export_circom_verification_code(&self) -> String {
         let mut template_str = format!(
             "template BaseSum$NUM_LIMBS() {{
@@ -124,6 +140,12 @@ impl<F: RichField + Extendable<D>, const D: usize, const B: usize> Gate<F, D> fo
 
         template_str
     }
+    fn supports_circom_export(&self) -> bool {
+        true
+    }
+    fn supports_solidity_export(&self) -> bool {
+        true
+    }
 
     fn eval_unfiltered(&self, vars: EvaluationVars<F, D>) -> Vec<F::Extension> {
         let sum = vars.local_wires[Self::WIRE_SUM];
@@ -265,6 +287,26 @@ impl<F: RichField, const B: usize> SimpleGenerator<F> for BaseSplitGenerator<B>
             out_buffer.set_target(b, b_value);
         }
     }
+
+    fn id(&self) -> Option<GeneratorId> {
+        Some(GeneratorId::BaseSplit)
+    }
+
+    /// Writes the base `B`, followed by the generator's fields.
+    fn serialize(&self, dst: &mut Vec<u8>) -> IoResult<()> {
+        dst.write_u32(B as u32)?;
+        dst.write_u32(self.row as u32)?;
+        dst.write_u32(self.num_limbs as u32)
+    }
+}
+
+impl<const B: usize> BaseSplitGenerator<B> {
+    /// Reads the fields of a generator written by `serialize`, after its base.
+    pub(crate) fn deserialize<R: Read + ?Sized>(src: &mut R) -> IoResult<Self> {
+        let row = src.read_u32()? as usize;
+        let num_limbs = src.read_u32()? as usize;
+        Ok(Self { row, num_limbs })
+    }
 }
 
 #[cfg(test)]
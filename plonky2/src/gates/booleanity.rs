@@ -0,0 +1,210 @@
+use alloc::boxed::Box;
+use alloc::format;
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+
+use crate::field::extension::Extendable;
+use crate::field::packed::PackedField;
+use crate::gates::gate::Gate;
+use crate::gates::packed_util::PackedEvaluableBase;
+use crate::gates::util::StridedConstraintConsumer;
+use crate::hash::hash_types::RichField;
+use crate::iop::ext_target::ExtensionTarget;
+use crate::iop::generator::WitnessGenerator;
+use crate::plonk::circuit_builder::CircuitBuilder;
+use crate::plonk::circuit_data::CircuitConfig;
+use crate::plonk::vars::{
+    EvaluationTargets, EvaluationVars, EvaluationVarsBase, EvaluationVarsBaseBatch,
+    EvaluationVarsBasePacked,
+};
+use crate::util::gate_serialization::{read_gate_param, write_gate_param, GateId};
+use crate::util::serialization::{IoResult, Read};
+
+/// A gate which enforces `x (x - 1) = 0`, i.e. that `x` is boolean, for each of its routed wires.
+/// This checks a whole row of targets at once, where `assert_bool` spends an arithmetic operation
+/// on each. Unused operations hold zero, which is boolean, so they need not be filled.
+#[derive(Debug, Clone)]
+pub struct BooleanityGate {
+    /// Number of booleanity checks performed by the gate.
+    pub num_ops: usize,
+}
+
+impl BooleanityGate {
+    pub fn new_from_config(config: &CircuitConfig) -> Self {
+        Self {
+            num_ops: Self::num_ops(config),
+        }
+    }
+
+    /// Determine the maximum number of operations that can fit in one gate for the given config.
+    pub(crate) fn num_ops(config: &CircuitConfig) -> usize {
+        config.num_routed_wires
+    }
+
+    pub fn wire_ith_input(i: usize) -> usize {
+        i
+    }
+
+    pub(crate) fn deserialize<R: Read + ?Sized>(src: &mut R) -> IoResult<Self> {
+        Ok(Self {
+            num_ops: read_gate_param(src)?,
+        })
+    }
+}
+
+impl<F: RichField + Extendable<D>, const D: usize> Gate<F, D> for BooleanityGate {
+    fn id(&self) -> String {
+        format!("{self:?}")
+    }
+
+    fn serialization_id(&self) -> Option<GateId> {
+        Some(GateId::Booleanity)
+    }
+
+    fn serialize(&self, dst: &mut Vec<u8>) -> IoResult<()> {
+        write_gate_param(dst, self.num_ops)
+    }
+
+    fn export_circom_verification_code(&self) -> String {
+        let mut template_str = "template Booleanity$NUM_OPS() {
+  signal input constants[NUM_OPENINGS_CONSTANTS()][2];
+  signal input wires[NUM_OPENINGS_WIRES()][2];
+  signal input public_input_hash[4];
+  signal input constraints[NUM_GATE_CONSTRAINTS()][2];
+  signal output out[NUM_GATE_CONSTRAINTS()][2];
+
+  signal filter[2];
+  $SET_FILTER;
+
+  for (var i = 0; i < $NUM_OPS; i++) {
+    out[i] <== ConstraintPush()(constraints[i], filter, GlExtSub()(GlExtMul()(wires[i], wires[i]), wires[i]));
+  }
+  for (var i = $NUM_OPS; i < NUM_GATE_CONSTRAINTS(); i++) {
+    out[i] <== constraints[i];
+  }
+}"
+        .to_string();
+        template_str = template_str.replace("$NUM_OPS", &self.num_ops.to_string());
+        template_str
+    }
+    fn export_solidity_verification_code(&self) -> String {
+        let mut template_str = "library Booleanity$NUM_OPSLib {
+    using GoldilocksExtLib for uint64[2];
+    function set_filter(GatesUtilsLib.EvaluationVars memory ev) internal pure {
+        $SET_FILTER;
+    }
+    function eval(GatesUtilsLib.EvaluationVars memory ev, uint64[2][$NUM_GATE_CONSTRAINTS] memory constraints) internal pure {
+        for (uint32 i = 0; i < $NUM_OPS; i++) {
+            GatesUtilsLib.push(constraints, ev.filter, i, ev.wires[i].mul(ev.wires[i]).sub(ev.wires[i]));
+        }
+    }
+}"
+        .to_string();
+        template_str = template_str.replace("$NUM_OPS", &self.num_ops.to_string());
+        template_str
+    }
+    fn supports_circom_export(&self) -> bool {
+        true
+    }
+    fn supports_solidity_export(&self) -> bool {
+        true
+    }
+
+    fn eval_unfiltered(&self, vars: EvaluationVars<F, D>) -> Vec<F::Extension> {
+        (0..self.num_ops)
+            .map(|i| {
+                let x = vars.local_wires[Self::wire_ith_input(i)];
+                x * x - x
+            })
+            .collect()
+    }
+
+    fn eval_unfiltered_base_one(
+        &self,
+        _vars: EvaluationVarsBase<F>,
+        _yield_constr: StridedConstraintConsumer<F>,
+    ) {
+        panic!("use eval_unfiltered_base_packed instead");
+    }
+
+    fn eval_unfiltered_base_batch(&self, vars_base: EvaluationVarsBaseBatch<F>) -> Vec<F> {
+        self.eval_unfiltered_base_batch_packed(vars_base)
+    }
+
+    fn eval_unfiltered_circuit(
+        &self,
+        builder: &mut CircuitBuilder<F, D>,
+        vars: EvaluationTargets<D>,
+    ) -> Vec<ExtensionTarget<D>> {
+        (0..self.num_ops)
+            .map(|i| {
+                let x = vars.local_wires[Self::wire_ith_input(i)];
+                builder.mul_sub_extension(x, x, x)
+            })
+            .collect()
+    }
+
+    fn generators(&self, _row: usize, _local_constants: &[F]) -> Vec<Box<dyn WitnessGenerator<F>>> {
+        // The gate only checks its wires, so there is nothing to generate.
+        Vec::new()
+    }
+
+    fn num_wires(&self) -> usize {
+        self.num_ops
+    }
+
+    fn num_constants(&self) -> usize {
+        0
+    }
+
+    fn degree(&self) -> usize {
+        2
+    }
+
+    fn num_constraints(&self) -> usize {
+        self.num_ops
+    }
+
+    fn num_ops(&self) -> usize {
+        self.num_ops
+    }
+}
+
+impl<F: RichField + Extendable<D>, const D: usize> PackedEvaluableBase<F, D> for BooleanityGate {
+    fn eval_unfiltered_base_packed<P: PackedField<Scalar = F>>(
+        &self,
+        vars: EvaluationVarsBasePacked<P>,
+        mut yield_constr: StridedConstraintConsumer<P>,
+    ) {
+        for i in 0..self.num_ops {
+            let x = vars.local_wires[Self::wire_ith_input(i)];
+            yield_constr.one(x * x - x);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use anyhow::Result;
+
+    use crate::field::goldilocks_field::GoldilocksField;
+    use crate::gates::booleanity::BooleanityGate;
+    use crate::gates::gate_testing::{test_eval_fns, test_low_degree};
+    use crate::plonk::circuit_data::CircuitConfig;
+    use crate::plonk::config::{GenericConfig, PoseidonGoldilocksConfig};
+
+    #[test]
+    fn low_degree() {
+        let gate = BooleanityGate::new_from_config(&CircuitConfig::standard_recursion_config());
+        test_low_degree::<GoldilocksField, _, 4>(gate);
+    }
+
+    #[test]
+    fn eval_fns() -> Result<()> {
+        const D: usize = 2;
+        type C = PoseidonGoldilocksConfig;
+        type F = <C as GenericConfig<D>>::F;
+        let gate = BooleanityGate::new_from_config(&CircuitConfig::standard_recursion_config());
+        test_eval_fns::<F, C, _, D>(gate)
+    }
+}
@@ -16,6 +16,8 @@ use crate::plonk::vars::{
     EvaluationTargets, EvaluationVars, EvaluationVarsBase, EvaluationVarsBaseBatch,
     EvaluationVarsBasePacked,
 };
+use crate::util::gate_serialization::{read_gate_param, write_gate_param, GateId};
+use crate::util::serialization::{IoResult, Read};
 
 /// A gate which takes a single constant parameter and outputs that value.
 #[derive(Copy, Clone, Debug)]
@@ -33,6 +35,12 @@ impl ConstantGate {
         debug_assert!(i < self.num_consts);
         i
     }
+
+    pub(crate) fn deserialize<R: Read + ?Sized>(src: &mut R) -> IoResult<Self> {
+        Ok(Self {
+            num_consts: read_gate_param(src)?,
+        })
+    }
 }
 
 impl<F: RichField + Extendable<D>, const D: usize> Gate<F, D> for ConstantGate {
@@ -40,6 +48,14 @@ impl<F: RichField + Extendable<D>, const D: usize> Gate<F, D> for ConstantGate {
         format!("{self:?}")
     }
 
+    fn serialization_id(&self) -> Option<GateId> {
+        Some(GateId::Constant)
+    }
+
+    fn serialize(&self, dst: &mut Vec<u8>) -> IoResult<()> {
+        write_gate_param(dst, self.num_consts)
+    }
+
     fn export_circom_verification_code(&self) -> String {
         let mut template_str = format!(
             "template Constant$NUM_CONSTANTS() {{
@@ -83,6 +99,12 @@ impl<F: RichField + Extendable<D>, const D: usize> Gate<F, D> for ConstantGate {
 
         template_str
     }
+    fn supports_circom_export(&self) -> bool {
+        true
+    }
+    fn supports_solidity_export(&self) -> bool {
+        true
+    }
 
     fn eval_unfiltered(&self, vars: EvaluationVars<F, D>) -> Vec<F::Extension> {
         (0..self.num_consts)
@@ -0,0 +1,334 @@
+use alloc::boxed::Box;
+use alloc::format;
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+
+use crate::field::extension::Extendable;
+use crate::field::packed::PackedField;
+use crate::gates::gate::Gate;
+use crate::gates::packed_util::PackedEvaluableBase;
+use crate::gates::util::StridedConstraintConsumer;
+use crate::hash::hash_types::RichField;
+use crate::iop::ext_target::ExtensionTarget;
+use crate::iop::generator::{GeneratedValues, SimpleGenerator, WitnessGenerator};
+use crate::iop::target::Target;
+use crate::iop::witness::{PartitionWitness, Witness, WitnessWrite};
+use crate::plonk::circuit_builder::CircuitBuilder;
+use crate::plonk::circuit_data::CircuitConfig;
+use crate::plonk::vars::{
+    EvaluationTargets, EvaluationVars, EvaluationVarsBase, EvaluationVarsBaseBatch,
+    EvaluationVarsBasePacked,
+};
+use crate::util::gate_serialization::{read_gate_param, write_gate_param, GateId};
+use crate::util::generator_serialization::GeneratorId;
+use crate::util::serialization::{IoResult, Read, Write};
+
+/// A gate which can perform a weighted three-term multiply-add, i.e. `result = c0 x y z + c1 w`.
+/// Counting the constant `c0`, its constraints have degree 4, one more than `ArithmeticGate`. If
+/// the config supports enough routed wires, it can support several such operations in one gate.
+#[derive(Debug, Clone)]
+pub struct CubeAddGate {
+    /// Number of operations performed by the gate.
+    pub num_ops: usize,
+}
+
+impl CubeAddGate {
+    pub fn new_from_config(config: &CircuitConfig) -> Self {
+        Self {
+            num_ops: Self::num_ops(config),
+        }
+    }
+
+    /// Determine the maximum number of operations that can fit in one gate for the given config.
+    pub(crate) fn num_ops(config: &CircuitConfig) -> usize {
+        let wires_per_op = 5;
+        config.num_routed_wires / wires_per_op
+    }
+
+    pub fn wire_ith_multiplicand_0(i: usize) -> usize {
+        5 * i
+    }
+    pub fn wire_ith_multiplicand_1(i: usize) -> usize {
+        5 * i + 1
+    }
+    pub fn wire_ith_multiplicand_2(i: usize) -> usize {
+        5 * i + 2
+    }
+    pub fn wire_ith_addend(i: usize) -> usize {
+        5 * i + 3
+    }
+    pub fn wire_ith_output(i: usize) -> usize {
+        5 * i + 4
+    }
+
+    pub(crate) fn deserialize<R: Read + ?Sized>(src: &mut R) -> IoResult<Self> {
+        Ok(Self {
+            num_ops: read_gate_param(src)?,
+        })
+    }
+}
+
+impl<F: RichField + Extendable<D>, const D: usize> Gate<F, D> for CubeAddGate {
+    fn id(&self) -> String {
+        format!("{self:?}")
+    }
+
+    fn serialization_id(&self) -> Option<GateId> {
+        Some(GateId::CubeAdd)
+    }
+
+    fn serialize(&self, dst: &mut Vec<u8>) -> IoResult<()> {
+        write_gate_param(dst, self.num_ops)
+    }
+
+    fn export_circom_verification_code(&self) -> String {
+        let mut template_str = "template CubeAdd$NUM_OPS() {
+  signal input constants[NUM_OPENINGS_CONSTANTS()][2];
+  signal input wires[NUM_OPENINGS_WIRES()][2];
+  signal input public_input_hash[4];
+  signal input constraints[NUM_GATE_CONSTRAINTS()][2];
+  signal output out[NUM_GATE_CONSTRAINTS()][2];
+
+  signal filter[2];
+  $SET_FILTER;
+
+  for (var i = 0; i < $NUM_OPS; i++) {
+    out[i] <== ConstraintPush()(constraints[i], filter, GlExtSub()(wires[5 * i + 4], GlExtAdd()(GlExtMul()(GlExtMul()(GlExtMul()(wires[5 * i], wires[5 * i + 1]), wires[5 * i + 2]), constants[$NUM_SELECTORS + 0]), GlExtMul()(wires[5 * i + 3], constants[$NUM_SELECTORS + 1]))));
+  }
+
+  for (var i = $NUM_OPS; i < NUM_GATE_CONSTRAINTS(); i++) {
+    out[i] <== constraints[i];
+  }
+}"
+        .to_string();
+        template_str = template_str.replace("$NUM_OPS", &self.num_ops.to_string());
+        template_str
+    }
+    fn export_solidity_verification_code(&self) -> String {
+        let mut template_str = "library CubeAdd$NUM_OPSLib {
+    using GoldilocksExtLib for uint64[2];
+    function set_filter(GatesUtilsLib.EvaluationVars memory ev) internal pure {
+        $SET_FILTER;
+    }
+    function eval(GatesUtilsLib.EvaluationVars memory ev, uint64[2][$NUM_GATE_CONSTRAINTS] memory constraints) internal pure {
+        for (uint32 i = 0; i < $NUM_OPS; i++) {
+            uint64[2] memory constraint;
+            constraint = ev.wires[5 * i].mul(ev.wires[5 * i + 1]).mul(ev.wires[5 * i + 2]).mul(ev.constants[$NUM_SELECTORS + 0]).add(ev.wires[5 * i + 3].mul(ev.constants[$NUM_SELECTORS + 1]));
+            GatesUtilsLib.push(constraints, ev.filter, i, ev.wires[5 * i + 4].sub(constraint));
+        }
+    }
+}"
+        .to_string();
+        template_str = template_str.replace("$NUM_OPS", &self.num_ops.to_string());
+        template_str
+    }
+    fn supports_circom_export(&self) -> bool {
+        true
+    }
+    fn supports_solidity_export(&self) -> bool {
+        true
+    }
+
+    fn eval_unfiltered(&self, vars: EvaluationVars<F, D>) -> Vec<F::Extension> {
+        let const_0 = vars.local_constants[0];
+        let const_1 = vars.local_constants[1];
+
+        (0..self.num_ops)
+            .map(|i| {
+                let multiplicand_0 = vars.local_wires[Self::wire_ith_multiplicand_0(i)];
+                let multiplicand_1 = vars.local_wires[Self::wire_ith_multiplicand_1(i)];
+                let multiplicand_2 = vars.local_wires[Self::wire_ith_multiplicand_2(i)];
+                let addend = vars.local_wires[Self::wire_ith_addend(i)];
+                let output = vars.local_wires[Self::wire_ith_output(i)];
+                let computed_output =
+                    multiplicand_0 * multiplicand_1 * multiplicand_2 * const_0 + addend * const_1;
+                output - computed_output
+            })
+            .collect()
+    }
+
+    fn eval_unfiltered_base_one(
+        &self,
+        _vars: EvaluationVarsBase<F>,
+        _yield_constr: StridedConstraintConsumer<F>,
+    ) {
+        panic!("use eval_unfiltered_base_packed instead");
+    }
+
+    fn eval_unfiltered_base_batch(&self, vars_base: EvaluationVarsBaseBatch<F>) -> Vec<F> {
+        self.eval_unfiltered_base_batch_packed(vars_base)
+    }
+
+    fn eval_unfiltered_circuit(
+        &self,
+        builder: &mut CircuitBuilder<F, D>,
+        vars: EvaluationTargets<D>,
+    ) -> Vec<ExtensionTarget<D>> {
+        let const_0 = vars.local_constants[0];
+        let const_1 = vars.local_constants[1];
+
+        (0..self.num_ops)
+            .map(|i| {
+                let multiplicand_0 = vars.local_wires[Self::wire_ith_multiplicand_0(i)];
+                let multiplicand_1 = vars.local_wires[Self::wire_ith_multiplicand_1(i)];
+                let multiplicand_2 = vars.local_wires[Self::wire_ith_multiplicand_2(i)];
+                let addend = vars.local_wires[Self::wire_ith_addend(i)];
+                let output = vars.local_wires[Self::wire_ith_output(i)];
+                let scaled_mul = builder.mul_many_extension([
+                    const_0,
+                    multiplicand_0,
+                    multiplicand_1,
+                    multiplicand_2,
+                ]);
+                let computed_output = builder.mul_add_extension(const_1, addend, scaled_mul);
+                builder.sub_extension(output, computed_output)
+            })
+            .collect()
+    }
+
+    fn generators(&self, row: usize, local_constants: &[F]) -> Vec<Box<dyn WitnessGenerator<F>>> {
+        (0..self.num_ops)
+            .map(|i| {
+                let g: Box<dyn WitnessGenerator<F>> = Box::new(
+                    CubeAddGenerator {
+                        row,
+                        const_0: local_constants[0],
+                        const_1: local_constants[1],
+                        i,
+                    }
+                    .adapter(),
+                );
+                g
+            })
+            .collect()
+    }
+
+    fn num_wires(&self) -> usize {
+        self.num_ops * 5
+    }
+
+    fn num_constants(&self) -> usize {
+        2
+    }
+
+    fn degree(&self) -> usize {
+        4
+    }
+
+    fn num_constraints(&self) -> usize {
+        self.num_ops
+    }
+}
+
+impl<F: RichField + Extendable<D>, const D: usize> PackedEvaluableBase<F, D> for CubeAddGate {
+    fn eval_unfiltered_base_packed<P: PackedField<Scalar = F>>(
+        &self,
+        vars: EvaluationVarsBasePacked<P>,
+        mut yield_constr: StridedConstraintConsumer<P>,
+    ) {
+        let const_0 = vars.local_constants[0];
+        let const_1 = vars.local_constants[1];
+
+        for i in 0..self.num_ops {
+            let multiplicand_0 = vars.local_wires[Self::wire_ith_multiplicand_0(i)];
+            let multiplicand_1 = vars.local_wires[Self::wire_ith_multiplicand_1(i)];
+            let multiplicand_2 = vars.local_wires[Self::wire_ith_multiplicand_2(i)];
+            let addend = vars.local_wires[Self::wire_ith_addend(i)];
+            let output = vars.local_wires[Self::wire_ith_output(i)];
+            let computed_output =
+                multiplicand_0 * multiplicand_1 * multiplicand_2 * const_0 + addend * const_1;
+
+            yield_constr.one(output - computed_output);
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+pub(crate) struct CubeAddGenerator<F: RichField> {
+    row: usize,
+    const_0: F,
+    const_1: F,
+    i: usize,
+}
+
+impl<F: RichField> SimpleGenerator<F> for CubeAddGenerator<F> {
+    fn dependencies(&self) -> Vec<Target> {
+        [
+            CubeAddGate::wire_ith_multiplicand_0(self.i),
+            CubeAddGate::wire_ith_multiplicand_1(self.i),
+            CubeAddGate::wire_ith_multiplicand_2(self.i),
+            CubeAddGate::wire_ith_addend(self.i),
+        ]
+        .iter()
+        .map(|&i| Target::wire(self.row, i))
+        .collect()
+    }
+
+    fn run_once(&self, witness: &PartitionWitness<F>, out_buffer: &mut GeneratedValues<F>) {
+        let get_wire = |wire: usize| -> F { witness.get_target(Target::wire(self.row, wire)) };
+
+        let multiplicand_0 = get_wire(CubeAddGate::wire_ith_multiplicand_0(self.i));
+        let multiplicand_1 = get_wire(CubeAddGate::wire_ith_multiplicand_1(self.i));
+        let multiplicand_2 = get_wire(CubeAddGate::wire_ith_multiplicand_2(self.i));
+        let addend = get_wire(CubeAddGate::wire_ith_addend(self.i));
+
+        let output_target = Target::wire(self.row, CubeAddGate::wire_ith_output(self.i));
+
+        let computed_output =
+            multiplicand_0 * multiplicand_1 * multiplicand_2 * self.const_0 + addend * self.const_1;
+
+        out_buffer.set_target(output_target, computed_output)
+    }
+
+    fn id(&self) -> Option<GeneratorId> {
+        Some(GeneratorId::CubeAdd)
+    }
+
+    fn serialize(&self, dst: &mut Vec<u8>) -> IoResult<()> {
+        dst.write_u32(self.row as u32)?;
+        dst.write_field(self.const_0)?;
+        dst.write_field(self.const_1)?;
+        dst.write_u32(self.i as u32)
+    }
+}
+
+impl<F: RichField> CubeAddGenerator<F> {
+    pub(crate) fn deserialize<R: Read + ?Sized>(src: &mut R) -> IoResult<Self> {
+        let row = src.read_u32()? as usize;
+        let const_0 = src.read_field()?;
+        let const_1 = src.read_field()?;
+        let i = src.read_u32()? as usize;
+        Ok(Self {
+            row,
+            const_0,
+            const_1,
+            i,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use anyhow::Result;
+
+    use crate::field::goldilocks_field::GoldilocksField;
+    use crate::gates::cube_add::CubeAddGate;
+    use crate::gates::gate_testing::{test_eval_fns, test_low_degree};
+    use crate::plonk::circuit_data::CircuitConfig;
+    use crate::plonk::config::{GenericConfig, PoseidonGoldilocksConfig};
+
+    #[test]
+    fn low_degree() {
+        let gate = CubeAddGate::new_from_config(&CircuitConfig::standard_recursion_config());
+        test_low_degree::<GoldilocksField, _, 4>(gate);
+    }
+
+    #[test]
+    fn eval_fns() -> Result<()> {
+        const D: usize = 2;
+        type C = PoseidonGoldilocksConfig;
+        type F = <C as GenericConfig<D>>::F;
+        let gate = CubeAddGate::new_from_config(&CircuitConfig::standard_recursion_config());
+        test_eval_fns::<F, C, _, D>(gate)
+    }
+}
@@ -0,0 +1,271 @@
+use alloc::boxed::Box;
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+use alloc::{format, vec};
+
+use crate::field::extension::Extendable;
+use crate::field::packed::PackedField;
+use crate::gates::gate::Gate;
+use crate::gates::packed_util::PackedEvaluableBase;
+use crate::gates::util::StridedConstraintConsumer;
+use crate::hash::hash_types::RichField;
+use crate::iop::ext_target::ExtensionTarget;
+use crate::iop::generator::{GeneratedValues, SimpleGenerator, WitnessGenerator};
+use crate::iop::target::Target;
+use crate::iop::witness::{PartitionWitness, Witness, WitnessWrite};
+use crate::plonk::circuit_builder::CircuitBuilder;
+use crate::plonk::circuit_data::CircuitConfig;
+use crate::plonk::vars::{
+    EvaluationTargets, EvaluationVars, EvaluationVarsBase, EvaluationVarsBaseBatch,
+    EvaluationVarsBasePacked,
+};
+use crate::util::gate_serialization::{read_gate_param, write_gate_param, GateId};
+use crate::util::generator_serialization::GeneratorId;
+use crate::util::serialization::{IoResult, Read, Write};
+
+/// A gate which computes differences of squares of base field elements, enforcing
+/// `output = a^2 - b^2`. If the config supports enough routed wires, it can support several such
+/// operations in one gate.
+#[derive(Debug, Clone)]
+pub struct DiffOfSquaresGate {
+    /// Number of differences computed by the gate.
+    pub num_ops: usize,
+}
+
+impl DiffOfSquaresGate {
+    pub fn new_from_config(config: &CircuitConfig) -> Self {
+        Self {
+            num_ops: Self::num_ops(config),
+        }
+    }
+
+    /// Determine the maximum number of operations that can fit in one gate for the given config.
+    pub(crate) fn num_ops(config: &CircuitConfig) -> usize {
+        let wires_per_op = 3;
+        config.num_routed_wires / wires_per_op
+    }
+
+    pub fn wire_ith_a(i: usize) -> usize {
+        3 * i
+    }
+    pub fn wire_ith_b(i: usize) -> usize {
+        3 * i + 1
+    }
+    pub fn wire_ith_output(i: usize) -> usize {
+        3 * i + 2
+    }
+
+    pub(crate) fn deserialize<R: Read + ?Sized>(src: &mut R) -> IoResult<Self> {
+        Ok(Self {
+            num_ops: read_gate_param(src)?,
+        })
+    }
+}
+
+impl<F: RichField + Extendable<D>, const D: usize> Gate<F, D> for DiffOfSquaresGate {
+    fn id(&self) -> String {
+        format!("{self:?}")
+    }
+
+    fn serialization_id(&self) -> Option<GateId> {
+        Some(GateId::DiffOfSquares)
+    }
+
+    fn serialize(&self, dst: &mut Vec<u8>) -> IoResult<()> {
+        write_gate_param(dst, self.num_ops)
+    }
+
+    fn export_circom_verification_code(&self) -> String {
+        let mut template_str = "template DiffOfSquares$NUM_OPS() {
+  signal input constants[NUM_OPENINGS_CONSTANTS()][2];
+  signal input wires[NUM_OPENINGS_WIRES()][2];
+  signal input public_input_hash[4];
+  signal input constraints[NUM_GATE_CONSTRAINTS()][2];
+  signal output out[NUM_GATE_CONSTRAINTS()][2];
+
+  signal filter[2];
+  $SET_FILTER;
+
+  for (var i = 0; i < $NUM_OPS; i++) {
+    out[i] <== ConstraintPush()(constraints[i], filter, GlExtSub()(wires[3 * i + 2], GlExtSub()(GlExtMul()(wires[3 * i], wires[3 * i]), GlExtMul()(wires[3 * i + 1], wires[3 * i + 1]))));
+  }
+  for (var i = $NUM_OPS; i < NUM_GATE_CONSTRAINTS(); i++) {
+    out[i] <== constraints[i];
+  }
+}"
+        .to_string();
+        template_str = template_str.replace("$NUM_OPS", &self.num_ops.to_string());
+        template_str
+    }
+    fn export_solidity_verification_code(&self) -> String {
+        let mut template_str = "library DiffOfSquares$NUM_OPSLib {
+    using GoldilocksExtLib for uint64[2];
+    function set_filter(GatesUtilsLib.EvaluationVars memory ev) internal pure {
+        $SET_FILTER;
+    }
+    function eval(GatesUtilsLib.EvaluationVars memory ev, uint64[2][$NUM_GATE_CONSTRAINTS] memory constraints) internal pure {
+        for (uint32 i = 0; i < $NUM_OPS; i++) {
+            GatesUtilsLib.push(constraints, ev.filter, i, ev.wires[3 * i + 2].sub(ev.wires[3 * i].mul(ev.wires[3 * i]).sub(ev.wires[3 * i + 1].mul(ev.wires[3 * i + 1]))));
+        }
+    }
+}"
+        .to_string();
+        template_str = template_str.replace("$NUM_OPS", &self.num_ops.to_string());
+        template_str
+    }
+    fn supports_circom_export(&self) -> bool {
+        true
+    }
+    fn supports_solidity_export(&self) -> bool {
+        true
+    }
+
+    fn eval_unfiltered(&self, vars: EvaluationVars<F, D>) -> Vec<F::Extension> {
+        (0..self.num_ops)
+            .map(|i| {
+                let a = vars.local_wires[Self::wire_ith_a(i)];
+                let b = vars.local_wires[Self::wire_ith_b(i)];
+                let output = vars.local_wires[Self::wire_ith_output(i)];
+                output - (a * a - b * b)
+            })
+            .collect()
+    }
+
+    fn eval_unfiltered_base_one(
+        &self,
+        _vars: EvaluationVarsBase<F>,
+        _yield_constr: StridedConstraintConsumer<F>,
+    ) {
+        panic!("use eval_unfiltered_base_packed instead");
+    }
+
+    fn eval_unfiltered_base_batch(&self, vars_base: EvaluationVarsBaseBatch<F>) -> Vec<F> {
+        self.eval_unfiltered_base_batch_packed(vars_base)
+    }
+
+    fn eval_unfiltered_circuit(
+        &self,
+        builder: &mut CircuitBuilder<F, D>,
+        vars: EvaluationTargets<D>,
+    ) -> Vec<ExtensionTarget<D>> {
+        (0..self.num_ops)
+            .map(|i| {
+                let a = vars.local_wires[Self::wire_ith_a(i)];
+                let b = vars.local_wires[Self::wire_ith_b(i)];
+                let output = vars.local_wires[Self::wire_ith_output(i)];
+                let b_squared = builder.mul_extension(b, b);
+                let computed_output = builder.mul_sub_extension(a, a, b_squared);
+                builder.sub_extension(output, computed_output)
+            })
+            .collect()
+    }
+
+    fn generators(&self, row: usize, _local_constants: &[F]) -> Vec<Box<dyn WitnessGenerator<F>>> {
+        (0..self.num_ops)
+            .map(|i| {
+                let g: Box<dyn WitnessGenerator<F>> =
+                    Box::new(DiffOfSquaresGenerator { row, i }.adapter());
+                g
+            })
+            .collect()
+    }
+
+    fn num_wires(&self) -> usize {
+        self.num_ops * 3
+    }
+
+    fn num_constants(&self) -> usize {
+        0
+    }
+
+    fn degree(&self) -> usize {
+        2
+    }
+
+    fn num_constraints(&self) -> usize {
+        self.num_ops
+    }
+}
+
+impl<F: RichField + Extendable<D>, const D: usize> PackedEvaluableBase<F, D> for DiffOfSquaresGate {
+    fn eval_unfiltered_base_packed<P: PackedField<Scalar = F>>(
+        &self,
+        vars: EvaluationVarsBasePacked<P>,
+        mut yield_constr: StridedConstraintConsumer<P>,
+    ) {
+        for i in 0..self.num_ops {
+            let a = vars.local_wires[Self::wire_ith_a(i)];
+            let b = vars.local_wires[Self::wire_ith_b(i)];
+            let output = vars.local_wires[Self::wire_ith_output(i)];
+            yield_constr.one(output - (a * a - b * b));
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+pub(crate) struct DiffOfSquaresGenerator {
+    row: usize,
+    i: usize,
+}
+
+impl<F: RichField> SimpleGenerator<F> for DiffOfSquaresGenerator {
+    fn dependencies(&self) -> Vec<Target> {
+        vec![
+            Target::wire(self.row, DiffOfSquaresGate::wire_ith_a(self.i)),
+            Target::wire(self.row, DiffOfSquaresGate::wire_ith_b(self.i)),
+        ]
+    }
+
+    fn run_once(&self, witness: &PartitionWitness<F>, out_buffer: &mut GeneratedValues<F>) {
+        let get_wire = |wire: usize| -> F { witness.get_target(Target::wire(self.row, wire)) };
+
+        let a = get_wire(DiffOfSquaresGate::wire_ith_a(self.i));
+        let b = get_wire(DiffOfSquaresGate::wire_ith_b(self.i));
+        let output_target = Target::wire(self.row, DiffOfSquaresGate::wire_ith_output(self.i));
+
+        out_buffer.set_target(output_target, a * a - b * b)
+    }
+
+    fn id(&self) -> Option<GeneratorId> {
+        Some(GeneratorId::DiffOfSquares)
+    }
+
+    fn serialize(&self, dst: &mut Vec<u8>) -> IoResult<()> {
+        dst.write_u32(self.row as u32)?;
+        dst.write_u32(self.i as u32)
+    }
+}
+
+impl DiffOfSquaresGenerator {
+    pub(crate) fn deserialize<R: Read + ?Sized>(src: &mut R) -> IoResult<Self> {
+        let row = src.read_u32()? as usize;
+        let i = src.read_u32()? as usize;
+        Ok(Self { row, i })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use anyhow::Result;
+
+    use crate::field::goldilocks_field::GoldilocksField;
+    use crate::gates::diff_of_squares::DiffOfSquaresGate;
+    use crate::gates::gate_testing::{test_eval_fns, test_low_degree};
+    use crate::plonk::circuit_data::CircuitConfig;
+    use crate::plonk::config::{GenericConfig, PoseidonGoldilocksConfig};
+
+    #[test]
+    fn low_degree() {
+        let gate = DiffOfSquaresGate::new_from_config(&CircuitConfig::standard_recursion_config());
+        test_low_degree::<GoldilocksField, _, 4>(gate);
+    }
+
+    #[test]
+    fn eval_fns() -> Result<()> {
+        const D: usize = 2;
+        type C = PoseidonGoldilocksConfig;
+        type F = <C as GenericConfig<D>>::F;
+        let gate = DiffOfSquaresGate::new_from_config(&CircuitConfig::standard_recursion_config());
+        test_eval_fns::<F, C, _, D>(gate)
+    }
+}
@@ -23,6 +23,8 @@ use crate::plonk::vars::{
     EvaluationTargets, EvaluationVars, EvaluationVarsBase, EvaluationVarsBaseBatch,
     EvaluationVarsBasePacked,
 };
+use crate::util::gate_serialization::{read_gate_param, write_gate_param, GateId};
+use crate::util::serialization::{IoResult, Read};
 
 /// A gate for raising a value to a power.
 #[derive(Clone, Debug)]
@@ -69,6 +71,10 @@ impl<F: RichField + Extendable<D>, const D: usize> ExponentiationGate<F, D> {
         debug_assert!(i < self.num_power_bits);
         2 + self.num_power_bits + i
     }
+
+    pub(crate) fn deserialize<R: Read + ?Sized>(src: &mut R) -> IoResult<Self> {
+        Ok(Self::new(read_gate_param(src)?))
+    }
 }
 
 impl<F: RichField + Extendable<D>, const D: usize> Gate<F, D> for ExponentiationGate<F, D> {
@@ -76,6 +82,14 @@ impl<F: RichField + Extendable<D>, const D: usize> Gate<F, D> for Exponentiation
         format!("{self:?}<D={D}>")
     }
 
+    fn serialization_id(&self) -> Option<GateId> {
+        Some(GateId::Exponentiation)
+    }
+
+    fn serialize(&self, dst: &mut Vec<u8>) -> IoResult<()> {
+        write_gate_param(dst, self.num_power_bits)
+    }
+
     fn export_circom_verification_code(&self) -> String {
         let mut template_str = format!(
         "template Exponentiation$NUM_POWER_BITS() {{
@@ -146,6 +160,12 @@ impl<F: RichField + Extendable<D>, const D: usize> Gate<F, D> for Exponentiation
         template_str = template_str.replace("$NUM_POWER_BITS", &*self.num_power_bits.to_string());
         template_str
     }
+    fn supports_circom_export(&self) -> bool {
+        true
+    }
+    fn supports_solidity_export(&self) -> bool {
+        true
+    }
 
     fn eval_unfiltered(&self, vars: EvaluationVars<F, D>) -> Vec<F::Extension> {
         let base = vars.local_wires[self.wire_base()];
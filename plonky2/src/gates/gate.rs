@@ -3,7 +3,7 @@ use alloc::string::String;
 use alloc::sync::Arc;
 use alloc::vec;
 use alloc::vec::Vec;
-use core::fmt::{Debug, Error, Formatter};
+use core::fmt::{Debug, Display, Error, Formatter};
 use core::hash::{Hash, Hasher};
 use core::ops::Range;
 
@@ -21,6 +21,8 @@ use crate::plonk::circuit_builder::CircuitBuilder;
 use crate::plonk::vars::{
     EvaluationTargets, EvaluationVars, EvaluationVarsBase, EvaluationVarsBaseBatch,
 };
+use crate::util::gate_serialization::GateId;
+use crate::util::serialization::{IoError, IoResult};
 
 /// A custom gate.
 pub trait Gate<F: RichField + Extendable<D>, const D: usize>: 'static + Send + Sync {
@@ -29,6 +31,30 @@ pub trait Gate<F: RichField + Extendable<D>, const D: usize>: 'static + Send + S
     fn export_circom_verification_code(&self) -> String;
     fn export_solidity_verification_code(&self) -> String;
 
+    /// Whether `export_circom_verification_code` is implemented, rather than a placeholder. Gates
+    /// opt in by overriding this, so that a placeholder is never reported as supported.
+    fn supports_circom_export(&self) -> bool {
+        false
+    }
+
+    /// Whether `export_solidity_verification_code` is implemented, rather than a placeholder; see
+    /// `supports_circom_export`.
+    fn supports_solidity_export(&self) -> bool {
+        false
+    }
+
+    /// The type of this gate, used to serialize it, or `None` if it does not support
+    /// serialization.
+    fn serialization_id(&self) -> Option<GateId> {
+        None
+    }
+
+    /// Writes the parameters of this gate to `dst`, such that a `GateSerializer` which knows its
+    /// `serialization_id` can reconstruct it.
+    fn serialize(&self, _dst: &mut Vec<u8>) -> IoResult<()> {
+        Err(IoError)
+    }
+
     fn eval_unfiltered(&self, vars: EvaluationVars<F, D>) -> Vec<F::Extension>;
 
     /// Like `eval_unfiltered`, but specialized for points in the base field.
@@ -232,6 +258,26 @@ pub struct CurrentSlot<F: RichField + Extendable<D>, const D: usize> {
     pub current_slot: HashMap<Vec<F>, (usize, usize)>,
 }
 
+/// An error indicating that an operation index doesn't fit in a gate's operation slots.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct GateCapacityError {
+    pub gate_id: String,
+    /// The requested operation index.
+    pub index: usize,
+    /// The number of operations which fit in one row of the gate.
+    pub num_ops: usize,
+}
+
+impl Display for GateCapacityError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        write!(
+            f,
+            "Operation {} does not fit in {}, which has {} operation slots",
+            self.index, self.gate_id, self.num_ops
+        )
+    }
+}
+
 /// A gate along with any constants used to configure it.
 #[derive(Clone)]
 pub struct GateInstance<F: RichField + Extendable<D>, const D: usize> {
@@ -1,19 +1,23 @@
 use alloc::vec;
 use alloc::vec::Vec;
 
-use anyhow::{ensure, Result};
+use anyhow::{anyhow, ensure, Result};
+use itertools::Itertools;
 
 use crate::field::extension::{Extendable, FieldExtension};
 use crate::field::polynomial::{PolynomialCoeffs, PolynomialValues};
 use crate::field::types::{Field, Sample};
-use crate::gates::gate::Gate;
+use crate::gates::gate::{Gate, GateRef};
 use crate::hash::hash_types::{HashOut, RichField};
-use crate::iop::witness::{PartialWitness, WitnessWrite};
+use crate::iop::generator::generate_partial_witness;
+use crate::iop::witness::{PartialWitness, Witness, WitnessWrite};
 use crate::plonk::circuit_builder::CircuitBuilder;
 use crate::plonk::circuit_data::CircuitConfig;
 use crate::plonk::config::GenericConfig;
 use crate::plonk::vars::{EvaluationTargets, EvaluationVars, EvaluationVarsBaseBatch};
 use crate::plonk::verifier::verify;
+use crate::util::gate_serialization::GateSerializer;
+use crate::util::serialization::{Buffer, Remaining};
 use crate::util::{log2_ceil, transpose};
 
 const WITNESS_SIZE: usize = 1 << 5;
@@ -161,3 +165,146 @@ pub fn test_eval_fns<
     let proof = data.prove(pw)?;
     verify(proof, &data.verifier_only, &data.common)
 }
+
+/// Evaluates the constraints of the given gate on `num_cases` random inputs through each of
+/// `eval_unfiltered`, `eval_unfiltered_base_batch` and `eval_unfiltered_circuit`, and checks that
+/// the results agree. Unlike `test_eval_fns`, this reports the case and constraint on which two
+/// evaluation paths disagree.
+pub fn fuzz_gate<
+    F: RichField + Extendable<D>,
+    C: GenericConfig<D, F = F>,
+    G: Gate<F, D>,
+    const D: usize,
+>(
+    gate: G,
+    num_cases: usize,
+) -> Result<()> {
+    let public_inputs_hash = HashOut::rand();
+
+    // Compare `eval_unfiltered_base_batch` against `eval_unfiltered` on base field inputs. Gates
+    // evaluated through `PackedEvaluableBase` do not support `eval_unfiltered_base_one`, so we go
+    // through the batch path, which every gate supports.
+    for case in 0..num_cases {
+        let wires_base = F::rand_vec(gate.num_wires());
+        let constants_base = F::rand_vec(gate.num_constants());
+        let wires = wires_base
+            .iter()
+            .map(|&x| F::Extension::from_basefield(x))
+            .collect::<Vec<_>>();
+        let constants = constants_base
+            .iter()
+            .map(|&x| F::Extension::from_basefield(x))
+            .collect::<Vec<_>>();
+
+        let evals = gate.eval_unfiltered(EvaluationVars {
+            local_constants: &constants,
+            local_wires: &wires,
+            public_inputs_hash: &public_inputs_hash,
+        });
+        let vars_base_batch =
+            EvaluationVarsBaseBatch::new(1, &constants_base, &wires_base, &public_inputs_hash);
+        // With a batch of 1, the evaluations are not interleaved.
+        let evals_base = gate.eval_unfiltered_base_batch(vars_base_batch);
+
+        for (i, (&eval, &eval_base)) in evals.iter().zip_eq(&evals_base).enumerate() {
+            ensure!(
+                eval == F::Extension::from_basefield(eval_base),
+                "Case {}: eval_unfiltered and eval_unfiltered_base_batch disagree on constraint {}",
+                case,
+                i
+            );
+        }
+    }
+
+    // Compare `eval_unfiltered_circuit` against `eval_unfiltered` on extension field inputs,
+    // evaluating all cases in one circuit.
+    let config = CircuitConfig::standard_recursion_config();
+    let mut pw = PartialWitness::new();
+    let mut builder = CircuitBuilder::<F, D>::new(config);
+    let public_inputs_hash_t = builder.add_virtual_hash();
+    pw.set_hash_target(public_inputs_hash_t, public_inputs_hash);
+
+    let mut cases = Vec::with_capacity(num_cases);
+    for _ in 0..num_cases {
+        let wires = F::Extension::rand_vec(gate.num_wires());
+        let constants = F::Extension::rand_vec(gate.num_constants());
+        let wires_t = builder.add_virtual_extension_targets(wires.len());
+        let constants_t = builder.add_virtual_extension_targets(constants.len());
+        pw.set_extension_targets(&wires_t, &wires);
+        pw.set_extension_targets(&constants_t, &constants);
+
+        let evals = gate.eval_unfiltered(EvaluationVars {
+            local_constants: &constants,
+            local_wires: &wires,
+            public_inputs_hash: &public_inputs_hash,
+        });
+        let evals_t = gate.eval_unfiltered_circuit(
+            &mut builder,
+            EvaluationTargets {
+                local_constants: &constants_t,
+                local_wires: &wires_t,
+                public_inputs_hash: &public_inputs_hash_t,
+            },
+        );
+        cases.push((evals, evals_t));
+    }
+
+    let data = builder.build::<C>();
+    let witness = generate_partial_witness(pw, &data.prover_only, &data.common);
+    for (case, (evals, evals_t)) in cases.into_iter().enumerate() {
+        for (i, (eval, eval_t)) in evals.into_iter().zip_eq(evals_t).enumerate() {
+            ensure!(
+                eval == witness.get_extension_target(eval_t),
+                "Case {}: eval_unfiltered and eval_unfiltered_circuit disagree on constraint {}",
+                case,
+                i
+            );
+        }
+    }
+
+    Ok(())
+}
+
+/// Writes the given gate with `serializer`, reads it back, and checks that the result has the same
+/// id and number of constraints, and that reading consumed everything that was written. This
+/// catches serializers whose reading and writing of a custom gate disagree.
+pub fn test_gate_serialization<
+    F: RichField + Extendable<D>,
+    G: Gate<F, D>,
+    S: GateSerializer<F, D>,
+    const D: usize,
+>(
+    gate: G,
+    serializer: &S,
+) -> Result<()> {
+    let gate = GateRef::new(gate);
+    let mut bytes = Vec::new();
+    serializer
+        .write_gate(&mut bytes, &gate)
+        .map_err(|_| anyhow!("Failed to serialize {}", gate.0.id()))?;
+
+    let mut buffer = Buffer::new(bytes);
+    let read: GateRef<F, D> = serializer
+        .read_gate(&mut buffer)
+        .map_err(|_| anyhow!("Failed to deserialize {}", gate.0.id()))?;
+    ensure!(
+        buffer.is_empty(),
+        "{} bytes were left unread after deserializing {}",
+        buffer.remaining(),
+        gate.0.id()
+    );
+    ensure!(
+        read.0.id() == gate.0.id(),
+        "Deserialized {} as {}",
+        gate.0.id(),
+        read.0.id()
+    );
+    ensure!(
+        read.0.num_constraints() == gate.0.num_constraints(),
+        "Deserialized {} with {} constraints, expected {}",
+        gate.0.id(),
+        read.0.num_constraints(),
+        gate.0.num_constraints()
+    );
+    Ok(())
+}
@@ -0,0 +1,306 @@
+use alloc::boxed::Box;
+use alloc::format;
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+
+use crate::field::extension::Extendable;
+use crate::field::packed::PackedField;
+use crate::gates::gate::Gate;
+use crate::gates::packed_util::PackedEvaluableBase;
+use crate::gates::util::StridedConstraintConsumer;
+use crate::hash::hash_types::{RichField, NUM_HASH_OUT_ELTS};
+use crate::iop::ext_target::ExtensionTarget;
+use crate::iop::generator::{GeneratedValues, SimpleGenerator, WitnessGenerator};
+use crate::iop::target::Target;
+use crate::iop::witness::{PartitionWitness, Witness, WitnessWrite};
+use crate::plonk::circuit_builder::CircuitBuilder;
+use crate::plonk::circuit_data::CircuitConfig;
+use crate::plonk::vars::{
+    EvaluationTargets, EvaluationVars, EvaluationVarsBase, EvaluationVarsBaseBatch,
+    EvaluationVarsBasePacked,
+};
+use crate::util::gate_serialization::{read_gate_param, write_gate_param, GateId};
+use crate::util::generator_serialization::GeneratorId;
+use crate::util::serialization::{IoResult, Read, Write};
+
+/// A gate which selects between two hashes, enforcing `output = cond * (a - b) + b` for each of
+/// their elements. `cond` is assumed to be boolean; the gate does not check it. If the config
+/// supports enough routed wires, it can support several such selections in one gate.
+#[derive(Debug, Clone)]
+pub struct HashSelectGate {
+    /// Number of selections performed by the gate.
+    pub num_ops: usize,
+}
+
+impl HashSelectGate {
+    const WIRES_PER_OP: usize = 1 + 3 * NUM_HASH_OUT_ELTS;
+
+    pub fn new_from_config(config: &CircuitConfig) -> Self {
+        Self {
+            num_ops: Self::num_ops(config),
+        }
+    }
+
+    /// Determine the maximum number of operations that can fit in one gate for the given config.
+    pub(crate) fn num_ops(config: &CircuitConfig) -> usize {
+        config.num_routed_wires / Self::WIRES_PER_OP
+    }
+
+    pub fn wire_ith_cond(i: usize) -> usize {
+        Self::WIRES_PER_OP * i
+    }
+    pub fn wire_ith_a(i: usize, j: usize) -> usize {
+        debug_assert!(j < NUM_HASH_OUT_ELTS);
+        Self::WIRES_PER_OP * i + 1 + j
+    }
+    pub fn wire_ith_b(i: usize, j: usize) -> usize {
+        debug_assert!(j < NUM_HASH_OUT_ELTS);
+        Self::WIRES_PER_OP * i + 1 + NUM_HASH_OUT_ELTS + j
+    }
+    pub fn wire_ith_output(i: usize, j: usize) -> usize {
+        debug_assert!(j < NUM_HASH_OUT_ELTS);
+        Self::WIRES_PER_OP * i + 1 + 2 * NUM_HASH_OUT_ELTS + j
+    }
+
+    pub(crate) fn deserialize<R: Read + ?Sized>(src: &mut R) -> IoResult<Self> {
+        Ok(Self {
+            num_ops: read_gate_param(src)?,
+        })
+    }
+}
+
+impl<F: RichField + Extendable<D>, const D: usize> Gate<F, D> for HashSelectGate {
+    fn id(&self) -> String {
+        format!("{self:?}")
+    }
+
+    fn serialization_id(&self) -> Option<GateId> {
+        Some(GateId::HashSelect)
+    }
+
+    fn serialize(&self, dst: &mut Vec<u8>) -> IoResult<()> {
+        write_gate_param(dst, self.num_ops)
+    }
+
+    fn export_circom_verification_code(&self) -> String {
+        let mut template_str = "template HashSelect$NUM_OPS() {
+  signal input constants[NUM_OPENINGS_CONSTANTS()][2];
+  signal input wires[NUM_OPENINGS_WIRES()][2];
+  signal input public_input_hash[4];
+  signal input constraints[NUM_GATE_CONSTRAINTS()][2];
+  signal output out[NUM_GATE_CONSTRAINTS()][2];
+
+  signal filter[2];
+  $SET_FILTER;
+
+  for (var i = 0; i < $NUM_OPS; i++) {
+    for (var j = 0; j < 4; j++) {
+      out[4 * i + j] <== ConstraintPush()(constraints[4 * i + j], filter, GlExtSub()(wires[13 * i + 9 + j], GlExtAdd()(GlExtMul()(wires[13 * i], GlExtSub()(wires[13 * i + 1 + j], wires[13 * i + 5 + j])), wires[13 * i + 5 + j])));
+    }
+  }
+  for (var i = 4 * $NUM_OPS; i < NUM_GATE_CONSTRAINTS(); i++) {
+    out[i] <== constraints[i];
+  }
+}"
+        .to_string();
+        template_str = template_str.replace("$NUM_OPS", &self.num_ops.to_string());
+        template_str
+    }
+    fn export_solidity_verification_code(&self) -> String {
+        let mut template_str = "library HashSelect$NUM_OPSLib {
+    using GoldilocksExtLib for uint64[2];
+    function set_filter(GatesUtilsLib.EvaluationVars memory ev) internal pure {
+        $SET_FILTER;
+    }
+    function eval(GatesUtilsLib.EvaluationVars memory ev, uint64[2][$NUM_GATE_CONSTRAINTS] memory constraints) internal pure {
+        for (uint32 i = 0; i < $NUM_OPS; i++) {
+            for (uint32 j = 0; j < 4; j++) {
+                uint64[2] memory selected = ev.wires[13 * i].mul(ev.wires[13 * i + 1 + j].sub(ev.wires[13 * i + 5 + j])).add(ev.wires[13 * i + 5 + j]);
+                GatesUtilsLib.push(constraints, ev.filter, 4 * i + j, ev.wires[13 * i + 9 + j].sub(selected));
+            }
+        }
+    }
+}"
+        .to_string();
+        template_str = template_str.replace("$NUM_OPS", &self.num_ops.to_string());
+        template_str
+    }
+    fn supports_circom_export(&self) -> bool {
+        true
+    }
+    fn supports_solidity_export(&self) -> bool {
+        true
+    }
+
+    fn eval_unfiltered(&self, vars: EvaluationVars<F, D>) -> Vec<F::Extension> {
+        let mut constraints = Vec::with_capacity(self.num_ops * NUM_HASH_OUT_ELTS);
+        for i in 0..self.num_ops {
+            let cond = vars.local_wires[Self::wire_ith_cond(i)];
+            for j in 0..NUM_HASH_OUT_ELTS {
+                let a = vars.local_wires[Self::wire_ith_a(i, j)];
+                let b = vars.local_wires[Self::wire_ith_b(i, j)];
+                let output = vars.local_wires[Self::wire_ith_output(i, j)];
+                constraints.push(output - (cond * (a - b) + b));
+            }
+        }
+        constraints
+    }
+
+    fn eval_unfiltered_base_one(
+        &self,
+        _vars: EvaluationVarsBase<F>,
+        _yield_constr: StridedConstraintConsumer<F>,
+    ) {
+        panic!("use eval_unfiltered_base_packed instead");
+    }
+
+    fn eval_unfiltered_base_batch(&self, vars_base: EvaluationVarsBaseBatch<F>) -> Vec<F> {
+        self.eval_unfiltered_base_batch_packed(vars_base)
+    }
+
+    fn eval_unfiltered_circuit(
+        &self,
+        builder: &mut CircuitBuilder<F, D>,
+        vars: EvaluationTargets<D>,
+    ) -> Vec<ExtensionTarget<D>> {
+        let mut constraints = Vec::with_capacity(self.num_ops * NUM_HASH_OUT_ELTS);
+        for i in 0..self.num_ops {
+            let cond = vars.local_wires[Self::wire_ith_cond(i)];
+            for j in 0..NUM_HASH_OUT_ELTS {
+                let a = vars.local_wires[Self::wire_ith_a(i, j)];
+                let b = vars.local_wires[Self::wire_ith_b(i, j)];
+                let output = vars.local_wires[Self::wire_ith_output(i, j)];
+                let difference = builder.sub_extension(a, b);
+                let selected = builder.mul_add_extension(cond, difference, b);
+                constraints.push(builder.sub_extension(output, selected));
+            }
+        }
+        constraints
+    }
+
+    fn generators(&self, row: usize, _local_constants: &[F]) -> Vec<Box<dyn WitnessGenerator<F>>> {
+        (0..self.num_ops)
+            .map(|i| {
+                let g: Box<dyn WitnessGenerator<F>> =
+                    Box::new(HashSelectGenerator { row, i }.adapter());
+                g
+            })
+            .collect()
+    }
+
+    fn num_wires(&self) -> usize {
+        self.num_ops * Self::WIRES_PER_OP
+    }
+
+    fn num_constants(&self) -> usize {
+        0
+    }
+
+    fn degree(&self) -> usize {
+        2
+    }
+
+    fn num_constraints(&self) -> usize {
+        self.num_ops * NUM_HASH_OUT_ELTS
+    }
+}
+
+impl<F: RichField + Extendable<D>, const D: usize> PackedEvaluableBase<F, D> for HashSelectGate {
+    fn eval_unfiltered_base_packed<P: PackedField<Scalar = F>>(
+        &self,
+        vars: EvaluationVarsBasePacked<P>,
+        mut yield_constr: StridedConstraintConsumer<P>,
+    ) {
+        for i in 0..self.num_ops {
+            let cond = vars.local_wires[Self::wire_ith_cond(i)];
+            for j in 0..NUM_HASH_OUT_ELTS {
+                let a = vars.local_wires[Self::wire_ith_a(i, j)];
+                let b = vars.local_wires[Self::wire_ith_b(i, j)];
+                let output = vars.local_wires[Self::wire_ith_output(i, j)];
+                yield_constr.one(output - (cond * (a - b) + b));
+            }
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+pub(crate) struct HashSelectGenerator {
+    row: usize,
+    i: usize,
+}
+
+impl<F: RichField> SimpleGenerator<F> for HashSelectGenerator {
+    fn dependencies(&self) -> Vec<Target> {
+        let mut deps = Vec::with_capacity(1 + 2 * NUM_HASH_OUT_ELTS);
+        deps.push(Target::wire(
+            self.row,
+            HashSelectGate::wire_ith_cond(self.i),
+        ));
+        for j in 0..NUM_HASH_OUT_ELTS {
+            deps.push(Target::wire(
+                self.row,
+                HashSelectGate::wire_ith_a(self.i, j),
+            ));
+            deps.push(Target::wire(
+                self.row,
+                HashSelectGate::wire_ith_b(self.i, j),
+            ));
+        }
+        deps
+    }
+
+    fn run_once(&self, witness: &PartitionWitness<F>, out_buffer: &mut GeneratedValues<F>) {
+        let get_wire = |wire: usize| -> F { witness.get_target(Target::wire(self.row, wire)) };
+
+        let cond = get_wire(HashSelectGate::wire_ith_cond(self.i));
+        for j in 0..NUM_HASH_OUT_ELTS {
+            let a = get_wire(HashSelectGate::wire_ith_a(self.i, j));
+            let b = get_wire(HashSelectGate::wire_ith_b(self.i, j));
+            let output_target = Target::wire(self.row, HashSelectGate::wire_ith_output(self.i, j));
+            out_buffer.set_target(output_target, cond * (a - b) + b);
+        }
+    }
+
+    fn id(&self) -> Option<GeneratorId> {
+        Some(GeneratorId::HashSelect)
+    }
+
+    fn serialize(&self, dst: &mut Vec<u8>) -> IoResult<()> {
+        dst.write_u32(self.row as u32)?;
+        dst.write_u32(self.i as u32)
+    }
+}
+
+impl HashSelectGenerator {
+    pub(crate) fn deserialize<R: Read + ?Sized>(src: &mut R) -> IoResult<Self> {
+        let row = src.read_u32()? as usize;
+        let i = src.read_u32()? as usize;
+        Ok(Self { row, i })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use anyhow::Result;
+
+    use crate::field::goldilocks_field::GoldilocksField;
+    use crate::gates::gate_testing::{test_eval_fns, test_low_degree};
+    use crate::gates::hash_select::HashSelectGate;
+    use crate::plonk::circuit_data::CircuitConfig;
+    use crate::plonk::config::{GenericConfig, PoseidonGoldilocksConfig};
+
+    #[test]
+    fn low_degree() {
+        let gate = HashSelectGate::new_from_config(&CircuitConfig::standard_recursion_config());
+        test_low_degree::<GoldilocksField, _, 4>(gate);
+    }
+
+    #[test]
+    fn eval_fns() -> Result<()> {
+        const D: usize = 2;
+        type C = PoseidonGoldilocksConfig;
+        type F = <C as GenericConfig<D>>::F;
+        let gate = HashSelectGate::new_from_config(&CircuitConfig::standard_recursion_config());
+        test_eval_fns::<F, C, _, D>(gate)
+    }
+}
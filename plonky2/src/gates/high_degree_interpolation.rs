@@ -21,6 +21,8 @@ use crate::iop::wire::Wire;
 use crate::iop::witness::{PartitionWitness, Witness, WitnessWrite};
 use crate::plonk::circuit_builder::CircuitBuilder;
 use crate::plonk::vars::{EvaluationTargets, EvaluationVars, EvaluationVarsBase};
+use crate::util::gate_serialization::{read_gate_bits_param, write_gate_param, GateId};
+use crate::util::serialization::{IoResult, Read};
 
 /// One of the instantiations of `InterpolationGate`: allows constraints of variable
 /// degree, up to `1<<subgroup_bits`.
@@ -84,6 +86,12 @@ impl<F: RichField + Extendable<D>, const D: usize> HighDegreeInterpolationGate<F
             })
             .collect()
     }
+
+    pub(crate) fn deserialize<R: Read + ?Sized>(src: &mut R) -> IoResult<Self> {
+        Ok(<Self as InterpolationGate<F, D>>::new(
+            read_gate_bits_param(src)?,
+        ))
+    }
 }
 
 impl<F: RichField + Extendable<D>, const D: usize> Gate<F, D>
@@ -93,6 +101,14 @@ impl<F: RichField + Extendable<D>, const D: usize> Gate<F, D>
         format!("{self:?}<D={D}>")
     }
 
+    fn serialization_id(&self) -> Option<GateId> {
+        Some(GateId::HighDegreeInterpolation)
+    }
+
+    fn serialize(&self, dst: &mut Vec<u8>) -> IoResult<()> {
+        write_gate_param(dst, self.subgroup_bits)
+    }
+
     fn export_circom_verification_code(&self) -> String {
         todo!()
     }
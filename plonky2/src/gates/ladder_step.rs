@@ -0,0 +1,336 @@
+use alloc::boxed::Box;
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+use alloc::{format, vec};
+use core::ops::Range;
+
+use crate::field::extension::Extendable;
+use crate::gates::gate::Gate;
+use crate::gates::util::StridedConstraintConsumer;
+use crate::hash::hash_types::RichField;
+use crate::iop::ext_target::ExtensionTarget;
+use crate::iop::generator::{GeneratedValues, SimpleGenerator, WitnessGenerator};
+use crate::iop::target::Target;
+use crate::iop::witness::{PartitionWitness, Witness, WitnessWrite};
+use crate::plonk::circuit_builder::CircuitBuilder;
+use crate::plonk::vars::{EvaluationTargets, EvaluationVars, EvaluationVarsBase};
+use crate::util::gate_serialization::{read_gate_param, write_gate_param, GateId};
+use crate::util::serialization::{IoResult, Read};
+
+/// A gate for the group-agnostic part of one Montgomery ladder step on a pair `(r0, r1)` of group
+/// elements, each represented by `num_limbs` field elements. Given a bit `b`, it outputs
+/// `to_double = if b { r1 } else { r0 }`, and then, given `sum = r0 + r1` and
+/// `doubled = 2 to_double` computed outside the gate by the group law, it outputs
+/// `(doubled, sum)` if `b = 0` or `(sum, doubled)` if `b = 1`. It also enforces that `b` is binary.
+#[derive(Debug, Clone)]
+pub struct LadderStepGate {
+    /// Number of field elements representing a group element.
+    pub num_limbs: usize,
+}
+
+impl LadderStepGate {
+    pub fn new(num_limbs: usize) -> Self {
+        Self { num_limbs }
+    }
+
+    pub fn wire_bit() -> usize {
+        0
+    }
+
+    fn limb_wires(&self, k: usize) -> Range<usize> {
+        let start = 1 + k * self.num_limbs;
+        start..start + self.num_limbs
+    }
+
+    pub fn wires_r0(&self) -> Range<usize> {
+        self.limb_wires(0)
+    }
+    pub fn wires_r1(&self) -> Range<usize> {
+        self.limb_wires(1)
+    }
+    pub fn wires_sum(&self) -> Range<usize> {
+        self.limb_wires(2)
+    }
+    pub fn wires_doubled(&self) -> Range<usize> {
+        self.limb_wires(3)
+    }
+    pub fn wires_to_double(&self) -> Range<usize> {
+        self.limb_wires(4)
+    }
+    pub fn wires_new_r0(&self) -> Range<usize> {
+        self.limb_wires(5)
+    }
+    pub fn wires_new_r1(&self) -> Range<usize> {
+        self.limb_wires(6)
+    }
+
+    pub(crate) fn deserialize<R: Read + ?Sized>(src: &mut R) -> IoResult<Self> {
+        Ok(Self {
+            num_limbs: read_gate_param(src)?,
+        })
+    }
+}
+
+impl<F: RichField + Extendable<D>, const D: usize> Gate<F, D> for LadderStepGate {
+    fn id(&self) -> String {
+        format!("{self:?}")
+    }
+
+    fn serialization_id(&self) -> Option<GateId> {
+        Some(GateId::LadderStep)
+    }
+
+    fn serialize(&self, dst: &mut Vec<u8>) -> IoResult<()> {
+        write_gate_param(dst, self.num_limbs)
+    }
+
+    fn export_circom_verification_code(&self) -> String {
+        let mut template_str = "template LadderStep$NUM_LIMBS() {
+  signal input constants[NUM_OPENINGS_CONSTANTS()][2];
+  signal input wires[NUM_OPENINGS_WIRES()][2];
+  signal input public_input_hash[4];
+  signal input constraints[NUM_GATE_CONSTRAINTS()][2];
+  signal output out[NUM_GATE_CONSTRAINTS()][2];
+
+  signal filter[2];
+  $SET_FILTER;
+
+  out[0] <== ConstraintPush()(constraints[0], filter, GlExtSub()(GlExtMul()(wires[0], wires[0]), wires[0]));
+  for (var i = 0; i < $NUM_LIMBS; i++) {
+    out[3 * i + 1] <== ConstraintPush()(constraints[3 * i + 1], filter, GlExtSub()(wires[1 + 4 * $NUM_LIMBS + i], GlExtAdd()(wires[1 + i], GlExtMul()(wires[0], GlExtSub()(wires[1 + $NUM_LIMBS + i], wires[1 + i])))));
+    out[3 * i + 2] <== ConstraintPush()(constraints[3 * i + 2], filter, GlExtSub()(wires[1 + 5 * $NUM_LIMBS + i], GlExtAdd()(wires[1 + 3 * $NUM_LIMBS + i], GlExtMul()(wires[0], GlExtSub()(wires[1 + 2 * $NUM_LIMBS + i], wires[1 + 3 * $NUM_LIMBS + i])))));
+    out[3 * i + 3] <== ConstraintPush()(constraints[3 * i + 3], filter, GlExtSub()(wires[1 + 6 * $NUM_LIMBS + i], GlExtAdd()(wires[1 + 2 * $NUM_LIMBS + i], GlExtMul()(wires[0], GlExtSub()(wires[1 + 3 * $NUM_LIMBS + i], wires[1 + 2 * $NUM_LIMBS + i])))));
+  }
+  for (var i = 1 + 3 * $NUM_LIMBS; i < NUM_GATE_CONSTRAINTS(); i++) {
+    out[i] <== constraints[i];
+  }
+}"
+        .to_string();
+        template_str = template_str.replace("$NUM_LIMBS", &self.num_limbs.to_string());
+        template_str
+    }
+    fn export_solidity_verification_code(&self) -> String {
+        let mut template_str = "library LadderStep$NUM_LIMBSLib {
+    using GoldilocksExtLib for uint64[2];
+    function set_filter(GatesUtilsLib.EvaluationVars memory ev) internal pure {
+        $SET_FILTER;
+    }
+    function eval(GatesUtilsLib.EvaluationVars memory ev, uint64[2][$NUM_GATE_CONSTRAINTS] memory constraints) internal pure {
+        GatesUtilsLib.push(constraints, ev.filter, 0, ev.wires[0].mul(ev.wires[0]).sub(ev.wires[0]));
+        for (uint32 i = 0; i < $NUM_LIMBS; i++) {
+            GatesUtilsLib.push(constraints, ev.filter, 3 * i + 1, ev.wires[1 + 4 * $NUM_LIMBS + i].sub(ev.wires[1 + i].add(ev.wires[0].mul(ev.wires[1 + $NUM_LIMBS + i].sub(ev.wires[1 + i])))));
+            GatesUtilsLib.push(constraints, ev.filter, 3 * i + 2, ev.wires[1 + 5 * $NUM_LIMBS + i].sub(ev.wires[1 + 3 * $NUM_LIMBS + i].add(ev.wires[0].mul(ev.wires[1 + 2 * $NUM_LIMBS + i].sub(ev.wires[1 + 3 * $NUM_LIMBS + i])))));
+            GatesUtilsLib.push(constraints, ev.filter, 3 * i + 3, ev.wires[1 + 6 * $NUM_LIMBS + i].sub(ev.wires[1 + 2 * $NUM_LIMBS + i].add(ev.wires[0].mul(ev.wires[1 + 3 * $NUM_LIMBS + i].sub(ev.wires[1 + 2 * $NUM_LIMBS + i])))));
+        }
+    }
+}"
+        .to_string();
+        template_str = template_str.replace("$NUM_LIMBS", &self.num_limbs.to_string());
+        template_str
+    }
+    fn supports_circom_export(&self) -> bool {
+        true
+    }
+    fn supports_solidity_export(&self) -> bool {
+        true
+    }
+
+    fn eval_unfiltered(&self, vars: EvaluationVars<F, D>) -> Vec<F::Extension> {
+        let wire = |i: usize| vars.local_wires[i];
+        let b = wire(Self::wire_bit());
+
+        let mut constraints = vec![b * b - b];
+        for i in 0..self.num_limbs {
+            let r0 = wire(self.wires_r0().start + i);
+            let r1 = wire(self.wires_r1().start + i);
+            let sum = wire(self.wires_sum().start + i);
+            let doubled = wire(self.wires_doubled().start + i);
+            let to_double = wire(self.wires_to_double().start + i);
+            let new_r0 = wire(self.wires_new_r0().start + i);
+            let new_r1 = wire(self.wires_new_r1().start + i);
+
+            constraints.push(to_double - (r0 + b * (r1 - r0)));
+            constraints.push(new_r0 - (doubled + b * (sum - doubled)));
+            constraints.push(new_r1 - (sum + b * (doubled - sum)));
+        }
+        constraints
+    }
+
+    fn eval_unfiltered_base_one(
+        &self,
+        vars: EvaluationVarsBase<F>,
+        mut yield_constr: StridedConstraintConsumer<F>,
+    ) {
+        let wire = |i: usize| vars.local_wires[i];
+        let b = wire(Self::wire_bit());
+
+        yield_constr.one(b * b - b);
+        for i in 0..self.num_limbs {
+            let r0 = wire(self.wires_r0().start + i);
+            let r1 = wire(self.wires_r1().start + i);
+            let sum = wire(self.wires_sum().start + i);
+            let doubled = wire(self.wires_doubled().start + i);
+            let to_double = wire(self.wires_to_double().start + i);
+            let new_r0 = wire(self.wires_new_r0().start + i);
+            let new_r1 = wire(self.wires_new_r1().start + i);
+
+            yield_constr.one(to_double - (r0 + b * (r1 - r0)));
+            yield_constr.one(new_r0 - (doubled + b * (sum - doubled)));
+            yield_constr.one(new_r1 - (sum + b * (doubled - sum)));
+        }
+    }
+
+    fn eval_unfiltered_circuit(
+        &self,
+        builder: &mut CircuitBuilder<F, D>,
+        vars: EvaluationTargets<D>,
+    ) -> Vec<ExtensionTarget<D>> {
+        let wire = |i: usize| vars.local_wires[i];
+        let b = wire(Self::wire_bit());
+
+        let mut constraints = vec![builder.mul_sub_extension(b, b, b)];
+        for i in 0..self.num_limbs {
+            let r0 = wire(self.wires_r0().start + i);
+            let r1 = wire(self.wires_r1().start + i);
+            let sum = wire(self.wires_sum().start + i);
+            let doubled = wire(self.wires_doubled().start + i);
+            let to_double = wire(self.wires_to_double().start + i);
+            let new_r0 = wire(self.wires_new_r0().start + i);
+            let new_r1 = wire(self.wires_new_r1().start + i);
+
+            let selected = builder.select_ext_generalized(b, r1, r0);
+            constraints.push(builder.sub_extension(to_double, selected));
+            let selected = builder.select_ext_generalized(b, sum, doubled);
+            constraints.push(builder.sub_extension(new_r0, selected));
+            let selected = builder.select_ext_generalized(b, doubled, sum);
+            constraints.push(builder.sub_extension(new_r1, selected));
+        }
+        constraints
+    }
+
+    fn generators(&self, row: usize, _local_constants: &[F]) -> Vec<Box<dyn WitnessGenerator<F>>> {
+        vec![
+            Box::new(
+                LadderSwapGenerator {
+                    row,
+                    gate: self.clone(),
+                }
+                .adapter(),
+            ),
+            Box::new(
+                LadderCombineGenerator {
+                    row,
+                    gate: self.clone(),
+                }
+                .adapter(),
+            ),
+        ]
+    }
+
+    fn num_wires(&self) -> usize {
+        1 + 7 * self.num_limbs
+    }
+
+    fn num_constants(&self) -> usize {
+        0
+    }
+
+    fn degree(&self) -> usize {
+        2
+    }
+
+    fn num_constraints(&self) -> usize {
+        1 + 3 * self.num_limbs
+    }
+}
+
+/// Generates `to_double` from the bit and the current pair.
+#[derive(Clone, Debug)]
+struct LadderSwapGenerator {
+    row: usize,
+    gate: LadderStepGate,
+}
+
+impl<F: RichField> SimpleGenerator<F> for LadderSwapGenerator {
+    fn dependencies(&self) -> Vec<Target> {
+        [LadderStepGate::wire_bit()]
+            .into_iter()
+            .chain(self.gate.wires_r0())
+            .chain(self.gate.wires_r1())
+            .map(|wire| Target::wire(self.row, wire))
+            .collect()
+    }
+
+    fn run_once(&self, witness: &PartitionWitness<F>, out_buffer: &mut GeneratedValues<F>) {
+        let get_wire = |wire: usize| -> F { witness.get_target(Target::wire(self.row, wire)) };
+
+        let b = get_wire(LadderStepGate::wire_bit());
+        let selected = if b == F::ZERO {
+            self.gate.wires_r0()
+        } else {
+            self.gate.wires_r1()
+        };
+        for (src, dst) in selected.zip(self.gate.wires_to_double()) {
+            out_buffer.set_target(Target::wire(self.row, dst), get_wire(src));
+        }
+    }
+}
+
+/// Generates the new pair from the bit and the externally computed sum and doubling.
+#[derive(Clone, Debug)]
+struct LadderCombineGenerator {
+    row: usize,
+    gate: LadderStepGate,
+}
+
+impl<F: RichField> SimpleGenerator<F> for LadderCombineGenerator {
+    fn dependencies(&self) -> Vec<Target> {
+        [LadderStepGate::wire_bit()]
+            .into_iter()
+            .chain(self.gate.wires_sum())
+            .chain(self.gate.wires_doubled())
+            .map(|wire| Target::wire(self.row, wire))
+            .collect()
+    }
+
+    fn run_once(&self, witness: &PartitionWitness<F>, out_buffer: &mut GeneratedValues<F>) {
+        let get_wire = |wire: usize| -> F { witness.get_target(Target::wire(self.row, wire)) };
+
+        let b = get_wire(LadderStepGate::wire_bit());
+        let (new_r0_src, new_r1_src) = if b == F::ZERO {
+            (self.gate.wires_doubled(), self.gate.wires_sum())
+        } else {
+            (self.gate.wires_sum(), self.gate.wires_doubled())
+        };
+        for (src, dst) in new_r0_src.zip(self.gate.wires_new_r0()) {
+            out_buffer.set_target(Target::wire(self.row, dst), get_wire(src));
+        }
+        for (src, dst) in new_r1_src.zip(self.gate.wires_new_r1()) {
+            out_buffer.set_target(Target::wire(self.row, dst), get_wire(src));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use anyhow::Result;
+
+    use crate::field::goldilocks_field::GoldilocksField;
+    use crate::gates::gate_testing::{fuzz_gate, test_eval_fns, test_low_degree};
+    use crate::gates::ladder_step::LadderStepGate;
+    use crate::plonk::config::{GenericConfig, PoseidonGoldilocksConfig};
+
+    #[test]
+    fn low_degree() {
+        test_low_degree::<GoldilocksField, _, 4>(LadderStepGate::new(3));
+    }
+
+    #[test]
+    fn eval_fns() -> Result<()> {
+        const D: usize = 2;
+        type C = PoseidonGoldilocksConfig;
+        type F = <C as GenericConfig<D>>::F;
+        test_eval_fns::<F, C, _, D>(LadderStepGate::new(3))?;
+        fuzz_gate::<F, C, _, D>(LadderStepGate::new(3), 20)
+    }
+}
@@ -22,6 +22,8 @@ use crate::iop::wire::Wire;
 use crate::iop::witness::{PartitionWitness, Witness, WitnessWrite};
 use crate::plonk::circuit_builder::CircuitBuilder;
 use crate::plonk::vars::{EvaluationTargets, EvaluationVars, EvaluationVarsBase};
+use crate::util::gate_serialization::{read_gate_bits_param, write_gate_param, GateId};
+use crate::util::serialization::{IoResult, Read};
 
 /// One of the instantiations of `InterpolationGate`: all constraints are degree <= 2.
 /// The lower degree is a tradeoff for more gates (`eval_unfiltered_recursively` for
@@ -79,6 +81,12 @@ impl<F: RichField + Extendable<D>, const D: usize> LowDegreeInterpolationGate<F,
         // Speed matters here, so we avoid `cyclic_subgroup_coset_known_order` which allocates.
         g.powers().take(size).map(move |x| x * shift)
     }
+
+    pub(crate) fn deserialize<R: Read + ?Sized>(src: &mut R) -> IoResult<Self> {
+        Ok(<Self as InterpolationGate<F, D>>::new(
+            read_gate_bits_param(src)?,
+        ))
+    }
 }
 
 impl<F: RichField + Extendable<D>, const D: usize> Gate<F, D> for LowDegreeInterpolationGate<F, D> {
@@ -86,6 +94,14 @@ impl<F: RichField + Extendable<D>, const D: usize> Gate<F, D> for LowDegreeInter
         format!("{self:?}<D={D}>")
     }
 
+    fn serialization_id(&self) -> Option<GateId> {
+        Some(GateId::LowDegreeInterpolation)
+    }
+
+    fn serialize(&self, dst: &mut Vec<u8>) -> IoResult<()> {
+        write_gate_param(dst, self.subgroup_bits)
+    }
+
     fn export_circom_verification_code(&self) -> String {
         let mut template_str = format!(
             "template LowDegreeInterpolation$SUBGROUP_BITS() {{
@@ -301,6 +317,12 @@ function two_adic_subgroup(i) {{
 
         template_str
     }
+    fn supports_circom_export(&self) -> bool {
+        true
+    }
+    fn supports_solidity_export(&self) -> bool {
+        true
+    }
 
     fn eval_unfiltered(&self, vars: EvaluationVars<F, D>) -> Vec<F::Extension> {
         let mut constraints = Vec::with_capacity(self.num_constraints());
@@ -3,23 +3,37 @@
 
 pub mod arithmetic_base;
 pub mod arithmetic_extension;
+pub mod base_inverse;
 pub mod base_sum;
+pub mod booleanity;
 pub mod constant;
+pub mod cube_add;
+pub mod diff_of_squares;
+pub mod dot_product;
 pub mod exponentiation;
 pub mod gate;
+pub mod hash_select;
 pub mod high_degree_interpolation;
 pub mod interpolation;
+pub mod inv_sum;
+pub mod ladder_step;
 pub mod low_degree_interpolation;
 pub mod multiplication_extension;
+pub mod mux4;
 pub mod noop;
 pub mod packed_util;
 pub mod poseidon;
 pub mod poseidon_mds;
 pub mod public_input;
+pub mod quadratic;
 pub mod random_access;
 pub mod reducing;
 pub mod reducing_extension;
+pub mod running_product;
 pub(crate) mod selectors;
+pub mod shared_scalar_mul;
+pub mod small_range;
+pub mod sum;
 pub mod util;
 
 // Can't use #[cfg(test)] here because it needs to be visible to other crates.
@@ -15,6 +15,9 @@ use crate::iop::witness::{PartitionWitness, Witness, WitnessWrite};
 use crate::plonk::circuit_builder::CircuitBuilder;
 use crate::plonk::circuit_data::CircuitConfig;
 use crate::plonk::vars::{EvaluationTargets, EvaluationVars, EvaluationVarsBase};
+use crate::util::gate_serialization::{read_gate_param, write_gate_param, GateId};
+use crate::util::generator_serialization::GeneratorId;
+use crate::util::serialization::{IoResult, Read, Write};
 
 /// A gate which can perform a weighted multiplication, i.e. `result = c0 x y`. If the config
 /// supports enough routed wires, it can support several such operations in one gate.
@@ -34,7 +37,7 @@ impl<const D: usize> MulExtensionGate<D> {
     /// Determine the maximum number of operations that can fit in one gate for the given config.
     pub(crate) fn num_ops(config: &CircuitConfig) -> usize {
         let wires_per_op = 3 * D;
-        config.num_routed_wires / wires_per_op
+        config.arithmetic_ops_per_row(wires_per_op)
     }
 
     pub fn wires_ith_multiplicand_0(i: usize) -> Range<usize> {
@@ -46,6 +49,12 @@ impl<const D: usize> MulExtensionGate<D> {
     pub fn wires_ith_output(i: usize) -> Range<usize> {
         3 * D * i + 2 * D..3 * D * i + 3 * D
     }
+
+    pub(crate) fn deserialize<R: Read + ?Sized>(src: &mut R) -> IoResult<Self> {
+        Ok(Self {
+            num_ops: read_gate_param(src)?,
+        })
+    }
 }
 
 impl<F: RichField + Extendable<D>, const D: usize> Gate<F, D> for MulExtensionGate<D> {
@@ -53,6 +62,14 @@ impl<F: RichField + Extendable<D>, const D: usize> Gate<F, D> for MulExtensionGa
         format!("{self:?}")
     }
 
+    fn serialization_id(&self) -> Option<GateId> {
+        Some(GateId::MulExtension)
+    }
+
+    fn serialize(&self, dst: &mut Vec<u8>) -> IoResult<()> {
+        write_gate_param(dst, self.num_ops)
+    }
+
     fn export_circom_verification_code(&self) -> String {
         let mut template_str = format!(
             "template MultiplicationExtension$NUM_OPS() {{
@@ -102,6 +119,12 @@ impl<F: RichField + Extendable<D>, const D: usize> Gate<F, D> for MulExtensionGa
         template_str = template_str.replace("$NUM_OPS", &*self.num_ops.to_string());
         template_str
     }
+    fn supports_circom_export(&self) -> bool {
+        true
+    }
+    fn supports_solidity_export(&self) -> bool {
+        true
+    }
 
     fn eval_unfiltered(&self, vars: EvaluationVars<F, D>) -> Vec<F::Extension> {
         let const_0 = vars.local_constants[0];
@@ -194,7 +217,7 @@ impl<F: RichField + Extendable<D>, const D: usize> Gate<F, D> for MulExtensionGa
 }
 
 #[derive(Clone, Debug)]
-struct MulExtensionGenerator<F: RichField + Extendable<D>, const D: usize> {
+pub(crate) struct MulExtensionGenerator<F: RichField + Extendable<D>, const D: usize> {
     row: usize,
     const_0: F,
     i: usize,
@@ -228,6 +251,25 @@ impl<F: RichField + Extendable<D>, const D: usize> SimpleGenerator<F>
 
         out_buffer.set_extension_target(output_target, computed_output)
     }
+
+    fn id(&self) -> Option<GeneratorId> {
+        Some(GeneratorId::MulExtension)
+    }
+
+    fn serialize(&self, dst: &mut Vec<u8>) -> IoResult<()> {
+        dst.write_u32(self.row as u32)?;
+        dst.write_field(self.const_0)?;
+        dst.write_u32(self.i as u32)
+    }
+}
+
+impl<F: RichField + Extendable<D>, const D: usize> MulExtensionGenerator<F, D> {
+    pub(crate) fn deserialize<R: Read + ?Sized>(src: &mut R) -> IoResult<Self> {
+        let row = src.read_u32()? as usize;
+        let const_0 = src.read_field()?;
+        let i = src.read_u32()? as usize;
+        Ok(Self { row, const_0, i })
+    }
 }
 
 #[cfg(test)]
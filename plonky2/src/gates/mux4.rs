@@ -0,0 +1,297 @@
+use alloc::boxed::Box;
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+use alloc::{format, vec};
+
+use crate::field::extension::Extendable;
+use crate::field::packed::PackedField;
+use crate::gates::gate::Gate;
+use crate::gates::packed_util::PackedEvaluableBase;
+use crate::gates::util::StridedConstraintConsumer;
+use crate::hash::hash_types::RichField;
+use crate::iop::ext_target::ExtensionTarget;
+use crate::iop::generator::{GeneratedValues, SimpleGenerator, WitnessGenerator};
+use crate::iop::target::Target;
+use crate::iop::witness::{PartitionWitness, Witness, WitnessWrite};
+use crate::plonk::circuit_builder::CircuitBuilder;
+use crate::plonk::circuit_data::CircuitConfig;
+use crate::plonk::vars::{
+    EvaluationTargets, EvaluationVars, EvaluationVarsBase, EvaluationVarsBaseBatch,
+    EvaluationVarsBasePacked,
+};
+use crate::util::gate_serialization::{read_gate_param, write_gate_param, GateId};
+use crate::util::generator_serialization::GeneratorId;
+use crate::util::serialization::{IoResult, Read, Write};
+
+/// A gate which selects one of four inputs based on two selector bits, enforcing
+/// `output = inputs[s0 + 2 s1]`. The selection is computed as the multilinear combination
+/// `i0 + s0 (i1 - i0) + s1 (i2 - i0 + s0 (i3 - i2 - i1 + i0))`, of degree 3. The selectors are
+/// assumed to be boolean; the gate does not check them. If the config supports enough routed
+/// wires, it can support several such selections in one gate.
+#[derive(Debug, Clone)]
+pub struct Mux4Gate {
+    /// Number of selections performed by the gate.
+    pub num_ops: usize,
+}
+
+impl Mux4Gate {
+    const WIRES_PER_OP: usize = 7;
+
+    pub fn new_from_config(config: &CircuitConfig) -> Self {
+        Self {
+            num_ops: Self::num_ops(config),
+        }
+    }
+
+    /// Determine the maximum number of operations that can fit in one gate for the given config.
+    pub(crate) fn num_ops(config: &CircuitConfig) -> usize {
+        config.num_routed_wires / Self::WIRES_PER_OP
+    }
+
+    pub fn wire_ith_s0(i: usize) -> usize {
+        Self::WIRES_PER_OP * i
+    }
+    pub fn wire_ith_s1(i: usize) -> usize {
+        Self::WIRES_PER_OP * i + 1
+    }
+    pub fn wire_ith_input(i: usize, j: usize) -> usize {
+        debug_assert!(j < 4);
+        Self::WIRES_PER_OP * i + 2 + j
+    }
+    pub fn wire_ith_output(i: usize) -> usize {
+        Self::WIRES_PER_OP * i + 6
+    }
+
+    pub(crate) fn deserialize<R: Read + ?Sized>(src: &mut R) -> IoResult<Self> {
+        Ok(Self {
+            num_ops: read_gate_param(src)?,
+        })
+    }
+}
+
+impl<F: RichField + Extendable<D>, const D: usize> Gate<F, D> for Mux4Gate {
+    fn id(&self) -> String {
+        format!("{self:?}")
+    }
+
+    fn serialization_id(&self) -> Option<GateId> {
+        Some(GateId::Mux4)
+    }
+
+    fn serialize(&self, dst: &mut Vec<u8>) -> IoResult<()> {
+        write_gate_param(dst, self.num_ops)
+    }
+
+    fn export_circom_verification_code(&self) -> String {
+        let mut template_str = "template Mux4$NUM_OPS() {
+  signal input constants[NUM_OPENINGS_CONSTANTS()][2];
+  signal input wires[NUM_OPENINGS_WIRES()][2];
+  signal input public_input_hash[4];
+  signal input constraints[NUM_GATE_CONSTRAINTS()][2];
+  signal output out[NUM_GATE_CONSTRAINTS()][2];
+
+  signal filter[2];
+  $SET_FILTER;
+
+  for (var i = 0; i < $NUM_OPS; i++) {
+    out[i] <== ConstraintPush()(constraints[i], filter, GlExtSub()(wires[7 * i + 6], GlExtAdd()(wires[7 * i + 2], GlExtAdd()(GlExtMul()(wires[7 * i], GlExtSub()(wires[7 * i + 3], wires[7 * i + 2])), GlExtMul()(wires[7 * i + 1], GlExtAdd()(GlExtSub()(wires[7 * i + 4], wires[7 * i + 2]), GlExtMul()(wires[7 * i], GlExtAdd()(GlExtSub()(wires[7 * i + 5], GlExtAdd()(wires[7 * i + 4], wires[7 * i + 3])), wires[7 * i + 2]))))))));
+  }
+  for (var i = $NUM_OPS; i < NUM_GATE_CONSTRAINTS(); i++) {
+    out[i] <== constraints[i];
+  }
+}"
+        .to_string();
+        template_str = template_str.replace("$NUM_OPS", &self.num_ops.to_string());
+        template_str
+    }
+    fn export_solidity_verification_code(&self) -> String {
+        let mut template_str = "library Mux4$NUM_OPSLib {
+    using GoldilocksExtLib for uint64[2];
+    function set_filter(GatesUtilsLib.EvaluationVars memory ev) internal pure {
+        $SET_FILTER;
+    }
+    function eval(GatesUtilsLib.EvaluationVars memory ev, uint64[2][$NUM_GATE_CONSTRAINTS] memory constraints) internal pure {
+        for (uint32 i = 0; i < $NUM_OPS; i++) {
+            uint64[2] memory high = ev.wires[7 * i + 4].sub(ev.wires[7 * i + 2]).add(ev.wires[7 * i].mul(ev.wires[7 * i + 5].sub(ev.wires[7 * i + 4]).sub(ev.wires[7 * i + 3]).add(ev.wires[7 * i + 2])));
+            uint64[2] memory selected = ev.wires[7 * i + 2].add(ev.wires[7 * i].mul(ev.wires[7 * i + 3].sub(ev.wires[7 * i + 2]))).add(ev.wires[7 * i + 1].mul(high));
+            GatesUtilsLib.push(constraints, ev.filter, i, ev.wires[7 * i + 6].sub(selected));
+        }
+    }
+}"
+        .to_string();
+        template_str = template_str.replace("$NUM_OPS", &self.num_ops.to_string());
+        template_str
+    }
+    fn supports_circom_export(&self) -> bool {
+        true
+    }
+    fn supports_solidity_export(&self) -> bool {
+        true
+    }
+
+    fn eval_unfiltered(&self, vars: EvaluationVars<F, D>) -> Vec<F::Extension> {
+        (0..self.num_ops)
+            .map(|i| {
+                let s0 = vars.local_wires[Self::wire_ith_s0(i)];
+                let s1 = vars.local_wires[Self::wire_ith_s1(i)];
+                let [i0, i1, i2, i3] =
+                    [0, 1, 2, 3].map(|j| vars.local_wires[Self::wire_ith_input(i, j)]);
+                let output = vars.local_wires[Self::wire_ith_output(i)];
+                output - (i0 + s0 * (i1 - i0) + s1 * (i2 - i0 + s0 * (i3 - i2 - i1 + i0)))
+            })
+            .collect()
+    }
+
+    fn eval_unfiltered_base_one(
+        &self,
+        _vars: EvaluationVarsBase<F>,
+        _yield_constr: StridedConstraintConsumer<F>,
+    ) {
+        panic!("use eval_unfiltered_base_packed instead");
+    }
+
+    fn eval_unfiltered_base_batch(&self, vars_base: EvaluationVarsBaseBatch<F>) -> Vec<F> {
+        self.eval_unfiltered_base_batch_packed(vars_base)
+    }
+
+    fn eval_unfiltered_circuit(
+        &self,
+        builder: &mut CircuitBuilder<F, D>,
+        vars: EvaluationTargets<D>,
+    ) -> Vec<ExtensionTarget<D>> {
+        (0..self.num_ops)
+            .map(|i| {
+                let s0 = vars.local_wires[Self::wire_ith_s0(i)];
+                let s1 = vars.local_wires[Self::wire_ith_s1(i)];
+                let [i0, i1, i2, i3] =
+                    [0, 1, 2, 3].map(|j| vars.local_wires[Self::wire_ith_input(i, j)]);
+                let output = vars.local_wires[Self::wire_ith_output(i)];
+                let low_diff = builder.sub_extension(i1, i0);
+                let high_diff = builder.sub_extension(i2, i0);
+                let i3_minus_i2 = builder.sub_extension(i3, i2);
+                let cross_diff = builder.sub_extension(i3_minus_i2, low_diff);
+                let high = builder.mul_add_extension(s0, cross_diff, high_diff);
+                let low = builder.mul_add_extension(s0, low_diff, i0);
+                let selected = builder.mul_add_extension(s1, high, low);
+                builder.sub_extension(output, selected)
+            })
+            .collect()
+    }
+
+    fn generators(&self, row: usize, _local_constants: &[F]) -> Vec<Box<dyn WitnessGenerator<F>>> {
+        (0..self.num_ops)
+            .map(|i| {
+                let g: Box<dyn WitnessGenerator<F>> = Box::new(Mux4Generator { row, i }.adapter());
+                g
+            })
+            .collect()
+    }
+
+    fn num_wires(&self) -> usize {
+        self.num_ops * Self::WIRES_PER_OP
+    }
+
+    fn num_constants(&self) -> usize {
+        0
+    }
+
+    fn degree(&self) -> usize {
+        3
+    }
+
+    fn num_constraints(&self) -> usize {
+        self.num_ops
+    }
+}
+
+impl<F: RichField + Extendable<D>, const D: usize> PackedEvaluableBase<F, D> for Mux4Gate {
+    fn eval_unfiltered_base_packed<P: PackedField<Scalar = F>>(
+        &self,
+        vars: EvaluationVarsBasePacked<P>,
+        mut yield_constr: StridedConstraintConsumer<P>,
+    ) {
+        for i in 0..self.num_ops {
+            let s0 = vars.local_wires[Self::wire_ith_s0(i)];
+            let s1 = vars.local_wires[Self::wire_ith_s1(i)];
+            let [i0, i1, i2, i3] =
+                [0, 1, 2, 3].map(|j| vars.local_wires[Self::wire_ith_input(i, j)]);
+            let output = vars.local_wires[Self::wire_ith_output(i)];
+            yield_constr
+                .one(output - (i0 + s0 * (i1 - i0) + s1 * (i2 - i0 + s0 * (i3 - i2 - i1 + i0))));
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+pub(crate) struct Mux4Generator {
+    row: usize,
+    i: usize,
+}
+
+impl<F: RichField> SimpleGenerator<F> for Mux4Generator {
+    fn dependencies(&self) -> Vec<Target> {
+        let mut deps = vec![
+            Target::wire(self.row, Mux4Gate::wire_ith_s0(self.i)),
+            Target::wire(self.row, Mux4Gate::wire_ith_s1(self.i)),
+        ];
+        deps.extend((0..4).map(|j| Target::wire(self.row, Mux4Gate::wire_ith_input(self.i, j))));
+        deps
+    }
+
+    fn run_once(&self, witness: &PartitionWitness<F>, out_buffer: &mut GeneratedValues<F>) {
+        let get_wire = |wire: usize| -> F { witness.get_target(Target::wire(self.row, wire)) };
+
+        let s0 = get_wire(Mux4Gate::wire_ith_s0(self.i));
+        let s1 = get_wire(Mux4Gate::wire_ith_s1(self.i));
+        let [i0, i1, i2, i3] = [0, 1, 2, 3].map(|j| get_wire(Mux4Gate::wire_ith_input(self.i, j)));
+        let output_target = Target::wire(self.row, Mux4Gate::wire_ith_output(self.i));
+
+        out_buffer.set_target(
+            output_target,
+            i0 + s0 * (i1 - i0) + s1 * (i2 - i0 + s0 * (i3 - i2 - i1 + i0)),
+        )
+    }
+
+    fn id(&self) -> Option<GeneratorId> {
+        Some(GeneratorId::Mux4)
+    }
+
+    fn serialize(&self, dst: &mut Vec<u8>) -> IoResult<()> {
+        dst.write_u32(self.row as u32)?;
+        dst.write_u32(self.i as u32)
+    }
+}
+
+impl Mux4Generator {
+    pub(crate) fn deserialize<R: Read + ?Sized>(src: &mut R) -> IoResult<Self> {
+        let row = src.read_u32()? as usize;
+        let i = src.read_u32()? as usize;
+        Ok(Self { row, i })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use anyhow::Result;
+
+    use crate::field::goldilocks_field::GoldilocksField;
+    use crate::gates::gate_testing::{test_eval_fns, test_low_degree};
+    use crate::gates::mux4::Mux4Gate;
+    use crate::plonk::circuit_data::CircuitConfig;
+    use crate::plonk::config::{GenericConfig, PoseidonGoldilocksConfig};
+
+    #[test]
+    fn low_degree() {
+        let gate = Mux4Gate::new_from_config(&CircuitConfig::standard_recursion_config());
+        test_low_degree::<GoldilocksField, _, 4>(gate);
+    }
+
+    #[test]
+    fn eval_fns() -> Result<()> {
+        const D: usize = 2;
+        type C = PoseidonGoldilocksConfig;
+        type F = <C as GenericConfig<D>>::F;
+        let gate = Mux4Gate::new_from_config(&CircuitConfig::standard_recursion_config());
+        test_eval_fns::<F, C, _, D>(gate)
+    }
+}
@@ -9,6 +9,8 @@ use crate::iop::ext_target::ExtensionTarget;
 use crate::iop::generator::WitnessGenerator;
 use crate::plonk::circuit_builder::CircuitBuilder;
 use crate::plonk::vars::{EvaluationTargets, EvaluationVars, EvaluationVarsBaseBatch};
+use crate::util::gate_serialization::GateId;
+use crate::util::serialization::IoResult;
 
 /// A gate which does nothing.
 pub struct NoopGate;
@@ -18,6 +20,14 @@ impl<F: RichField + Extendable<D>, const D: usize> Gate<F, D> for NoopGate {
         "NoopGate".into()
     }
 
+    fn serialization_id(&self) -> Option<GateId> {
+        Some(GateId::Noop)
+    }
+
+    fn serialize(&self, _dst: &mut Vec<u8>) -> IoResult<()> {
+        Ok(())
+    }
+
     fn export_circom_verification_code(&self) -> String {
         todo!()
     }
@@ -21,6 +21,9 @@ use crate::iop::witness::{PartitionWitness, Witness, WitnessWrite};
 use crate::plonk::circuit_builder::CircuitBuilder;
 use crate::plonk::vars::{EvaluationTargets, EvaluationVars, EvaluationVarsBase};
 use crate::alloc::string::ToString;
+use crate::util::gate_serialization::GateId;
+use crate::util::generator_serialization::GeneratorId;
+use crate::util::serialization::{IoResult, Read, Write};
 
 /// Evaluates a full Poseidon permutation with 12 state elements.
 ///
@@ -101,6 +104,14 @@ impl<F: RichField + Extendable<D>, const D: usize> Gate<F, D> for PoseidonGate<F
         format!("{self:?}<WIDTH={SPONGE_WIDTH}>")
     }
 
+    fn serialization_id(&self) -> Option<GateId> {
+        Some(GateId::Poseidon)
+    }
+
+    fn serialize(&self, _dst: &mut Vec<u8>) -> IoResult<()> {
+        Ok(())
+    }
+
     fn export_circom_verification_code(&self) -> String {
         let mut template_str = format!(
             "template Poseidon12() {{
@@ -399,6 +410,9 @@ function MDS_MATRIX_DIAG(i) {{
     fn export_solidity_verification_code(&self) -> String {
         todo!()
     }
+    fn supports_circom_export(&self) -> bool {
+        true
+    }
 
     fn eval_unfiltered(&self, vars: EvaluationVars<F, D>) -> Vec<F::Extension> {
         let mut constraints = Vec::with_capacity(self.num_constraints());
@@ -703,7 +717,7 @@ function MDS_MATRIX_DIAG(i) {{
 }
 
 #[derive(Debug, Clone)]
-struct PoseidonGenerator<F: RichField + Extendable<D> + Poseidon, const D: usize> {
+pub(crate) struct PoseidonGenerator<F: RichField + Extendable<D> + Poseidon, const D: usize> {
     row: usize,
     _phantom: PhantomData<F>,
 }
@@ -800,6 +814,24 @@ impl<F: RichField + Extendable<D> + Poseidon, const D: usize> SimpleGenerator<F>
             out_buffer.set_wire(local_wire(PoseidonGate::<F, D>::wire_output(i)), state[i]);
         }
     }
+
+    fn id(&self) -> Option<GeneratorId> {
+        Some(GeneratorId::Poseidon)
+    }
+
+    fn serialize(&self, dst: &mut Vec<u8>) -> IoResult<()> {
+        dst.write_u32(self.row as u32)
+    }
+}
+
+impl<F: RichField + Extendable<D> + Poseidon, const D: usize> PoseidonGenerator<F, D> {
+    pub(crate) fn deserialize<R: Read + ?Sized>(src: &mut R) -> IoResult<Self> {
+        let row = src.read_u32()? as usize;
+        Ok(Self {
+            row,
+            _phantom: PhantomData,
+        })
+    }
 }
 
 #[cfg(test)]
@@ -19,6 +19,8 @@ use crate::iop::target::Target;
 use crate::iop::witness::{PartitionWitness, Witness, WitnessWrite};
 use crate::plonk::circuit_builder::CircuitBuilder;
 use crate::plonk::vars::{EvaluationTargets, EvaluationVars, EvaluationVarsBase};
+use crate::util::gate_serialization::GateId;
+use crate::util::serialization::IoResult;
 
 /// Poseidon MDS Gate
 #[derive(Debug, Default)]
@@ -119,6 +121,14 @@ impl<F: RichField + Extendable<D> + Poseidon, const D: usize> Gate<F, D> for Pos
         format!("{self:?}<WIDTH={SPONGE_WIDTH}>")
     }
 
+    fn serialization_id(&self) -> Option<GateId> {
+        Some(GateId::PoseidonMds)
+    }
+
+    fn serialize(&self, _dst: &mut Vec<u8>) -> IoResult<()> {
+        Ok(())
+    }
+
     fn export_circom_verification_code(&self) -> String {
         assert_eq!(D, 2);
         assert_eq!(SPONGE_WIDTH, 12);
@@ -164,6 +174,9 @@ impl<F: RichField + Extendable<D> + Poseidon, const D: usize> Gate<F, D> for Pos
     fn export_solidity_verification_code(&self) -> String {
         todo!()
     }
+    fn supports_circom_export(&self) -> bool {
+        true
+    }
 
     fn eval_unfiltered(&self, vars: EvaluationVars<F, D>) -> Vec<F::Extension> {
         let inputs: [_; SPONGE_WIDTH] = (0..SPONGE_WIDTH)
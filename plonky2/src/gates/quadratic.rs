@@ -0,0 +1,230 @@
+use alloc::boxed::Box;
+use alloc::format;
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+
+use crate::field::extension::Extendable;
+use crate::field::packed::PackedField;
+use crate::gates::gate::Gate;
+use crate::gates::packed_util::PackedEvaluableBase;
+use crate::gates::util::StridedConstraintConsumer;
+use crate::hash::hash_types::RichField;
+use crate::iop::ext_target::ExtensionTarget;
+use crate::iop::generator::WitnessGenerator;
+use crate::plonk::circuit_builder::CircuitBuilder;
+use crate::plonk::circuit_data::CircuitConfig;
+use crate::plonk::vars::{
+    EvaluationTargets, EvaluationVars, EvaluationVarsBase, EvaluationVarsBaseBatch,
+    EvaluationVarsBasePacked,
+};
+use crate::util::gate_serialization::{read_gate_param, write_gate_param, GateId};
+use crate::util::serialization::{IoResult, Read};
+
+/// A gate which enforces `a x^2 + b x y + c y^2 + d x + e y + f = 0` for base field elements `x`
+/// and `y`, where the coefficients `(a, b, c, d, e, f)` are the gate's local constants. If the
+/// config supports enough routed wires, it can check several pairs `(x, y)` against the same
+/// coefficients in one gate.
+#[derive(Debug, Clone)]
+pub struct QuadraticGate {
+    /// Number of relations checked by the gate.
+    pub num_ops: usize,
+}
+
+impl QuadraticGate {
+    pub fn new_from_config(config: &CircuitConfig) -> Self {
+        Self {
+            num_ops: Self::num_ops(config),
+        }
+    }
+
+    /// Determine the maximum number of operations that can fit in one gate for the given config.
+    pub(crate) fn num_ops(config: &CircuitConfig) -> usize {
+        let wires_per_op = 2;
+        config.num_routed_wires / wires_per_op
+    }
+
+    pub fn wire_ith_x(i: usize) -> usize {
+        2 * i
+    }
+    pub fn wire_ith_y(i: usize) -> usize {
+        2 * i + 1
+    }
+
+    pub(crate) fn deserialize<R: Read + ?Sized>(src: &mut R) -> IoResult<Self> {
+        Ok(Self {
+            num_ops: read_gate_param(src)?,
+        })
+    }
+}
+
+impl<F: RichField + Extendable<D>, const D: usize> Gate<F, D> for QuadraticGate {
+    fn id(&self) -> String {
+        format!("{self:?}")
+    }
+
+    fn serialization_id(&self) -> Option<GateId> {
+        Some(GateId::Quadratic)
+    }
+
+    fn serialize(&self, dst: &mut Vec<u8>) -> IoResult<()> {
+        write_gate_param(dst, self.num_ops)
+    }
+
+    fn export_circom_verification_code(&self) -> String {
+        let mut template_str = "template Quadratic$NUM_OPS() {
+  signal input constants[NUM_OPENINGS_CONSTANTS()][2];
+  signal input wires[NUM_OPENINGS_WIRES()][2];
+  signal input public_input_hash[4];
+  signal input constraints[NUM_GATE_CONSTRAINTS()][2];
+  signal output out[NUM_GATE_CONSTRAINTS()][2];
+
+  signal filter[2];
+  $SET_FILTER;
+
+  for (var i = 0; i < $NUM_OPS; i++) {
+    out[i] <== ConstraintPush()(constraints[i], filter, GlExtAdd()(GlExtAdd()(GlExtMul()(wires[2 * i], GlExtAdd()(GlExtMul()(constants[$NUM_SELECTORS + 0], wires[2 * i]), GlExtAdd()(GlExtMul()(constants[$NUM_SELECTORS + 1], wires[2 * i + 1]), constants[$NUM_SELECTORS + 3]))), GlExtMul()(wires[2 * i + 1], GlExtAdd()(GlExtMul()(constants[$NUM_SELECTORS + 2], wires[2 * i + 1]), constants[$NUM_SELECTORS + 4]))), constants[$NUM_SELECTORS + 5]));
+  }
+  for (var i = $NUM_OPS; i < NUM_GATE_CONSTRAINTS(); i++) {
+    out[i] <== constraints[i];
+  }
+}"
+        .to_string();
+        template_str = template_str.replace("$NUM_OPS", &self.num_ops.to_string());
+        template_str
+    }
+    fn export_solidity_verification_code(&self) -> String {
+        let mut template_str = "library Quadratic$NUM_OPSLib {
+    using GoldilocksExtLib for uint64[2];
+    function set_filter(GatesUtilsLib.EvaluationVars memory ev) internal pure {
+        $SET_FILTER;
+    }
+    function eval(GatesUtilsLib.EvaluationVars memory ev, uint64[2][$NUM_GATE_CONSTRAINTS] memory constraints) internal pure {
+        for (uint32 i = 0; i < $NUM_OPS; i++) {
+            uint64[2] memory x_terms = ev.wires[2 * i].mul(ev.constants[$NUM_SELECTORS + 0].mul(ev.wires[2 * i]).add(ev.constants[$NUM_SELECTORS + 1].mul(ev.wires[2 * i + 1])).add(ev.constants[$NUM_SELECTORS + 3]));
+            uint64[2] memory y_terms = ev.wires[2 * i + 1].mul(ev.constants[$NUM_SELECTORS + 2].mul(ev.wires[2 * i + 1]).add(ev.constants[$NUM_SELECTORS + 4]));
+            GatesUtilsLib.push(constraints, ev.filter, i, x_terms.add(y_terms).add(ev.constants[$NUM_SELECTORS + 5]));
+        }
+    }
+}"
+        .to_string();
+        template_str = template_str.replace("$NUM_OPS", &self.num_ops.to_string());
+        template_str
+    }
+    fn supports_circom_export(&self) -> bool {
+        true
+    }
+    fn supports_solidity_export(&self) -> bool {
+        true
+    }
+
+    fn eval_unfiltered(&self, vars: EvaluationVars<F, D>) -> Vec<F::Extension> {
+        let [a, b, c, d, e, f] = [0, 1, 2, 3, 4, 5].map(|i| vars.local_constants[i]);
+
+        (0..self.num_ops)
+            .map(|i| {
+                let x = vars.local_wires[Self::wire_ith_x(i)];
+                let y = vars.local_wires[Self::wire_ith_y(i)];
+                x * (a * x + b * y + d) + y * (c * y + e) + f
+            })
+            .collect()
+    }
+
+    fn eval_unfiltered_base_one(
+        &self,
+        _vars: EvaluationVarsBase<F>,
+        _yield_constr: StridedConstraintConsumer<F>,
+    ) {
+        panic!("use eval_unfiltered_base_packed instead");
+    }
+
+    fn eval_unfiltered_base_batch(&self, vars_base: EvaluationVarsBaseBatch<F>) -> Vec<F> {
+        self.eval_unfiltered_base_batch_packed(vars_base)
+    }
+
+    fn eval_unfiltered_circuit(
+        &self,
+        builder: &mut CircuitBuilder<F, D>,
+        vars: EvaluationTargets<D>,
+    ) -> Vec<ExtensionTarget<D>> {
+        let [a, b, c, d, e, f] = [0, 1, 2, 3, 4, 5].map(|i| vars.local_constants[i]);
+
+        (0..self.num_ops)
+            .map(|i| {
+                let x = vars.local_wires[Self::wire_ith_x(i)];
+                let y = vars.local_wires[Self::wire_ith_y(i)];
+                let x_coeff = builder.mul_add_extension(a, x, d);
+                let x_coeff = builder.mul_add_extension(b, y, x_coeff);
+                let y_coeff = builder.mul_add_extension(c, y, e);
+                let y_terms = builder.mul_add_extension(y, y_coeff, f);
+                builder.mul_add_extension(x, x_coeff, y_terms)
+            })
+            .collect()
+    }
+
+    fn generators(&self, _row: usize, _local_constants: &[F]) -> Vec<Box<dyn WitnessGenerator<F>>> {
+        // The gate only checks its wires, so there is nothing to generate.
+        Vec::new()
+    }
+
+    fn num_wires(&self) -> usize {
+        self.num_ops * 2
+    }
+
+    fn num_constants(&self) -> usize {
+        6
+    }
+
+    fn degree(&self) -> usize {
+        3
+    }
+
+    fn num_constraints(&self) -> usize {
+        self.num_ops
+    }
+
+    fn num_ops(&self) -> usize {
+        self.num_ops
+    }
+}
+
+impl<F: RichField + Extendable<D>, const D: usize> PackedEvaluableBase<F, D> for QuadraticGate {
+    fn eval_unfiltered_base_packed<P: PackedField<Scalar = F>>(
+        &self,
+        vars: EvaluationVarsBasePacked<P>,
+        mut yield_constr: StridedConstraintConsumer<P>,
+    ) {
+        let [a, b, c, d, e, f] = [0, 1, 2, 3, 4, 5].map(|i| vars.local_constants[i]);
+
+        for i in 0..self.num_ops {
+            let x = vars.local_wires[Self::wire_ith_x(i)];
+            let y = vars.local_wires[Self::wire_ith_y(i)];
+            yield_constr.one(x * (a * x + b * y + d) + y * (c * y + e) + f);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use anyhow::Result;
+
+    use crate::field::goldilocks_field::GoldilocksField;
+    use crate::gates::gate_testing::{test_eval_fns, test_low_degree};
+    use crate::gates::quadratic::QuadraticGate;
+    use crate::plonk::circuit_data::CircuitConfig;
+    use crate::plonk::config::{GenericConfig, PoseidonGoldilocksConfig};
+
+    #[test]
+    fn low_degree() {
+        let gate = QuadraticGate::new_from_config(&CircuitConfig::standard_recursion_config());
+        test_low_degree::<GoldilocksField, _, 4>(gate);
+    }
+
+    #[test]
+    fn eval_fns() -> Result<()> {
+        const D: usize = 2;
+        type C = PoseidonGoldilocksConfig;
+        type F = <C as GenericConfig<D>>::F;
+        let gate = QuadraticGate::new_from_config(&CircuitConfig::standard_recursion_config());
+        test_eval_fns::<F, C, _, D>(gate)
+    }
+}
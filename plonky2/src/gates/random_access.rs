@@ -24,6 +24,10 @@ use crate::plonk::vars::{
     EvaluationTargets, EvaluationVars, EvaluationVarsBase, EvaluationVarsBaseBatch,
     EvaluationVarsBasePacked,
 };
+use crate::util::gate_serialization::{
+    read_gate_bits_param, read_gate_param, write_gate_param, GateId,
+};
+use crate::util::serialization::{IoResult, Read};
 
 /// A gate for checking that a particular element of a list matches a given value.
 #[derive(Copy, Clone, Debug)]
@@ -41,7 +45,7 @@ pub struct RandomAccessGate<F: RichField + Extendable<D>, const D: usize> {
 }
 
 impl<F: RichField + Extendable<D>, const D: usize> RandomAccessGate<F, D> {
-    fn new(num_copies: usize, bits: usize, num_extra_constants: usize) -> Self {
+    pub(crate) fn new(num_copies: usize, bits: usize, num_extra_constants: usize) -> Self {
         Self {
             bits,
             num_copies,
@@ -115,6 +119,14 @@ impl<F: RichField + Extendable<D>, const D: usize> RandomAccessGate<F, D> {
         debug_assert!(copy < self.num_copies);
         self.num_routed_wires() + copy * self.bits + i
     }
+
+    pub(crate) fn deserialize<R: Read + ?Sized>(src: &mut R) -> IoResult<Self> {
+        Ok(Self::new(
+            read_gate_param(src)?,
+            read_gate_bits_param(src)?,
+            read_gate_param(src)?,
+        ))
+    }
 }
 
 impl<F: RichField + Extendable<D>, const D: usize> Gate<F, D> for RandomAccessGate<F, D> {
@@ -122,6 +134,16 @@ impl<F: RichField + Extendable<D>, const D: usize> Gate<F, D> for RandomAccessGa
         format!("{self:?}<D={D}>")
     }
 
+    fn serialization_id(&self) -> Option<GateId> {
+        Some(GateId::RandomAccess)
+    }
+
+    fn serialize(&self, dst: &mut Vec<u8>) -> IoResult<()> {
+        write_gate_param(dst, self.num_copies)?;
+        write_gate_param(dst, self.bits)?;
+        write_gate_param(dst, self.num_extra_constants)
+    }
+
     fn export_circom_verification_code(&self) -> String {
         let mut template_str = format!(
             "template RandomAccessB$BITSC$NUM_COPIESE$NUM_EXTRA_CONSTANTS() {{
@@ -246,6 +268,12 @@ function ra_wire_bit(i, copy) {{
         );
         template_str
     }
+    fn supports_circom_export(&self) -> bool {
+        true
+    }
+    fn supports_solidity_export(&self) -> bool {
+        true
+    }
 
     fn eval_unfiltered(&self, vars: EvaluationVars<F, D>) -> Vec<F::Extension> {
         let mut constraints = Vec::with_capacity(self.num_constraints());
@@ -0,0 +1,366 @@
+use alloc::boxed::Box;
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+use alloc::{format, vec};
+use core::ops::Range;
+
+use crate::field::extension::Extendable;
+use crate::field::packed::PackedField;
+use crate::field::types::Field;
+use crate::gates::gate::Gate;
+use crate::gates::packed_util::PackedEvaluableBase;
+use crate::gates::util::StridedConstraintConsumer;
+use crate::hash::hash_types::RichField;
+use crate::iop::ext_target::ExtensionTarget;
+use crate::iop::generator::{GeneratedValues, SimpleGenerator, WitnessGenerator};
+use crate::iop::target::Target;
+use crate::iop::witness::{PartitionWitness, Witness, WitnessWrite};
+use crate::plonk::circuit_builder::CircuitBuilder;
+use crate::plonk::circuit_data::CircuitConfig;
+use crate::plonk::plonk_common::{reduce_with_powers, reduce_with_powers_ext_circuit};
+use crate::plonk::vars::{
+    EvaluationTargets, EvaluationVars, EvaluationVarsBase, EvaluationVarsBaseBatch,
+    EvaluationVarsBasePacked,
+};
+use crate::util::gate_serialization::{read_gate_param, write_gate_param, GateId};
+use crate::util::generator_serialization::GeneratorId;
+use crate::util::serialization::{IoResult, Read, Write};
+
+/// The base of the limbs used by `SmallRangeGate`.
+const LIMB_BASE: usize = 4;
+
+/// A gate which range-checks several values to `[0, 4^num_limbs)` at once, by decomposing each into
+/// base-4 limbs. Only the checked values are routed; the limbs live in advice wires, so many more
+/// checks fit in one row than with a `BaseSumGate<2>` bit decomposition. With 4 and 8 limbs this
+/// gives byte and `u16` range checks respectively.
+///
+/// This is not a lookup argument: the prover has no lookup table to check against, so each check
+/// pays for its own limbs.
+#[derive(Debug, Clone)]
+pub struct SmallRangeGate {
+    /// Number of base-4 limbs per checked value.
+    pub num_limbs: usize,
+    /// Number of range checks performed by the gate.
+    pub num_ops: usize,
+}
+
+impl SmallRangeGate {
+    pub fn new_from_config(num_limbs: usize, config: &CircuitConfig) -> Self {
+        Self {
+            num_limbs,
+            num_ops: Self::num_ops(num_limbs, config),
+        }
+    }
+
+    /// A gate checking values are in `[0, 2^8)`.
+    pub fn new_u8(config: &CircuitConfig) -> Self {
+        Self::new_from_config(4, config)
+    }
+
+    /// A gate checking values are in `[0, 2^16)`.
+    pub fn new_u16(config: &CircuitConfig) -> Self {
+        Self::new_from_config(8, config)
+    }
+
+    /// Determine the maximum number of operations that can fit in one gate for the given config.
+    pub(crate) fn num_ops(num_limbs: usize, config: &CircuitConfig) -> usize {
+        let wires_per_op = 1 + num_limbs;
+        (config.num_wires / wires_per_op).min(config.num_routed_wires)
+    }
+
+    pub fn wire_ith_value(&self, i: usize) -> usize {
+        debug_assert!(i < self.num_ops);
+        i
+    }
+
+    pub fn wires_ith_limbs(&self, i: usize) -> Range<usize> {
+        debug_assert!(i < self.num_ops);
+        let start = self.num_ops + i * self.num_limbs;
+        start..start + self.num_limbs
+    }
+
+    pub(crate) fn deserialize<R: Read + ?Sized>(src: &mut R) -> IoResult<Self> {
+        Ok(Self {
+            num_limbs: read_gate_param(src)?,
+            num_ops: read_gate_param(src)?,
+        })
+    }
+}
+
+impl<F: RichField + Extendable<D>, const D: usize> Gate<F, D> for SmallRangeGate {
+    fn id(&self) -> String {
+        format!("{self:?}")
+    }
+
+    fn serialization_id(&self) -> Option<GateId> {
+        Some(GateId::SmallRange)
+    }
+
+    fn serialize(&self, dst: &mut Vec<u8>) -> IoResult<()> {
+        write_gate_param(dst, self.num_limbs)?;
+        write_gate_param(dst, self.num_ops)
+    }
+
+    fn export_circom_verification_code(&self) -> String {
+        let mut template_str = "template SmallRange$NUM_OPSx$NUM_LIMBS() {
+  signal input constants[NUM_OPENINGS_CONSTANTS()][2];
+  signal input wires[NUM_OPENINGS_WIRES()][2];
+  signal input public_input_hash[4];
+  signal input constraints[NUM_GATE_CONSTRAINTS()][2];
+  signal output out[NUM_GATE_CONSTRAINTS()][2];
+
+  signal filter[2];
+  $SET_FILTER;
+
+  component reduce[$NUM_OPS];
+  component product[$NUM_OPS][$NUM_LIMBS][3];
+  for (var i = 0; i < $NUM_OPS; i++) {
+    reduce[i] = Reduce($NUM_LIMBS);
+    reduce[i].alpha <== GlExt(4, 0)();
+    reduce[i].old_eval <== GlExt(0, 0)();
+    for (var j = 0; j < $NUM_LIMBS; j++) {
+      reduce[i].in[j] <== wires[$NUM_OPS + i * $NUM_LIMBS + j];
+    }
+    out[i * ($NUM_LIMBS + 1)] <== ConstraintPush()(constraints[i * ($NUM_LIMBS + 1)], filter, GlExtSub()(reduce[i].out, wires[i]));
+    for (var j = 0; j < $NUM_LIMBS; j++) {
+      for (var k = 0; k < 3; k++) {
+        product[i][j][k] = GlExtMul();
+        if (k == 0) product[i][j][k].a <== wires[$NUM_OPS + i * $NUM_LIMBS + j];
+        else product[i][j][k].a <== product[i][j][k - 1].out;
+        product[i][j][k].b <== GlExtSub()(wires[$NUM_OPS + i * $NUM_LIMBS + j], GlExt(k + 1, 0)());
+      }
+      out[i * ($NUM_LIMBS + 1) + j + 1] <== ConstraintPush()(constraints[i * ($NUM_LIMBS + 1) + j + 1], filter, product[i][j][2].out);
+    }
+  }
+  for (var i = $NUM_OPS * ($NUM_LIMBS + 1); i < NUM_GATE_CONSTRAINTS(); i++) {
+    out[i] <== constraints[i];
+  }
+}"
+        .to_string();
+        template_str = template_str.replace("$NUM_OPS", &self.num_ops.to_string());
+        template_str = template_str.replace("$NUM_LIMBS", &self.num_limbs.to_string());
+        template_str
+    }
+    fn export_solidity_verification_code(&self) -> String {
+        let mut template_str = "library SmallRange$NUM_OPSx$NUM_LIMBSLib {
+    using GoldilocksExtLib for uint64[2];
+    function set_filter(GatesUtilsLib.EvaluationVars memory ev) internal pure {
+        $SET_FILTER;
+    }
+    function eval(GatesUtilsLib.EvaluationVars memory ev, uint64[2][$NUM_GATE_CONSTRAINTS] memory constraints) internal pure {
+        for (uint32 i = 0; i < $NUM_OPS; i++) {
+            uint32 start = $NUM_OPS + i * $NUM_LIMBS;
+            uint64[2] memory sum;
+            for (uint32 j = $NUM_LIMBS; j > 0; j--) {
+                sum = sum.mul(GatesUtilsLib.field_ext_from(4, 0)).add(ev.wires[start + j - 1]);
+            }
+            GatesUtilsLib.push(constraints, ev.filter, i * ($NUM_LIMBS + 1), sum.sub(ev.wires[i]));
+            for (uint32 j = 0; j < $NUM_LIMBS; j++) {
+                uint64[2] memory product = ev.wires[start + j];
+                for (uint32 k = 1; k < 4; k++) {
+                    product = product.mul(ev.wires[start + j].sub(GatesUtilsLib.field_ext_from(k, 0)));
+                }
+                GatesUtilsLib.push(constraints, ev.filter, i * ($NUM_LIMBS + 1) + j + 1, product);
+            }
+        }
+    }
+}"
+        .to_string();
+        template_str = template_str.replace("$NUM_OPS", &self.num_ops.to_string());
+        template_str = template_str.replace("$NUM_LIMBS", &self.num_limbs.to_string());
+        template_str
+    }
+    fn supports_circom_export(&self) -> bool {
+        true
+    }
+    fn supports_solidity_export(&self) -> bool {
+        true
+    }
+
+    fn eval_unfiltered(&self, vars: EvaluationVars<F, D>) -> Vec<F::Extension> {
+        let mut constraints = Vec::with_capacity(self.num_ops * (1 + self.num_limbs));
+        for i in 0..self.num_ops {
+            let value = vars.local_wires[self.wire_ith_value(i)];
+            let limbs = &vars.local_wires[self.wires_ith_limbs(i)];
+            let computed_value =
+                reduce_with_powers(limbs, F::Extension::from_canonical_usize(LIMB_BASE));
+            constraints.push(computed_value - value);
+            for &limb in limbs {
+                constraints.push(
+                    (0..LIMB_BASE)
+                        .map(|k| limb - F::Extension::from_canonical_usize(k))
+                        .product(),
+                );
+            }
+        }
+        constraints
+    }
+
+    fn eval_unfiltered_base_one(
+        &self,
+        _vars: EvaluationVarsBase<F>,
+        _yield_constr: StridedConstraintConsumer<F>,
+    ) {
+        panic!("use eval_unfiltered_base_packed instead");
+    }
+
+    fn eval_unfiltered_base_batch(&self, vars_base: EvaluationVarsBaseBatch<F>) -> Vec<F> {
+        self.eval_unfiltered_base_batch_packed(vars_base)
+    }
+
+    fn eval_unfiltered_circuit(
+        &self,
+        builder: &mut CircuitBuilder<F, D>,
+        vars: EvaluationTargets<D>,
+    ) -> Vec<ExtensionTarget<D>> {
+        let base = builder.constant(F::from_canonical_usize(LIMB_BASE));
+        let mut constraints = Vec::with_capacity(self.num_ops * (1 + self.num_limbs));
+        for i in 0..self.num_ops {
+            let value = vars.local_wires[self.wire_ith_value(i)];
+            let limbs = &vars.local_wires[self.wires_ith_limbs(i)];
+            let computed_value = reduce_with_powers_ext_circuit(builder, limbs, base);
+            constraints.push(builder.sub_extension(computed_value, value));
+            for &limb in limbs {
+                let mut acc = builder.one_extension();
+                for k in 0..LIMB_BASE {
+                    // acc' = acc (limb - k) = acc limb + (-k) acc
+                    let neg_k = -F::from_canonical_usize(k);
+                    acc = builder.arithmetic_extension(F::ONE, neg_k, acc, limb, acc);
+                }
+                constraints.push(acc);
+            }
+        }
+        constraints
+    }
+
+    fn generators(&self, row: usize, _local_constants: &[F]) -> Vec<Box<dyn WitnessGenerator<F>>> {
+        (0..self.num_ops)
+            .map(|i| {
+                let g: Box<dyn WitnessGenerator<F>> = Box::new(
+                    SmallRangeGenerator {
+                        row,
+                        gate: self.clone(),
+                        i,
+                    }
+                    .adapter(),
+                );
+                g
+            })
+            .collect()
+    }
+
+    fn num_wires(&self) -> usize {
+        self.num_ops * (1 + self.num_limbs)
+    }
+
+    fn num_constants(&self) -> usize {
+        0
+    }
+
+    // Bounded by the range-check (x-0)*(x-1)*(x-2)*(x-3).
+    fn degree(&self) -> usize {
+        LIMB_BASE
+    }
+
+    fn num_constraints(&self) -> usize {
+        self.num_ops * (1 + self.num_limbs)
+    }
+}
+
+impl<F: RichField + Extendable<D>, const D: usize> PackedEvaluableBase<F, D> for SmallRangeGate {
+    fn eval_unfiltered_base_packed<P: PackedField<Scalar = F>>(
+        &self,
+        vars: EvaluationVarsBasePacked<P>,
+        mut yield_constr: StridedConstraintConsumer<P>,
+    ) {
+        for i in 0..self.num_ops {
+            let value = vars.local_wires[self.wire_ith_value(i)];
+            let limbs = vars.local_wires.view(self.wires_ith_limbs(i));
+            let computed_value = reduce_with_powers(limbs, F::from_canonical_usize(LIMB_BASE));
+            yield_constr.one(computed_value - value);
+
+            let constraints_iter = limbs.iter().map(|&limb| {
+                (0..LIMB_BASE)
+                    .map(|k| limb - F::from_canonical_usize(k))
+                    .product::<P>()
+            });
+            yield_constr.many(constraints_iter);
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub(crate) struct SmallRangeGenerator {
+    row: usize,
+    gate: SmallRangeGate,
+    i: usize,
+}
+
+impl<F: RichField> SimpleGenerator<F> for SmallRangeGenerator {
+    fn dependencies(&self) -> Vec<Target> {
+        vec![Target::wire(self.row, self.gate.wire_ith_value(self.i))]
+    }
+
+    fn run_once(&self, witness: &PartitionWitness<F>, out_buffer: &mut GeneratedValues<F>) {
+        // Out-of-range values are truncated to their low limbs, so the sum constraint fails.
+        let mut value = witness
+            .get_target(Target::wire(self.row, self.gate.wire_ith_value(self.i)))
+            .to_canonical_u64();
+        for limb in self.gate.wires_ith_limbs(self.i) {
+            let limb_value = F::from_canonical_u64(value % LIMB_BASE as u64);
+            out_buffer.set_target(Target::wire(self.row, limb), limb_value);
+            value /= LIMB_BASE as u64;
+        }
+    }
+
+    fn id(&self) -> Option<GeneratorId> {
+        Some(GeneratorId::SmallRange)
+    }
+
+    fn serialize(&self, dst: &mut Vec<u8>) -> IoResult<()> {
+        dst.write_u32(self.row as u32)?;
+        dst.write_u32(self.gate.num_limbs as u32)?;
+        dst.write_u32(self.gate.num_ops as u32)?;
+        dst.write_u32(self.i as u32)
+    }
+}
+
+impl SmallRangeGenerator {
+    pub(crate) fn deserialize<R: Read + ?Sized>(src: &mut R) -> IoResult<Self> {
+        let row = src.read_u32()? as usize;
+        let gate = SmallRangeGate {
+            num_limbs: src.read_u32()? as usize,
+            num_ops: src.read_u32()? as usize,
+        };
+        let i = src.read_u32()? as usize;
+        Ok(Self { row, gate, i })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use anyhow::Result;
+
+    use crate::field::goldilocks_field::GoldilocksField;
+    use crate::gates::gate_testing::{test_eval_fns, test_low_degree};
+    use crate::gates::small_range::SmallRangeGate;
+    use crate::plonk::circuit_data::CircuitConfig;
+    use crate::plonk::config::{GenericConfig, PoseidonGoldilocksConfig};
+
+    #[test]
+    fn low_degree() {
+        let config = CircuitConfig::standard_recursion_config();
+        test_low_degree::<GoldilocksField, _, 4>(SmallRangeGate::new_u8(&config));
+        test_low_degree::<GoldilocksField, _, 4>(SmallRangeGate::new_u16(&config));
+    }
+
+    #[test]
+    fn eval_fns() -> Result<()> {
+        const D: usize = 2;
+        type C = PoseidonGoldilocksConfig;
+        type F = <C as GenericConfig<D>>::F;
+        let config = CircuitConfig::standard_recursion_config();
+        test_eval_fns::<F, C, _, D>(SmallRangeGate::new_u8(&config))?;
+        test_eval_fns::<F, C, _, D>(SmallRangeGate::new_u16(&config))
+    }
+}
@@ -0,0 +1,263 @@
+use alloc::boxed::Box;
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+use alloc::{format, vec};
+
+use crate::field::extension::Extendable;
+use crate::field::packed::PackedField;
+use crate::gates::gate::Gate;
+use crate::gates::packed_util::PackedEvaluableBase;
+use crate::gates::util::StridedConstraintConsumer;
+use crate::hash::hash_types::RichField;
+use crate::iop::ext_target::ExtensionTarget;
+use crate::iop::generator::{GeneratedValues, SimpleGenerator, WitnessGenerator};
+use crate::iop::target::Target;
+use crate::iop::witness::{PartitionWitness, Witness, WitnessWrite};
+use crate::plonk::circuit_builder::CircuitBuilder;
+use crate::plonk::circuit_data::CircuitConfig;
+use crate::plonk::vars::{
+    EvaluationTargets, EvaluationVars, EvaluationVarsBase, EvaluationVarsBaseBatch,
+    EvaluationVarsBasePacked,
+};
+use crate::util::gate_serialization::{read_gate_param, write_gate_param, GateId};
+use crate::util::generator_serialization::GeneratorId;
+use crate::util::serialization::{IoResult, Read, Write};
+
+/// A gate which computes the sum of `num_addends` base field elements, i.e.
+/// `output = sum_i addend_i`.
+#[derive(Copy, Clone, Debug)]
+pub struct SumGate {
+    pub num_addends: usize,
+}
+
+impl SumGate {
+    pub fn new(num_addends: usize) -> Self {
+        Self { num_addends }
+    }
+
+    pub fn new_from_config(config: &CircuitConfig) -> Self {
+        Self::new(Self::max_addends(config))
+    }
+
+    /// Determine the maximum number of addends that can fit in one gate for the given config.
+    pub(crate) fn max_addends(config: &CircuitConfig) -> usize {
+        // One routed wire is reserved for the output.
+        config.num_routed_wires - 1
+    }
+
+    pub fn wire_ith_addend(&self, i: usize) -> usize {
+        debug_assert!(i < self.num_addends);
+        i
+    }
+
+    pub fn wire_output(&self) -> usize {
+        self.num_addends
+    }
+
+    pub(crate) fn deserialize<R: Read + ?Sized>(src: &mut R) -> IoResult<Self> {
+        Ok(Self::new(read_gate_param(src)?))
+    }
+}
+
+impl<F: RichField + Extendable<D>, const D: usize> Gate<F, D> for SumGate {
+    fn id(&self) -> String {
+        format!("{self:?}")
+    }
+
+    fn serialization_id(&self) -> Option<GateId> {
+        Some(GateId::Sum)
+    }
+
+    fn serialize(&self, dst: &mut Vec<u8>) -> IoResult<()> {
+        write_gate_param(dst, self.num_addends)
+    }
+
+    fn export_circom_verification_code(&self) -> String {
+        let mut template_str = "template Sum$NUM_ADDENDS() {
+  signal input constants[NUM_OPENINGS_CONSTANTS()][2];
+  signal input wires[NUM_OPENINGS_WIRES()][2];
+  signal input public_input_hash[4];
+  signal input constraints[NUM_GATE_CONSTRAINTS()][2];
+  signal output out[NUM_GATE_CONSTRAINTS()][2];
+
+  signal filter[2];
+  $SET_FILTER;
+
+  signal acc[$NUM_ADDENDS + 1][2];
+  acc[0] <== GlExt(0, 0)();
+  for (var i = 0; i < $NUM_ADDENDS; i++) {
+    acc[i + 1] <== GlExtAdd()(acc[i], wires[i]);
+  }
+  out[0] <== ConstraintPush()(constraints[0], filter, GlExtSub()(wires[$NUM_ADDENDS], acc[$NUM_ADDENDS]));
+
+  for (var i = 1; i < NUM_GATE_CONSTRAINTS(); i++) {
+    out[i] <== constraints[i];
+  }
+}"
+        .to_string();
+        template_str = template_str.replace("$NUM_ADDENDS", &self.num_addends.to_string());
+        template_str
+    }
+    fn export_solidity_verification_code(&self) -> String {
+        let mut template_str = "library Sum$NUM_ADDENDSLib {
+    using GoldilocksExtLib for uint64[2];
+    function set_filter(GatesUtilsLib.EvaluationVars memory ev) internal pure {
+        $SET_FILTER;
+    }
+    function eval(GatesUtilsLib.EvaluationVars memory ev, uint64[2][$NUM_GATE_CONSTRAINTS] memory constraints) internal pure {
+        uint64[2] memory acc;
+        for (uint32 i = 0; i < $NUM_ADDENDS; i++) {
+            acc = acc.add(ev.wires[i]);
+        }
+        GatesUtilsLib.push(constraints, ev.filter, 0, ev.wires[$NUM_ADDENDS].sub(acc));
+    }
+}"
+        .to_string();
+        template_str = template_str.replace("$NUM_ADDENDS", &self.num_addends.to_string());
+        template_str
+    }
+    fn supports_circom_export(&self) -> bool {
+        true
+    }
+    fn supports_solidity_export(&self) -> bool {
+        true
+    }
+
+    fn eval_unfiltered(&self, vars: EvaluationVars<F, D>) -> Vec<F::Extension> {
+        let computed_output = (0..self.num_addends)
+            .map(|i| vars.local_wires[self.wire_ith_addend(i)])
+            .sum::<F::Extension>();
+        let output = vars.local_wires[self.wire_output()];
+
+        vec![output - computed_output]
+    }
+
+    fn eval_unfiltered_base_one(
+        &self,
+        _vars: EvaluationVarsBase<F>,
+        _yield_constr: StridedConstraintConsumer<F>,
+    ) {
+        panic!("use eval_unfiltered_base_packed instead");
+    }
+
+    fn eval_unfiltered_base_batch(&self, vars_base: EvaluationVarsBaseBatch<F>) -> Vec<F> {
+        self.eval_unfiltered_base_batch_packed(vars_base)
+    }
+
+    fn eval_unfiltered_circuit(
+        &self,
+        builder: &mut CircuitBuilder<F, D>,
+        vars: EvaluationTargets<D>,
+    ) -> Vec<ExtensionTarget<D>> {
+        let addends = (0..self.num_addends)
+            .map(|i| vars.local_wires[self.wire_ith_addend(i)])
+            .collect::<Vec<_>>();
+        let computed_output = builder.add_many_extension(addends);
+        let output = vars.local_wires[self.wire_output()];
+
+        vec![builder.sub_extension(output, computed_output)]
+    }
+
+    fn generators(&self, row: usize, _local_constants: &[F]) -> Vec<Box<dyn WitnessGenerator<F>>> {
+        vec![Box::new(SumGenerator { row, gate: *self }.adapter())]
+    }
+
+    fn num_wires(&self) -> usize {
+        self.num_addends + 1
+    }
+
+    fn num_constants(&self) -> usize {
+        0
+    }
+
+    fn degree(&self) -> usize {
+        1
+    }
+
+    fn num_constraints(&self) -> usize {
+        1
+    }
+}
+
+impl<F: RichField + Extendable<D>, const D: usize> PackedEvaluableBase<F, D> for SumGate {
+    fn eval_unfiltered_base_packed<P: PackedField<Scalar = F>>(
+        &self,
+        vars: EvaluationVarsBasePacked<P>,
+        mut yield_constr: StridedConstraintConsumer<P>,
+    ) {
+        let computed_output = (0..self.num_addends)
+            .map(|i| vars.local_wires[self.wire_ith_addend(i)])
+            .sum::<P>();
+        let output = vars.local_wires[self.wire_output()];
+
+        yield_constr.one(output - computed_output);
+    }
+}
+
+#[derive(Clone, Debug)]
+pub(crate) struct SumGenerator {
+    row: usize,
+    gate: SumGate,
+}
+
+impl<F: RichField> SimpleGenerator<F> for SumGenerator {
+    fn dependencies(&self) -> Vec<Target> {
+        (0..self.gate.num_addends)
+            .map(|i| Target::wire(self.row, self.gate.wire_ith_addend(i)))
+            .collect()
+    }
+
+    fn run_once(&self, witness: &PartitionWitness<F>, out_buffer: &mut GeneratedValues<F>) {
+        let computed_output = (0..self.gate.num_addends)
+            .map(|i| witness.get_target(Target::wire(self.row, self.gate.wire_ith_addend(i))))
+            .sum();
+        let output_target = Target::wire(self.row, self.gate.wire_output());
+
+        out_buffer.set_target(output_target, computed_output)
+    }
+
+    fn id(&self) -> Option<GeneratorId> {
+        Some(GeneratorId::Sum)
+    }
+
+    fn serialize(&self, dst: &mut Vec<u8>) -> IoResult<()> {
+        dst.write_u32(self.row as u32)?;
+        dst.write_u32(self.gate.num_addends as u32)
+    }
+}
+
+impl SumGenerator {
+    pub(crate) fn deserialize<R: Read + ?Sized>(src: &mut R) -> IoResult<Self> {
+        let row = src.read_u32()? as usize;
+        let gate = SumGate {
+            num_addends: src.read_u32()? as usize,
+        };
+        Ok(Self { row, gate })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use anyhow::Result;
+
+    use crate::field::goldilocks_field::GoldilocksField;
+    use crate::gates::gate_testing::{test_eval_fns, test_low_degree};
+    use crate::gates::sum::SumGate;
+    use crate::plonk::circuit_data::CircuitConfig;
+    use crate::plonk::config::{GenericConfig, PoseidonGoldilocksConfig};
+
+    #[test]
+    fn low_degree() {
+        let gate = SumGate::new_from_config(&CircuitConfig::standard_recursion_config());
+        test_low_degree::<GoldilocksField, _, 4>(gate);
+    }
+
+    #[test]
+    fn eval_fns() -> Result<()> {
+        const D: usize = 2;
+        type C = PoseidonGoldilocksConfig;
+        type F = <C as GenericConfig<D>>::F;
+        let gate = SumGate::new_from_config(&CircuitConfig::standard_recursion_config());
+        test_eval_fns::<F, C, _, D>(gate)
+    }
+}
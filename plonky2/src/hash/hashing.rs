@@ -7,7 +7,7 @@ use std::iter::repeat;
 use crate::field::extension::Extendable;
 use crate::field::types::Field;
 use crate::hash::hash_types::{HashOut, HashOutTarget, RichField, NUM_HASH_OUT_ELTS};
-use crate::iop::target::Target;
+use crate::iop::target::{BoolTarget, Target};
 use crate::plonk::circuit_builder::CircuitBuilder;
 use crate::plonk::config::AlgebraicHasher;
 
@@ -32,6 +32,19 @@ impl<F: RichField + Extendable<D>, const D: usize> CircuitBuilder<F, D> {
         HashOutTarget::from_vec(self.hash_n_to_m_no_pad::<H>(inputs, 4))
     }
 
+    /// Returns the hash of `inputs` if `cond` is true, or `default` otherwise. The hash is selected
+    /// after the fact, so the permutations are added to the circuit regardless of `cond`, and this
+    /// costs as much as an unconditional hash, plus a select per output element.
+    pub fn hash_if<H: AlgebraicHasher<F>>(
+        &mut self,
+        cond: BoolTarget,
+        inputs: &[Target],
+        default: HashOutTarget,
+    ) -> HashOutTarget {
+        let hash = self.hash_n_to_hash_no_pad::<H>(inputs.to_vec());
+        self.select_hash(cond, hash, default)
+    }
+
     pub fn hash_n_to_m_no_pad<H: AlgebraicHasher<F>>(
         &mut self,
         inputs: Vec<Target>,
@@ -148,3 +161,51 @@ pub fn hash_n_to_m_no_pad<F: RichField, P: PlonkyPermutation<F>>(
 pub fn hash_n_to_hash_no_pad<F: RichField, P: PlonkyPermutation<F>>(inputs: &[F]) -> HashOut<F> {
     HashOut::from_vec(hash_n_to_m_no_pad::<F, P>(inputs, NUM_HASH_OUT_ELTS))
 }
+
+#[cfg(test)]
+mod tests {
+    use anyhow::Result;
+
+    use crate::field::types::Sample;
+    use crate::hash::hash_types::HashOut;
+    use crate::hash::poseidon::PoseidonHash;
+    use crate::iop::witness::{PartialWitness, WitnessWrite};
+    use crate::plonk::circuit_builder::CircuitBuilder;
+    use crate::plonk::circuit_data::CircuitConfig;
+    use crate::plonk::config::{GenericConfig, Hasher, PoseidonGoldilocksConfig};
+    use crate::plonk::verifier::verify;
+
+    #[test]
+    fn test_hash_if() -> Result<()> {
+        const D: usize = 2;
+        type C = PoseidonGoldilocksConfig;
+        type F = <C as GenericConfig<D>>::F;
+        let config = CircuitConfig::standard_recursion_config();
+        let mut pw = PartialWitness::new();
+        let mut builder = CircuitBuilder::<F, D>::new(config);
+
+        let input_values = F::rand_vec(10);
+        let default_value = HashOut::<F>::rand();
+        let inputs = builder.add_virtual_targets(10);
+        let default = builder.add_virtual_hash();
+        for (&target, &value) in inputs.iter().zip(&input_values) {
+            pw.set_target(target, value);
+        }
+        pw.set_hash_target(default, default_value);
+
+        for (cond_value, expected) in [
+            (true, PoseidonHash::hash_no_pad(&input_values)),
+            (false, default_value),
+        ] {
+            let cond = builder.add_virtual_bool_target_safe();
+            pw.set_bool_target(cond, cond_value);
+            let hash = builder.hash_if::<PoseidonHash>(cond, &inputs, default);
+            let expected = builder.constant_hash(expected);
+            builder.connect_hashes(hash, expected);
+        }
+
+        let data = builder.build::<C>();
+        let proof = data.prove(pw)?;
+        verify(proof, &data.verifier_only, &data.common)
+    }
+}
@@ -120,25 +120,12 @@ impl<F: RichField + Extendable<D>, const D: usize> CircuitBuilder<F, D> {
     ) {
         debug_assert!(H::AlgebraicPermutation::RATE >= NUM_HASH_OUT_ELTS);
 
-        let zero = self.zero();
         let mut state: HashOutTarget = self.hash_or_noop::<H>(leaf_data);
         debug_assert_eq!(state.elements.len(), NUM_HASH_OUT_ELTS);
 
         for (&bit, &sibling) in leaf_index_bits.iter().zip(&proof.siblings) {
             debug_assert_eq!(sibling.elements.len(), NUM_HASH_OUT_ELTS);
-
-            let mut perm_inputs = H::AlgebraicPermutation::default();
-            perm_inputs.set_from_slice(&state.elements, 0);
-            perm_inputs.set_from_slice(&sibling.elements, NUM_HASH_OUT_ELTS);
-            // Ensure the rest of the state, if any, is zero:
-            perm_inputs.set_from_iter(std::iter::repeat(zero), 2 * NUM_HASH_OUT_ELTS);
-            let perm_outs = self.permute_swapped::<H>(perm_inputs, bit);
-            let hash_outs = perm_outs.squeeze()[0..NUM_HASH_OUT_ELTS]
-                .try_into()
-                .unwrap();
-            state = HashOutTarget {
-                elements: hash_outs,
-            };
+            state = self.merkle_two_to_one::<H>(state, sibling, bit);
         }
 
         for i in 0..NUM_HASH_OUT_ELTS {
@@ -150,6 +137,58 @@ impl<F: RichField + Extendable<D>, const D: usize> CircuitBuilder<F, D> {
         }
     }
 
+    /// Builds a Merkle tree over `leaves` and asserts that its root is `root`. If the number of
+    /// leaves is not a power of two, the tree is padded with empty leaves, whose digests are zero,
+    /// so the root matches that of a `MerkleTree` (with a cap height of 0) over the same padding.
+    pub fn assert_merkle_root<H: AlgebraicHasher<F>>(
+        &mut self,
+        leaves: &[Vec<Target>],
+        root: HashOutTarget,
+    ) {
+        assert!(!leaves.is_empty(), "A Merkle tree needs at least one leaf");
+        debug_assert!(H::AlgebraicPermutation::RATE >= NUM_HASH_OUT_ELTS);
+
+        let zero = self.zero();
+        let mut layer = leaves
+            .iter()
+            .map(|leaf| self.hash_or_noop::<H>(leaf.clone()))
+            .collect::<Vec<_>>();
+        layer.resize(
+            leaves.len().next_power_of_two(),
+            HashOutTarget::from_partial(&[], zero),
+        );
+
+        let _false = self._false();
+        while layer.len() > 1 {
+            layer = layer
+                .chunks(2)
+                .map(|pair| self.merkle_two_to_one::<H>(pair[0], pair[1], _false))
+                .collect();
+        }
+        self.connect_hashes(layer[0], root);
+    }
+
+    /// Compresses two Merkle tree nodes into their parent, swapping them first if `swap` is true.
+    fn merkle_two_to_one<H: AlgebraicHasher<F>>(
+        &mut self,
+        left: HashOutTarget,
+        right: HashOutTarget,
+        swap: BoolTarget,
+    ) -> HashOutTarget {
+        let zero = self.zero();
+        let mut perm_inputs = H::AlgebraicPermutation::default();
+        perm_inputs.set_from_slice(&left.elements, 0);
+        perm_inputs.set_from_slice(&right.elements, NUM_HASH_OUT_ELTS);
+        // Ensure the rest of the state, if any, is zero:
+        perm_inputs.set_from_iter(std::iter::repeat(zero), 2 * NUM_HASH_OUT_ELTS);
+        let perm_outs = self.permute_swapped::<H>(perm_inputs, swap);
+        HashOutTarget {
+            elements: perm_outs.squeeze()[0..NUM_HASH_OUT_ELTS]
+                .try_into()
+                .unwrap(),
+        }
+    }
+
     pub fn connect_hashes(&mut self, x: HashOutTarget, y: HashOutTarget) {
         for i in 0..4 {
             self.connect(x.elements[i], y.elements[i]);
@@ -171,6 +210,7 @@ mod tests {
 
     use super::*;
     use crate::field::types::Field;
+    use crate::hash::hash_types::HashOut;
     use crate::hash::merkle_tree::MerkleTree;
     use crate::iop::witness::{PartialWitness, WitnessWrite};
     use crate::plonk::circuit_builder::CircuitBuilder;
@@ -226,4 +266,52 @@ mod tests {
 
         verify(proof, &data.verifier_only, &data.common)
     }
+
+    const D: usize = 2;
+    type C = PoseidonGoldilocksConfig;
+    type F = <C as GenericConfig<D>>::F;
+
+    fn merkle_root_circuit(leaves: &[Vec<F>], root: HashOut<F>) -> Result<()> {
+        let config = CircuitConfig::standard_recursion_config();
+        let mut pw = PartialWitness::new();
+        let mut builder = CircuitBuilder::<F, D>::new(config);
+
+        let leaf_targets = leaves
+            .iter()
+            .map(|leaf| {
+                let targets = builder.add_virtual_targets(leaf.len());
+                for (&target, &value) in targets.iter().zip(leaf) {
+                    pw.set_target(target, value);
+                }
+                targets
+            })
+            .collect::<Vec<_>>();
+        let root_target = builder.add_virtual_hash();
+        pw.set_hash_target(root_target, root);
+        builder
+            .assert_merkle_root::<<C as GenericConfig<D>>::InnerHasher>(&leaf_targets, root_target);
+
+        let data = builder.build::<C>();
+        let proof = data.prove(pw)?;
+        verify(proof, &data.verifier_only, &data.common)
+    }
+
+    #[test]
+    fn test_assert_merkle_root() -> Result<()> {
+        let leaves = random_data::<F>(3, 7);
+        let mut padded_leaves = leaves.clone();
+        padded_leaves.push(vec![]);
+        let tree = MerkleTree::<F, <C as GenericConfig<D>>::InnerHasher>::new(padded_leaves, 0);
+        merkle_root_circuit(&leaves, tree.cap.0[0])
+    }
+
+    #[test]
+    #[should_panic(expected = "was set twice with different values")]
+    fn test_assert_merkle_root_wrong_root() {
+        // The padding leaf must be empty, so a root over a different fourth leaf is rejected.
+        let mut leaves = random_data::<F>(4, 7);
+        let tree = MerkleTree::<F, <C as GenericConfig<D>>::InnerHasher>::new(leaves.clone(), 0);
+        leaves.pop();
+        merkle_root_circuit(&leaves, tree.cap.0[0]).unwrap();
+    }
 }
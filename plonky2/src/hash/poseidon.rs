@@ -752,6 +752,14 @@ impl<F: RichField> AlgebraicHasher<F> for PoseidonHash {
     }
 }
 
+/// A hiding commitment to `values`, computed as the Poseidon hash of `blinding` followed by
+/// `values`. This matches `CircuitBuilder::commit`. As the hash is unpadded, the number of values
+/// being committed to should be fixed.
+pub fn commit<F: RichField>(values: &[F], blinding: F) -> HashOut<F> {
+    let inputs = [&[blinding], values].concat();
+    PoseidonHash::hash_no_pad(&inputs)
+}
+
 #[cfg(test)]
 pub(crate) mod test_helpers {
     use crate::field::types::Field;
@@ -16,7 +16,7 @@ use crate::plonk::config::{AlgebraicHasher, GenericHashOut, Hasher};
 pub struct Challenger<F: RichField, H: Hasher<F>> {
     pub(crate) sponge_state: H::Permutation,
     pub(crate) input_buffer: Vec<F>,
-    output_buffer: Vec<F>,
+    pub(crate) output_buffer: Vec<F>,
 }
 
 /// Observes prover messages, and generates verifier challenges based on the transcript.
@@ -1,3 +1,4 @@
+use alloc::collections::{BTreeMap, BTreeSet};
 use alloc::vec;
 use alloc::vec::Vec;
 use core::fmt::Debug;
@@ -12,6 +13,8 @@ use crate::iop::wire::Wire;
 use crate::iop::witness::{PartialWitness, PartitionWitness, Witness, WitnessWrite};
 use crate::plonk::circuit_data::{CommonCircuitData, ProverOnlyCircuitData};
 use crate::plonk::config::GenericConfig;
+use crate::util::generator_serialization::GeneratorId;
+use crate::util::serialization::{IoError, IoResult, Read, Write};
 use dyn_clonable::clonable;
 
 /// Given a `PartitionWitness` that has only inputs set, populates the rest of the witness using the
@@ -25,6 +28,24 @@ pub(crate) fn generate_partial_witness<
     inputs: PartialWitness<F>,
     prover_data: &'a ProverOnlyCircuitData<F, C, D>,
     common_data: &'a CommonCircuitData<F, D>,
+) -> PartitionWitness<'a, F> {
+    generate_partial_witness_with_oracle(inputs, prover_data, common_data, &|_| None)
+}
+
+/// Like `generate_partial_witness`, but whenever the generators cannot make progress, the targets
+/// they are waiting on are resolved by calling `oracle`. For each such partition of targets, the
+/// oracle is called on its targets in index order until it returns a value. It is called at most
+/// once per target, so the sequence of calls is deterministic for a given circuit and inputs.
+pub(crate) fn generate_partial_witness_with_oracle<
+    'a,
+    F: RichField + Extendable<D>,
+    C: GenericConfig<D, F = F>,
+    const D: usize,
+>(
+    inputs: PartialWitness<F>,
+    prover_data: &'a ProverOnlyCircuitData<F, C, D>,
+    common_data: &'a CommonCircuitData<F, D>,
+    oracle: &dyn Fn(Target) -> Option<F>,
 ) -> PartitionWitness<'a, F> {
     let config = &common_data.config;
     let generators = &prover_data.generators;
@@ -50,8 +71,53 @@ pub(crate) fn generate_partial_witness<
 
     let mut buffer = GeneratedValues::empty();
 
-    // Keep running generators until we fail to make progress.
-    while !pending_generator_indices.is_empty() {
+    // The targets of each partition, only computed if the oracle is needed.
+    let mut targets_by_rep: Option<BTreeMap<usize, Vec<usize>>> = None;
+    let mut queried_target_indices = BTreeSet::new();
+
+    // Keep running generators until we fail to make progress, then ask the oracle for help.
+    loop {
+        if pending_generator_indices.is_empty() {
+            if remaining_generators == 0 {
+                break;
+            }
+            let targets_by_rep = targets_by_rep.get_or_insert_with(|| {
+                let mut targets_by_rep = BTreeMap::<usize, Vec<usize>>::new();
+                for (index, &rep) in prover_data.representative_map.iter().enumerate() {
+                    targets_by_rep.entry(rep).or_default().push(index);
+                }
+                targets_by_rep
+            });
+            let unfilled_reps = (0..generators.len())
+                .filter(|&i| !generator_is_expired[i])
+                .flat_map(|i| generators[i].watch_list())
+                .map(|t| witness.representative_map[witness.target_index(t)])
+                .filter(|&rep| witness.values[rep].is_none())
+                .collect::<BTreeSet<_>>();
+            for rep in unfilled_reps {
+                for &index in &targets_by_rep[&rep] {
+                    if !queried_target_indices.insert(index) {
+                        continue;
+                    }
+                    let target = target_from_index(index, config.num_wires, common_data.degree());
+                    if let Some(value) = oracle(target) {
+                        witness.set_target(target, value);
+                        if let Some(watchers) = generator_indices_by_watches.get(&rep) {
+                            pending_generator_indices.extend(
+                                watchers
+                                    .iter()
+                                    .filter(|&&watcher| !generator_is_expired[watcher]),
+                            );
+                        }
+                        break;
+                    }
+                }
+            }
+            if pending_generator_indices.is_empty() {
+                break;
+            }
+        }
+
         let mut next_pending_generator_indices = Vec::new();
 
         for &generator_idx in &pending_generator_indices {
@@ -97,6 +163,17 @@ pub(crate) fn generate_partial_witness<
     witness
 }
 
+/// The inverse of `Target::index`.
+fn target_from_index(index: usize, num_wires: usize, degree: usize) -> Target {
+    if index < degree * num_wires {
+        Target::wire(index / num_wires, index % num_wires)
+    } else {
+        Target::VirtualTarget {
+            index: index - degree * num_wires,
+        }
+    }
+}
+
 /// A generator participates in the generation of the witness.
 #[clonable]
 pub trait WitnessGenerator<F: Field>: 'static + Send + Sync + Debug + Clone{
@@ -108,6 +185,21 @@ pub trait WitnessGenerator<F: Field>: 'static + Send + Sync + Debug + Clone{
     /// flag is true, the generator will never be run again, otherwise it will be queued for another
     /// run next time a target in its watch list is populated.
     fn run(&self, witness: &PartitionWitness<F>, out_buffer: &mut GeneratedValues<F>) -> bool;
+
+    /// The type of this generator, used to serialize it, or `None` if it does not support
+    /// serialization.
+    fn id(&self) -> Option<GeneratorId> {
+        None
+    }
+
+    /// Writes the parameters of this generator to `dst`, such that a `WitnessGeneratorSerializer`
+    /// which knows its `id` can reconstruct it.
+    fn serialize(&self, _dst: &mut Vec<u8>) -> IoResult<()>
+    where
+        F: RichField,
+    {
+        Err(IoError)
+    }
 }
 
 /// Values generated by a generator invocation.
@@ -164,6 +256,19 @@ pub trait SimpleGenerator<F: Field>: 'static + Send + Sync + Debug {
 
     fn run_once(&self, witness: &PartitionWitness<F>, out_buffer: &mut GeneratedValues<F>);
 
+    /// See `WitnessGenerator::id`.
+    fn id(&self) -> Option<GeneratorId> {
+        None
+    }
+
+    /// See `WitnessGenerator::serialize`.
+    fn serialize(&self, _dst: &mut Vec<u8>) -> IoResult<()>
+    where
+        F: RichField,
+    {
+        Err(IoError)
+    }
+
     fn adapter(self) -> SimpleGeneratorAdapter<F, Self>
     where
         Self: Sized,
@@ -194,6 +299,17 @@ impl<F: Field, SG: SimpleGenerator<F> + Clone> WitnessGenerator<F> for SimpleGen
             false
         }
     }
+
+    fn id(&self) -> Option<GeneratorId> {
+        self.inner.id()
+    }
+
+    fn serialize(&self, dst: &mut Vec<u8>) -> IoResult<()>
+    where
+        F: RichField,
+    {
+        self.inner.serialize(dst)
+    }
 }
 
 /// A generator which copies one wire to another.
@@ -212,6 +328,26 @@ impl<F: Field> SimpleGenerator<F> for CopyGenerator {
         let value = witness.get_target(self.src);
         out_buffer.set_target(self.dst, value);
     }
+
+    fn id(&self) -> Option<GeneratorId> {
+        Some(GeneratorId::Copy)
+    }
+
+    fn serialize(&self, dst: &mut Vec<u8>) -> IoResult<()> {
+        dst.write_target(self.src)?;
+        dst.write_target(self.dst)
+    }
+}
+
+impl CopyGenerator {
+    pub(crate) fn deserialize<R: Read + ?Sized>(src: &mut R) -> IoResult<Self> {
+        let src_target = src.read_target()?;
+        let dst = src.read_target()?;
+        Ok(Self {
+            src: src_target,
+            dst,
+        })
+    }
 }
 
 /// A generator for including a random value
@@ -229,6 +365,21 @@ impl<F: Field> SimpleGenerator<F> for RandomValueGenerator {
         let random_value = F::rand();
         out_buffer.set_target(self.target, random_value);
     }
+
+    fn id(&self) -> Option<GeneratorId> {
+        Some(GeneratorId::RandomValue)
+    }
+
+    fn serialize(&self, dst: &mut Vec<u8>) -> IoResult<()> {
+        dst.write_target(self.target)
+    }
+}
+
+impl RandomValueGenerator {
+    pub(crate) fn deserialize<R: Read + ?Sized>(src: &mut R) -> IoResult<Self> {
+        let target = src.read_target()?;
+        Ok(Self { target })
+    }
 }
 
 /// A generator for testing if a value equals zero
@@ -254,6 +405,23 @@ impl<F: Field> SimpleGenerator<F> for NonzeroTestGenerator {
 
         out_buffer.set_target(self.dummy, dummy_value);
     }
+
+    fn id(&self) -> Option<GeneratorId> {
+        Some(GeneratorId::NonzeroTest)
+    }
+
+    fn serialize(&self, dst: &mut Vec<u8>) -> IoResult<()> {
+        dst.write_target(self.to_test)?;
+        dst.write_target(self.dummy)
+    }
+}
+
+impl NonzeroTestGenerator {
+    pub(crate) fn deserialize<R: Read + ?Sized>(src: &mut R) -> IoResult<Self> {
+        let to_test = src.read_target()?;
+        let dummy = src.read_target()?;
+        Ok(Self { to_test, dummy })
+    }
 }
 
 /// Generator used to fill an extra constant.
@@ -271,6 +439,21 @@ impl<F: Field> ConstantGenerator<F> {
     }
 }
 
+impl<F: RichField> ConstantGenerator<F> {
+    pub(crate) fn deserialize<R: Read + ?Sized>(src: &mut R) -> IoResult<Self> {
+        let row = src.read_u32()? as usize;
+        let constant_index = src.read_u32()? as usize;
+        let wire_index = src.read_u32()? as usize;
+        let constant = src.read_field()?;
+        Ok(Self {
+            row,
+            constant_index,
+            wire_index,
+            constant,
+        })
+    }
+}
+
 impl<F: Field> SimpleGenerator<F> for ConstantGenerator<F> {
     fn dependencies(&self) -> Vec<Target> {
         vec![]
@@ -279,4 +462,18 @@ impl<F: Field> SimpleGenerator<F> for ConstantGenerator<F> {
     fn run_once(&self, _witness: &PartitionWitness<F>, out_buffer: &mut GeneratedValues<F>) {
         out_buffer.set_target(Target::wire(self.row, self.wire_index), self.constant);
     }
+
+    fn id(&self) -> Option<GeneratorId> {
+        Some(GeneratorId::Constant)
+    }
+
+    fn serialize(&self, dst: &mut Vec<u8>) -> IoResult<()>
+    where
+        F: RichField,
+    {
+        dst.write_u32(self.row as u32)?;
+        dst.write_u32(self.constant_index as u32)?;
+        dst.write_u32(self.wire_index as u32)?;
+        dst.write_field(self.constant)
+    }
 }
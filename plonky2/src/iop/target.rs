@@ -1,14 +1,19 @@
 use alloc::vec::Vec;
 use core::ops::Range;
 
+#[cfg(feature = "std")]
+use anyhow::{ensure, Result};
 use serde::{Deserialize, Serialize};
 
 use crate::iop::ext_target::ExtensionTarget;
 use crate::iop::wire::Wire;
 use crate::plonk::circuit_data::CircuitConfig;
+use crate::util::serialization::Write;
+#[cfg(feature = "std")]
+use crate::util::serialization::{Buffer, Read, Remaining};
 
 /// A location in the witness.
-#[derive(Copy, Clone, Eq, PartialEq, Hash, Debug, Serialize, Deserialize)]
+#[derive(Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug, Serialize, Deserialize)]
 pub enum Target {
     Wire(Wire),
     /// A target that doesn't have any inherent location in the witness (but it can be copied to
@@ -54,6 +59,24 @@ impl Target {
         arr[0] = self;
         ExtensionTarget(arr)
     }
+
+    /// Serializes this target, tagged as either a wire, with its row and column, or a virtual
+    /// target, with its index.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut buffer = Vec::new();
+        buffer
+            .write_target(*self)
+            .expect("Writing to a byte-vector cannot fail.");
+        buffer
+    }
+
+    #[cfg(feature = "std")]
+    pub fn from_bytes(bytes: Vec<u8>) -> Result<Self> {
+        let mut buffer = Buffer::new(bytes);
+        let target = buffer.read_target().map_err(anyhow::Error::msg)?;
+        ensure!(buffer.is_empty(), "Trailing bytes after the target");
+        Ok(target)
+    }
 }
 
 /// A `Target` which has already been constrained such that it can only be 0 or 1.
@@ -73,3 +96,37 @@ impl BoolTarget {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use anyhow::Result;
+
+    use crate::iop::target::Target;
+
+    #[test]
+    fn test_target_serialization() -> Result<()> {
+        let targets = [
+            Target::wire(0, 0),
+            Target::wire(1234, 79),
+            Target::VirtualTarget { index: 0 },
+            Target::VirtualTarget { index: 56789 },
+        ];
+        for target in targets {
+            assert_eq!(Target::from_bytes(target.to_bytes())?, target);
+        }
+
+        // A wire and a virtual target with the same numbers are encoded differently.
+        assert_ne!(
+            Target::wire(5, 0).to_bytes()[..5],
+            Target::VirtualTarget { index: 5 }.to_bytes()[..5]
+        );
+        // Unknown tags, truncated encodings, and trailing bytes are rejected.
+        assert!(Target::from_bytes(vec![2, 0, 0, 0, 0]).is_err());
+        assert!(Target::from_bytes(vec![0, 1, 0, 0, 0]).is_err());
+        let mut bytes = Target::VirtualTarget { index: 3 }.to_bytes();
+        bytes.push(0);
+        assert!(Target::from_bytes(bytes).is_err());
+
+        Ok(())
+    }
+}
@@ -6,7 +6,7 @@ use serde::{Deserialize, Serialize};
 use crate::plonk::circuit_data::CircuitConfig;
 
 /// Represents a wire in the circuit, seen as a `degree x num_wires` table.
-#[derive(Copy, Clone, Eq, PartialEq, Hash, Debug, Serialize, Deserialize)]
+#[derive(Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug, Serialize, Deserialize)]
 pub struct Wire {
     /// Row index of the wire.
     pub row: usize,
@@ -5,7 +5,7 @@ use hashbrown::HashMap;
 use itertools::Itertools;
 
 use crate::field::extension::{Extendable, FieldExtension};
-use crate::field::types::Field;
+use crate::field::types::{Field, PrimeField64};
 use crate::fri::structure::{FriOpenings, FriOpeningsTarget};
 use crate::fri::witness_util::set_fri_proof_target;
 use crate::hash::hash_types::{HashOut, HashOutTarget, MerkleCapTarget, RichField};
@@ -16,6 +16,9 @@ use crate::iop::wire::Wire;
 use crate::plonk::circuit_data::{VerifierCircuitTarget, VerifierOnlyCircuitData};
 use crate::plonk::config::{AlgebraicHasher, GenericConfig};
 use crate::plonk::proof::{Proof, ProofTarget, ProofWithPublicInputs, ProofWithPublicInputsTarget};
+#[cfg(feature = "std")]
+use crate::util::serialization::Buffer;
+use crate::util::serialization::{Read, Write};
 
 pub trait WitnessWrite<F: Field> {
     fn set_target(&mut self, target: Target, value: F);
@@ -265,6 +268,27 @@ impl<F: Field> PartialWitness<F> {
     }
 }
 
+impl<F: PrimeField64> PartialWitness<F> {
+    /// Serializes the assigned target values in a canonical order, so that two witnesses holding
+    /// the same assignments always serialize to the same bytes.
+    ///
+    /// Panics if a target's row, column or index does not fit in a `u32`.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut buffer = Vec::new();
+        buffer
+            .write_partial_witness(self)
+            .expect("Target indices must fit in a u32");
+        buffer
+    }
+
+    #[cfg(feature = "std")]
+    pub fn from_bytes(bytes: Vec<u8>) -> anyhow::Result<Self> {
+        let mut buffer = Buffer::new(bytes);
+        let pw = buffer.read_partial_witness().map_err(anyhow::Error::msg)?;
+        Ok(pw)
+    }
+}
+
 impl<F: Field> WitnessWrite<F> for PartialWitness<F> {
     fn set_target(&mut self, target: Target, value: F) {
         let opt_old_value = self.target_values.insert(target, value);
@@ -284,6 +308,42 @@ impl<F: Field> Witness<F> for PartialWitness<F> {
     }
 }
 
+/// Assignments shared by many witnesses of the same circuit, such as inputs which are fixed
+/// across proofs. Each proof's witness is then built with `instantiate`, which only needs the
+/// varying assignments.
+#[derive(Clone, Debug, Default)]
+pub struct WitnessTemplate<F: Field> {
+    fixed: PartialWitness<F>,
+}
+
+impl<F: Field> WitnessTemplate<F> {
+    pub fn new() -> Self {
+        Self {
+            fixed: PartialWitness::new(),
+        }
+    }
+
+    /// Returns a witness holding the fixed assignments along with `varying`. Panics if a varying
+    /// assignment conflicts with a fixed one.
+    pub fn instantiate(&self, varying: &[(Target, F)]) -> PartialWitness<F> {
+        let mut pw = self.fixed.clone();
+        pw.extend(varying.iter().copied());
+        pw
+    }
+}
+
+impl<F: Field> WitnessWrite<F> for WitnessTemplate<F> {
+    fn set_target(&mut self, target: Target, value: F) {
+        self.fixed.set_target(target, value)
+    }
+}
+
+impl<F: Field> Witness<F> for WitnessTemplate<F> {
+    fn try_get_target(&self, target: Target) -> Option<F> {
+        self.fixed.try_get_target(target)
+    }
+}
+
 /// `PartitionWitness` holds a disjoint-set forest of the targets respecting a circuit's copy constraints.
 /// The value of a target is defined to be the value of its root in the forest.
 #[derive(Clone)]
@@ -326,7 +386,7 @@ impl<'a, F: Field> PartitionWitness<'a, F> {
         target.index(self.num_wires, self.degree)
     }
 
-    pub fn full_witness(self) -> MatrixWitness<F> {
+    pub fn full_witness(&self) -> MatrixWitness<F> {
         let mut wire_values = vec![vec![F::ZERO; self.degree]; self.num_wires];
         for i in 0..self.degree {
             for j in 0..self.num_wires {
@@ -353,3 +413,123 @@ impl<'a, F: Field> Witness<F> for PartitionWitness<'a, F> {
         self.values[rep_index]
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use alloc::vec::Vec;
+
+    use anyhow::Result;
+
+    use crate::field::goldilocks_field::GoldilocksField;
+    use crate::field::types::{Field, Sample};
+    use crate::iop::target::Target;
+    use crate::iop::witness::{PartialWitness, Witness, WitnessTemplate, WitnessWrite};
+    use crate::plonk::circuit_builder::CircuitBuilder;
+    use crate::plonk::circuit_data::CircuitConfig;
+    use crate::plonk::config::{GenericConfig, PoseidonGoldilocksConfig};
+    use crate::util::serialization::Write;
+
+    #[test]
+    fn test_partial_witness_serialization() -> Result<()> {
+        type F = GoldilocksField;
+
+        let assignments = (0..100)
+            .map(|i| {
+                let target = if i % 2 == 0 {
+                    Target::wire(i, i % 7)
+                } else {
+                    Target::VirtualTarget { index: i }
+                };
+                (target, F::rand())
+            })
+            .collect::<Vec<_>>();
+
+        let mut pw_forward = PartialWitness::new();
+        for &(t, v) in &assignments {
+            pw_forward.set_target(t, v);
+        }
+        let mut pw_backward = PartialWitness::new();
+        for &(t, v) in assignments.iter().rev() {
+            pw_backward.set_target(t, v);
+        }
+
+        let bytes = pw_forward.to_bytes();
+        assert_eq!(bytes, pw_backward.to_bytes());
+
+        let pw_decoded = PartialWitness::<F>::from_bytes(bytes)?;
+        assert_eq!(pw_decoded.target_values, pw_forward.target_values);
+        Ok(())
+    }
+
+    #[test]
+    fn test_partial_witness_deserialization_errors() {
+        type F = GoldilocksField;
+        let target = Target::VirtualTarget { index: 3 };
+
+        // A target assigned twice, with conflicting values.
+        let mut bytes = Vec::new();
+        bytes.write_u32(2).unwrap();
+        for value in [F::ZERO, F::ONE] {
+            bytes.write_target(target).unwrap();
+            bytes.write_field(value).unwrap();
+        }
+        assert!(PartialWitness::<F>::from_bytes(bytes).is_err());
+
+        // Targets whose indices do not fit in the encoding are not truncated.
+        let mut bytes = Vec::new();
+        assert!(bytes
+            .write_target(Target::VirtualTarget {
+                index: u32::MAX as usize + 1
+            })
+            .is_err());
+        assert!(bytes
+            .write_target(Target::wire(u32::MAX as usize + 1, 0))
+            .is_err());
+    }
+
+    #[test]
+    fn test_witness_template() -> Result<()> {
+        const D: usize = 2;
+        type C = PoseidonGoldilocksConfig;
+        type F = <C as GenericConfig<D>>::F;
+
+        let config = CircuitConfig::standard_recursion_config();
+        let mut builder = CircuitBuilder::<F, D>::new(config);
+        let initial_a = builder.add_virtual_target();
+        let initial_b = builder.add_virtual_target();
+        let cur_target = builder.fibonacci_100(initial_a, initial_b);
+        builder.register_public_input(initial_b);
+        builder.register_public_input(cur_target);
+        let data = builder.build::<C>();
+
+        let mut template = WitnessTemplate::new();
+        template.set_target(initial_a, F::ZERO);
+
+        for b in [1, 2, 5] {
+            let pw = template.instantiate(&[(initial_b, F::from_canonical_u64(b))]);
+            assert_eq!(pw.get_target(initial_a), F::ZERO);
+            let proof = data.prove(pw)?;
+            // With `initial_a = 0`, the result scales linearly with `initial_b`.
+            assert_eq!(
+                proof.public_inputs,
+                vec![
+                    F::from_canonical_u64(b),
+                    F::from_canonical_u64(b) * F::from_canonical_u64(3736710860384812976)
+                ]
+            );
+            data.verify(proof)?;
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    #[should_panic(expected = "was set twice with different values")]
+    fn test_witness_template_conflict() {
+        type F = GoldilocksField;
+        let target = Target::VirtualTarget { index: 0 };
+        let mut template = WitnessTemplate::new();
+        template.set_target(target, F::ZERO);
+        template.instantiate(&[(target, F::ONE)]);
+    }
+}
@@ -1,9 +1,12 @@
 use alloc::boxed::Box;
 use alloc::collections::BTreeMap;
-use alloc::vec;
+use alloc::string::String;
 use alloc::vec::Vec;
+use alloc::{format, vec};
 use core::cmp::max;
 #[cfg(feature = "std")]
+use std::panic::{self, AssertUnwindSafe};
+#[cfg(feature = "std")]
 use std::time::Instant;
 
 use hashbrown::{HashMap, HashSet};
@@ -16,32 +19,39 @@ use crate::field::fft::fft_root_table;
 use crate::field::polynomial::PolynomialValues;
 use crate::field::types::Field;
 use crate::fri::oracle::PolynomialBatch;
-use crate::fri::{FriConfig, FriParams};
+use crate::fri::FriParams;
 use crate::gadgets::arithmetic::BaseArithmeticOperation;
 use crate::gadgets::arithmetic_extension::ExtensionArithmeticOperation;
 use crate::gadgets::polynomial::PolynomialCoeffsExtTarget;
 use crate::gates::arithmetic_base::ArithmeticGate;
 use crate::gates::arithmetic_extension::ArithmeticExtensionGate;
+use crate::gates::base_inverse::BaseInverseGate;
 use crate::gates::constant::ConstantGate;
 use crate::gates::gate::{CurrentSlot, Gate, GateInstance, GateRef};
+use crate::gates::inv_sum::InvSumGate;
 use crate::gates::noop::NoopGate;
 use crate::gates::public_input::PublicInputGate;
+use crate::gates::quadratic::QuadraticGate;
 use crate::gates::selectors::selector_polynomials;
 use crate::hash::hash_types::{HashOut, HashOutTarget, MerkleCapTarget, RichField};
 use crate::hash::merkle_proofs::MerkleProofTarget;
 use crate::hash::merkle_tree::MerkleCap;
 use crate::iop::ext_target::ExtensionTarget;
+#[cfg(feature = "std")]
+use crate::iop::generator::GeneratedValues;
 use crate::iop::generator::{
     ConstantGenerator, CopyGenerator, RandomValueGenerator, SimpleGenerator, WitnessGenerator,
 };
 use crate::iop::target::{BoolTarget, Target};
 use crate::iop::wire::Wire;
+#[cfg(feature = "std")]
+use crate::iop::witness::{PartitionWitness, WitnessWrite};
 use crate::plonk::circuit_data::{
     CircuitConfig, CircuitData, CommonCircuitData, ProverCircuitData, ProverOnlyCircuitData,
     VerifierCircuitData, VerifierCircuitTarget, VerifierOnlyCircuitData,
 };
 use crate::plonk::config::{AlgebraicHasher, GenericConfig, GenericHashOut, Hasher};
-use crate::plonk::copy_constraint::CopyConstraint;
+use crate::plonk::copy_constraint::{ConstraintError, CopyConstraint};
 use crate::plonk::permutation_argument::Forest;
 use crate::plonk::plonk_common::PlonkOracle;
 use crate::timed;
@@ -101,6 +111,26 @@ pub struct CircuitBuilder<F: RichField + Extendable<D>, const D: usize> {
     /// Optional verifier data that is registered as public inputs.
     /// This is used in cyclic recursion to hold the circuit's own verifier key.
     pub(crate) verifier_data_public_input: Option<VerifierCircuitTarget>,
+
+    /// A cheap, order-sensitive fingerprint of the gates and copy constraints added so far. See
+    /// `live_fingerprint`.
+    live_fingerprint: u64,
+}
+
+const FNV_OFFSET_BASIS: u64 = 0xcbf29ce484222325;
+const FNV_PRIME: u64 = 0x100000001b3;
+
+/// Statistics about a circuit under construction, as returned by `CircuitBuilder::stats`.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct CircuitStats {
+    /// The number of gate instances, i.e. rows.
+    pub num_gates: usize,
+    /// The total number of constraints imposed by all gate instances.
+    pub num_constraints: usize,
+    /// The maximum degree of any gate used.
+    pub degree: usize,
+    /// The fraction of routed wires, across all rows, which are taken by gates.
+    pub routed_wire_utilization: f64,
 }
 
 impl<F: RichField + Extendable<D>, const D: usize> CircuitBuilder<F, D> {
@@ -123,6 +153,7 @@ impl<F: RichField + Extendable<D>, const D: usize> CircuitBuilder<F, D> {
             constant_generators: Vec::new(),
             goal_common_data: None,
             verifier_data_public_input: None,
+            live_fingerprint: FNV_OFFSET_BASIS,
         };
         builder.check_config();
         builder
@@ -131,19 +162,20 @@ impl<F: RichField + Extendable<D>, const D: usize> CircuitBuilder<F, D> {
     fn check_config(&self) {
         let &CircuitConfig {
             security_bits,
-            fri_config:
-                FriConfig {
-                    rate_bits,
-                    proof_of_work_bits,
-                    num_query_rounds,
-                    ..
-                },
+            max_arithmetic_ops_per_row,
+            ref fri_config,
             ..
         } = &self.config;
 
+        assert_ne!(
+            max_arithmetic_ops_per_row,
+            Some(0),
+            "Arithmetic gates need at least one operation per row"
+        );
+
         // Conjectured FRI security; see the ethSTARK paper.
         let fri_field_bits = F::Extension::order().bits() as usize;
-        let fri_query_security_bits = num_query_rounds * rate_bits + proof_of_work_bits as usize;
+        let fri_query_security_bits = fri_config.query_security_bits();
         let fri_security_bits = fri_field_bits.min(fri_query_security_bits);
         assert!(
             fri_security_bits >= security_bits,
@@ -151,6 +183,9 @@ impl<F: RichField + Extendable<D>, const D: usize> CircuitBuilder<F, D> {
         );
     }
 
+    /// Sets an application-specific tag, which is hashed into the circuit digest. The challenger
+    /// observes that digest before anything else, so proofs for the same circuit under different
+    /// domains are not interchangeable.
     pub fn set_domain_separator(&mut self, separator: Vec<F>) {
         assert!(self.domain_separator.is_none());
         self.domain_separator = Some(separator);
@@ -165,6 +200,14 @@ impl<F: RichField + Extendable<D>, const D: usize> CircuitBuilder<F, D> {
         self.public_inputs.push(target);
     }
 
+    /// Registers the given target as a public input if `cond` holds, and does nothing otherwise.
+    /// The number of public inputs of the built circuit, and hence of its proofs, depends on `cond`.
+    pub fn register_public_input_if(&mut self, cond: bool, target: Target) {
+        if cond {
+            self.register_public_input(target);
+        }
+    }
+
     /// Registers the given targets as public inputs.
     pub fn register_public_inputs(&mut self, targets: &[Target]) {
         targets.iter().for_each(|&t| self.register_public_input(t));
@@ -238,6 +281,193 @@ impl<F: RichField + Extendable<D>, const D: usize> CircuitBuilder<F, D> {
         b
     }
 
+    /// Returns the virtual targets which are not involved in any copy constraint, which are
+    /// neither public inputs nor constants, and which no generator writes. Such targets are not
+    /// bound by any gate constraint, so whatever value the partial witness assigns to them goes
+    /// unchecked. This usually indicates a missing `connect`.
+    ///
+    /// Generator outputs are found as in `generators_writing`, so targets written by generators
+    /// which reject that probe are still reported. This runs every generator once, so `build` does
+    /// not call it; call it explicitly while developing a circuit.
+    pub fn unconstrained_targets(&self) -> Vec<Target> {
+        let mut constrained = self
+            .copy_constraints
+            .iter()
+            .flat_map(|cc| [cc.pair.0, cc.pair.1])
+            .collect::<HashSet<_>>();
+        constrained.extend(&self.public_inputs);
+        constrained.extend(self.targets_to_constants.keys());
+
+        let candidates = (0..self.virtual_target_index)
+            .map(|index| Target::VirtualTarget { index })
+            .filter(|t| !constrained.contains(t))
+            .collect::<Vec<_>>();
+        // Probing generators is comparatively expensive, so skip it in the common case.
+        if candidates.is_empty() {
+            return candidates;
+        }
+
+        let mut forest = self.partition_forest();
+        forest.compress_paths();
+        // Gate generators only write the wires of their own row, so they are not probed.
+        let generated = self
+            .generators
+            .iter()
+            .flat_map(|generator| self.probe_outputs(generator.as_ref(), &forest))
+            .collect::<HashSet<_>>();
+        candidates
+            .into_iter()
+            .filter(|t| !generated.contains(t))
+            .collect()
+    }
+
+    /// Checks that the copy constraints added so far never connect the targets of two distinct
+    /// constants, which would make the circuit unprovable.
+    pub fn check_copy_constraints(&self) -> Result<(), ConstraintError<F>> {
+        let mut forest = self.partition_forest();
+
+        // Visit constants in target order, so that the reported pair is deterministic.
+        let mut constants = self.targets_to_constants.iter().collect::<Vec<_>>();
+        constants.sort_by_key(|&(&target, _)| target);
+        let mut partition_constants = HashMap::new();
+        for (&target, &c) in constants {
+            let partition = forest.find(forest.target_index(target));
+            let &mut first = partition_constants.entry(partition).or_insert(c);
+            if first != c {
+                return Err(ConstraintError::ConflictingConstants { first, second: c });
+            }
+        }
+        Ok(())
+    }
+
+    /// Returns the ids of the generators added so far which read `t`, or a target connected to it.
+    /// Generators without an id are listed by their `Debug` representation. Gate generators are
+    /// included, although they are only added to the circuit by `build`.
+    pub fn generators_depending_on(&self, t: Target) -> Vec<String> {
+        let mut forest = self.partition_forest();
+        let partition = forest.find(forest.target_index(t));
+        self.generator_ids_where(|generator| {
+            generator
+                .watch_list()
+                .into_iter()
+                .any(|watch| forest.find(forest.target_index(watch)) == partition)
+        })
+    }
+
+    /// Returns the ids of the generators added so far which write `t`, or a target connected to
+    /// it, in the same format as `generators_depending_on`.
+    ///
+    /// Generators do not declare their outputs, so each generator is run once on a witness in which
+    /// the targets it reads are set to one. This only reports the outputs of generators which read
+    /// nothing but their watched targets, and accept that witness. Generators which panic on it are
+    /// skipped, as are all probed generators without the `std` feature.
+    pub fn generators_writing(&self, t: Target) -> Vec<String> {
+        let mut forest = self.partition_forest();
+        forest.compress_paths();
+        let partition = forest.parents[forest.target_index(t)];
+        self.generator_ids_where(|generator| {
+            self.probe_outputs(generator, &forest)
+                .into_iter()
+                .any(|target| forest.parents[forest.target_index(target)] == partition)
+        })
+    }
+
+    /// Runs `generator` once on a witness in which the targets it watches are set to one, and
+    /// returns the targets it writes, or nothing if the generator panics. `forest` must have
+    /// compressed paths.
+    #[cfg(feature = "std")]
+    fn probe_outputs(&self, generator: &dyn WitnessGenerator<F>, forest: &Forest) -> Vec<Target> {
+        let mut witness =
+            PartitionWitness::new(self.config.num_wires, self.num_gates(), &forest.parents);
+        for watch in generator.watch_list() {
+            witness.set_target(watch, F::ONE);
+        }
+        let mut out_buffer = GeneratedValues::empty();
+        // Valid generators may reject the made-up witness, e.g. by asserting that two inputs differ.
+        let run = panic::catch_unwind(AssertUnwindSafe(|| {
+            generator.run(&witness, &mut out_buffer);
+        }));
+        if run.is_err() {
+            return Vec::new();
+        }
+        out_buffer
+            .target_values
+            .into_iter()
+            .map(|(target, _)| target)
+            .collect()
+    }
+
+    /// Panics cannot be caught without `std`, so generators are not probed.
+    #[cfg(not(feature = "std"))]
+    fn probe_outputs(&self, _generator: &dyn WitnessGenerator<F>, _forest: &Forest) -> Vec<Target> {
+        Vec::new()
+    }
+
+    /// Returns the ids of the generators added so far, including gate generators, which satisfy
+    /// `predicate`.
+    fn generator_ids_where<P: FnMut(&dyn WitnessGenerator<F>) -> bool>(
+        &self,
+        mut predicate: P,
+    ) -> Vec<String> {
+        let gate_generators = self.gate_generators();
+        self.generators
+            .iter()
+            .chain(&gate_generators)
+            .filter(|generator| predicate(generator.as_ref()))
+            .map(|generator| match generator.id() {
+                Some(id) => format!("{id:?}"),
+                None => format!("{generator:?}"),
+            })
+            .collect()
+    }
+
+    /// The generators of the gates added so far, excluding those of unused operations.
+    fn gate_generators(&self) -> Vec<Box<dyn WitnessGenerator<F>>> {
+        // Map between gates where not all generators are used and the gate's number of used generators.
+        let incomplete_gates = self
+            .current_slots
+            .values()
+            .flat_map(|current_slot| current_slot.current_slot.values().copied())
+            .collect::<HashMap<_, _>>();
+
+        self.gate_instances
+            .iter()
+            .enumerate()
+            .flat_map(|(index, gate)| {
+                let mut gens = gate.gate_ref.0.generators(index, &gate.constants);
+                // Remove unused generators, if any. Some gates, such as `QuadraticGate`, have
+                // operations but no generators, hence `truncate` rather than `drain`.
+                if let Some(&op) = incomplete_gates.get(&index) {
+                    gens.truncate(op);
+                }
+                gens
+            })
+            .collect()
+    }
+
+    /// The partition of the targets added so far induced by the copy constraints.
+    fn partition_forest(&self) -> Forest {
+        let config = &self.config;
+        let mut forest = Forest::new(
+            config.num_wires,
+            config.num_routed_wires,
+            self.num_gates(),
+            self.virtual_target_index,
+        );
+        for row in 0..self.num_gates() {
+            for column in 0..config.num_wires {
+                forest.add(Target::Wire(Wire { row, column }));
+            }
+        }
+        for index in 0..self.virtual_target_index {
+            forest.add(Target::VirtualTarget { index });
+        }
+        for &CopyConstraint { pair: (a, b), .. } in &self.copy_constraints {
+            forest.merge(a, b);
+        }
+        forest
+    }
+
     /// Add a virtual target and register it as a public input.
     pub fn add_virtual_public_input(&mut self) -> Target {
         let t = self.add_virtual_target();
@@ -293,6 +523,13 @@ impl<F: RichField + Extendable<D>, const D: usize> CircuitBuilder<F, D> {
         // could be modified later, i.e. in the case of `ConstantGate`. We will add them later in
         // `build` instead.
 
+        let fingerprint_data = [0].into_iter().chain(gate_type.id().into_bytes()).chain(
+            constants
+                .iter()
+                .flat_map(|c| c.to_canonical_u64().to_le_bytes()),
+        );
+        self.update_live_fingerprint(fingerprint_data);
+
         // Register this gate type if we haven't seen it before.
         let gate_ref = GateRef::new(gate_type);
         self.gates.insert(gate_ref.clone());
@@ -348,10 +585,34 @@ impl<F: RichField + Extendable<D>, const D: usize> CircuitBuilder<F, D> {
             y.is_routable(&self.config),
             "Tried to route a wire that isn't routable"
         );
+        let fingerprint_data = [1]
+            .into_iter()
+            .chain(Self::target_fingerprint_data(x))
+            .chain(Self::target_fingerprint_data(y));
+        self.update_live_fingerprint(fingerprint_data);
         self.copy_constraints
             .push(CopyConstraint::new((x, y), self.context_log.open_stack()));
     }
 
+    /// Mixes `bytes` into the live fingerprint, using 64-bit FNV-1a.
+    fn update_live_fingerprint(&mut self, bytes: impl IntoIterator<Item = u8>) {
+        for byte in bytes {
+            self.live_fingerprint ^= byte as u64;
+            self.live_fingerprint = self.live_fingerprint.wrapping_mul(FNV_PRIME);
+        }
+    }
+
+    fn target_fingerprint_data(target: Target) -> impl Iterator<Item = u8> {
+        let (tag, a, b) = match target {
+            Target::Wire(Wire { row, column }) => (0, row, column),
+            Target::VirtualTarget { index } => (1, index, 0),
+        };
+        [tag]
+            .into_iter()
+            .chain((a as u64).to_le_bytes())
+            .chain((b as u64).to_le_bytes())
+    }
+
     pub fn assert_zero(&mut self, x: Target) {
         let zero = self.zero();
         self.connect(x, zero);
@@ -415,6 +676,23 @@ impl<F: RichField + Extendable<D>, const D: usize> CircuitBuilder<F, D> {
         target
     }
 
+    /// Constrains `x` to equal `c`. If `x` is a virtual target which isn't already bound to a
+    /// constant, and `c` has no target yet, `x` itself becomes the target for `c`, so it is filled
+    /// and constrained by the constant generator machinery without any routing from a separate
+    /// constant target. Otherwise this is equivalent to `connect(x, constant(c))`.
+    pub fn set_to_constant(&mut self, x: Target, c: F) {
+        if matches!(x, Target::VirtualTarget { .. })
+            && !self.targets_to_constants.contains_key(&x)
+            && !self.constants_to_targets.contains_key(&c)
+        {
+            self.constants_to_targets.insert(c, x);
+            self.targets_to_constants.insert(x, c);
+        } else {
+            let c = self.constant(c);
+            self.connect(x, c);
+        }
+    }
+
     pub fn constants(&mut self, constants: &[F]) -> Vec<Target> {
         constants.iter().map(|&c| self.constant(c)).collect()
     }
@@ -517,6 +795,123 @@ impl<F: RichField + Extendable<D>, const D: usize> CircuitBuilder<F, D> {
         (gate_idx, slot_idx)
     }
 
+    /// The number of routed wires left unused in the most recently added row. If that row holds a
+    /// batched gate with free operation slots, only the wires of the operations used so far count
+    /// as taken. With no rows yet, a whole fresh row of `num_routed_wires` is reported.
+    pub fn free_routed_wires_in_current_row(&self) -> usize {
+        let num_routed_wires = self.config.num_routed_wires;
+        if self.num_gates() == 0 {
+            return num_routed_wires;
+        }
+        num_routed_wires - self.routed_wires_used_in_row(self.num_gates() - 1)
+    }
+
+    /// The number of routed wires taken by the gate in `row`, counting only the operations used so
+    /// far if the gate still has free operation slots.
+    fn routed_wires_used_in_row(&self, row: usize) -> usize {
+        let gate = &self.gate_instances[row].gate_ref.0;
+        let used_wires = match self.next_op_in_row(row) {
+            Some(op) => op * gate.num_wires() / gate.num_ops(),
+            None => gate.num_wires(),
+        };
+
+        used_wires.min(self.config.num_routed_wires)
+    }
+
+    /// The index of the next free operation slot of the gate in `row`, or `None` if all of its
+    /// operations are used.
+    fn next_op_in_row(&self, row: usize) -> Option<usize> {
+        self.current_slots
+            .values()
+            .flat_map(|slot| slot.current_slot.values())
+            .find(|&&(gate_idx, _)| gate_idx == row)
+            .map(|&(_, op)| op)
+    }
+
+    /// A cheap fingerprint of the gates and copy constraints added so far, in order, which is
+    /// updated as they are added rather than recomputed. It is meant for detecting edits, e.g. in
+    /// an editor UI; unlike the circuit digest, it is not collision resistant, and it does not
+    /// cover gates added during `build` or constants set after a gate is added.
+    pub fn live_fingerprint(&self) -> u64 {
+        self.live_fingerprint
+    }
+
+    /// Statistics about the gates added so far, e.g. to check a circuit against a budget in tests.
+    /// Gates added during `build`, such as the public input gate, constant gates and padding, are
+    /// not included.
+    pub fn stats(&self) -> CircuitStats {
+        let num_gates = self.num_gates();
+        let num_constraints = self
+            .gate_instances
+            .iter()
+            .map(|inst| inst.gate_ref.0.num_constraints())
+            .sum();
+        let degree = self
+            .gates
+            .iter()
+            .map(|gate| gate.0.degree())
+            .max()
+            .unwrap_or(0);
+        let routed_wires_used: usize = (0..num_gates)
+            .map(|row| self.routed_wires_used_in_row(row))
+            .sum();
+        let routed_wire_utilization = if num_gates == 0 {
+            0.0
+        } else {
+            routed_wires_used as f64 / (num_gates * self.config.num_routed_wires) as f64
+        };
+
+        CircuitStats {
+            num_gates,
+            num_constraints,
+            degree,
+            routed_wire_utilization,
+        }
+    }
+
+    /// A heuristic count of the degrees of freedom of the witness: the number of independent
+    /// values in the circuit, minus the number of gate constraints on them.
+    ///
+    /// Values are the classes of targets joined by copy constraints, among the wires of the gates
+    /// added so far and all virtual targets; classes holding a constant are not counted. Only the
+    /// wires and constraints of used operations of batched gates are counted. Constraints are
+    /// assumed to be independent, which they need not be, so this is only a rough guide. Still, a
+    /// circuit whose witness is determined by its public inputs should not have more degrees of
+    /// freedom than it has independent public inputs; any excess suggests it is under-constrained.
+    pub fn degrees_of_freedom(&self) -> isize {
+        let mut forest = self.partition_forest();
+        let mut num_constraints = 0;
+        let mut targets = (0..self.virtual_target_index)
+            .map(|index| Target::VirtualTarget { index })
+            .collect::<Vec<_>>();
+        for (row, inst) in self.gate_instances.iter().enumerate() {
+            let gate = &inst.gate_ref.0;
+            let (num_wires, gate_constraints) = match self.next_op_in_row(row) {
+                Some(op) => (
+                    op * gate.num_wires() / gate.num_ops(),
+                    op * gate.num_constraints() / gate.num_ops(),
+                ),
+                None => (gate.num_wires(), gate.num_constraints()),
+            };
+            targets.extend((0..num_wires).map(|column| Target::wire(row, column)));
+            num_constraints += gate_constraints;
+        }
+
+        let constant_partitions = self
+            .targets_to_constants
+            .keys()
+            .map(|&t| forest.find(forest.target_index(t)))
+            .collect::<HashSet<_>>();
+        let num_values = targets
+            .into_iter()
+            .map(|t| forest.find(forest.target_index(t)))
+            .filter(|partition| !constant_partitions.contains(partition))
+            .collect::<HashSet<_>>()
+            .len();
+
+        num_values as isize - num_constraints as isize
+    }
+
     fn fri_params(&self, degree_bits: usize) -> FriParams {
         self.config
             .fri_config
@@ -586,6 +981,44 @@ impl<F: RichField + Extendable<D>, const D: usize> CircuitBuilder<F, D> {
         }
     }
 
+    /// Runs `fill` once for each unused operation in the partially used row of `gate`, if any.
+    /// This is meant for gates whose unused operations would be unsatisfiable with their wires
+    /// defaulting to zero, where `fill` adds a satisfiable operation to the gate.
+    fn fill_partial_gate<G: Gate<F, D>>(&mut self, gate: G, mut fill: impl FnMut(&mut Self)) {
+        let num_ops = gate.num_ops();
+        let next_op = self
+            .current_slots
+            .get(&GateRef::new(gate))
+            .and_then(|slot| slot.current_slot.get(&Vec::new()))
+            .map(|&(_, op)| op);
+        if let Some(next_op) = next_op {
+            for _ in next_op..num_ops {
+                fill(self);
+            }
+        }
+    }
+
+    /// Unused operations in a `QuadraticGate` would need their own solution of the gate's relation,
+    /// so we copy the wires of the first operation, which is always used, into them instead.
+    fn fill_quadratic_gates(&mut self) {
+        let gate = QuadraticGate::new_from_config(&self.config);
+        let num_ops = gate.num_ops;
+        let mut partial_rows = self
+            .current_slots
+            .get(&GateRef::new(gate))
+            .map(|slot| slot.current_slot.values().copied().collect::<Vec<_>>())
+            .unwrap_or_default();
+        // Sort the rows so that the copy constraints do not depend on the hash map's order.
+        partial_rows.sort_unstable();
+        for (row, next_op) in partial_rows {
+            for i in next_op..num_ops {
+                for wire in [QuadraticGate::wire_ith_x, QuadraticGate::wire_ith_y] {
+                    self.connect(Target::wire(row, wire(0)), Target::wire(row, wire(i)));
+                }
+            }
+        }
+    }
+
     fn blind_and_pad(&mut self) {
         if self.config.zero_knowledge {
             self.blind();
@@ -671,31 +1104,7 @@ impl<F: RichField + Extendable<D>, const D: usize> CircuitBuilder<F, D> {
     fn sigma_vecs(&self, k_is: &[F], subgroup: &[F]) -> (Vec<PolynomialValues<F>>, Forest) {
         let degree = self.gate_instances.len();
         let degree_log = log2_strict(degree);
-        let config = &self.config;
-        let mut forest = Forest::new(
-            config.num_wires,
-            config.num_routed_wires,
-            degree,
-            self.virtual_target_index,
-        );
-
-        for gate in 0..degree {
-            for input in 0..config.num_wires {
-                forest.add(Target::Wire(Wire {
-                    row: gate,
-                    column: input,
-                }));
-            }
-        }
-
-        for index in 0..self.virtual_target_index {
-            forest.add(Target::VirtualTarget { index });
-        }
-
-        for &CopyConstraint { pair: (a, b), .. } in &self.copy_constraints {
-            forest.merge(a, b);
-        }
-
+        let mut forest = self.partition_forest();
         forest.compress_paths();
 
         let wire_partition = forest.wire_partition();
@@ -743,6 +1152,19 @@ impl<F: RichField + Extendable<D>, const D: usize> CircuitBuilder<F, D> {
         let rate_bits = self.config.fri_config.rate_bits;
         let cap_height = self.config.fri_config.cap_height;
 
+        // Unused operations in these gates would be unsatisfiable with zero wires, so fill them
+        // with operations on constants. Other packed gates accept all-zero operations as they are.
+        self.fill_partial_gate(BaseInverseGate::new_from_config(&self.config), |builder| {
+            let one = builder.one();
+            builder.inverse_packed(one);
+        });
+        self.fill_partial_gate(InvSumGate::new_from_config(&self.config), |builder| {
+            let zero = builder.zero();
+            let one = builder.one();
+            builder.inv_sum(one, zero);
+        });
+        self.fill_quadratic_gates();
+
         // Hash the public inputs, and route them to a `PublicInputGate` which will enforce that
         // those hash wires match the claimed public inputs.
         let num_public_inputs = self.public_inputs.len();
@@ -810,6 +1232,14 @@ impl<F: RichField + Extendable<D>, const D: usize> CircuitBuilder<F, D> {
         constant_vecs.extend(self.constant_polys());
         let num_constants = constant_vecs.len();
 
+        // The number of rows using each gate, in the same order as `gates`.
+        let gate_ids = gates.iter().map(|g| g.0.id()).collect::<Vec<_>>();
+        let mut gate_instance_counts = vec![0; gates.len()];
+        for instance in &self.gate_instances {
+            let id = instance.gate_ref.0.id();
+            gate_instance_counts[gate_ids.iter().position(|g| *g == id).unwrap()] += 1;
+        }
+
         let subgroup = F::two_adic_subgroup(degree_bits);
 
         let k_is = get_unique_coset_shifts(degree, self.config.num_routed_wires);
@@ -833,28 +1263,9 @@ impl<F: RichField + Extendable<D>, const D: usize> CircuitBuilder<F, D> {
             Some(&fft_root_table),
         );
 
-        // Map between gates where not all generators are used and the gate's number of used generators.
-        let incomplete_gates = self
-            .current_slots
-            .values()
-            .flat_map(|current_slot| current_slot.current_slot.values().copied())
-            .collect::<HashMap<_, _>>();
-
         // Add gate generators.
-        self.add_generators(
-            self.gate_instances
-                .iter()
-                .enumerate()
-                .flat_map(|(index, gate)| {
-                    let mut gens = gate.gate_ref.0.generators(index, &gate.constants);
-                    // Remove unused generators, if any.
-                    if let Some(&op) = incomplete_gates.get(&index) {
-                        gens.drain(op..);
-                    }
-                    gens
-                })
-                .collect(),
-        );
+        let gate_generators = self.gate_generators();
+        self.add_generators(gate_generators);
 
         // Index generator indices by their watched targets.
         let mut generator_indices_by_watches = BTreeMap::new();
@@ -922,6 +1333,7 @@ impl<F: RichField + Extendable<D>, const D: usize> CircuitBuilder<F, D> {
             representative_map: forest.parents,
             fft_root_table: Some(fft_root_table),
             circuit_digest,
+            gate_instance_counts,
         };
 
         let verifier_only = VerifierOnlyCircuitData {
@@ -953,3 +1365,341 @@ impl<F: RichField + Extendable<D>, const D: usize> CircuitBuilder<F, D> {
         circuit_data.verifier_data()
     }
 }
+
+#[cfg(test)]
+impl<F: RichField + Extendable<D>, const D: usize> CircuitBuilder<F, D> {
+    /// Computes the 100th element of the Fibonacci-like sequence starting with `initial_a` and
+    /// `initial_b`, using 99 additions. Tests use this as a small circuit to prove.
+    pub(crate) fn fibonacci_100(&mut self, initial_a: Target, initial_b: Target) -> Target {
+        let mut prev_target = initial_a;
+        let mut cur_target = initial_b;
+        for _ in 0..99 {
+            let temp = self.add(prev_target, cur_target);
+            prev_target = cur_target;
+            cur_target = temp;
+        }
+        cur_target
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use anyhow::Result;
+
+    use crate::field::types::Field;
+    use crate::gates::arithmetic_base::ArithmeticGate;
+    use crate::gates::arithmetic_extension::ArithmeticExtensionGate;
+    use crate::gates::constant::ConstantGate;
+    use crate::gates::noop::NoopGate;
+    use crate::iop::target::Target;
+    use crate::iop::witness::{PartialWitness, WitnessWrite};
+    use crate::plonk::circuit_builder::CircuitBuilder;
+    use crate::plonk::circuit_data::CircuitConfig;
+    use crate::plonk::config::{GenericConfig, PoseidonGoldilocksConfig};
+    use crate::plonk::copy_constraint::ConstraintError;
+
+    #[test]
+    fn test_check_copy_constraints() {
+        const D: usize = 2;
+        type C = PoseidonGoldilocksConfig;
+        type F = <C as GenericConfig<D>>::F;
+        let config = CircuitConfig::standard_recursion_config();
+        let mut builder = CircuitBuilder::<F, D>::new(config);
+
+        let x = builder.add_virtual_target();
+        let y = builder.add_virtual_target();
+        let one = builder.constant(F::ONE);
+        let two = builder.constant(F::TWO);
+        builder.connect(x, one);
+        builder.connect(y, two);
+        assert_eq!(builder.check_copy_constraints(), Ok(()));
+
+        builder.connect(x, y);
+        let err = builder.check_copy_constraints().unwrap_err();
+        assert_eq!(
+            err,
+            ConstraintError::ConflictingConstants {
+                first: F::ONE,
+                second: F::TWO,
+            }
+        );
+        assert_eq!(
+            err.to_string(),
+            "Copy constraints connect the distinct constants 1 and 2"
+        );
+    }
+
+    #[test]
+    fn test_stats() {
+        const D: usize = 2;
+        type C = PoseidonGoldilocksConfig;
+        type F = <C as GenericConfig<D>>::F;
+        let config = CircuitConfig::standard_recursion_config();
+        let mut builder = CircuitBuilder::<F, D>::new(config);
+        assert_eq!(builder.stats().num_gates, 0);
+
+        // The 100th Fibonacci number takes 99 additions, packed 20 per `ArithmeticGate`.
+        let initial_a = builder.add_virtual_target();
+        let initial_b = builder.add_virtual_target();
+        let cur_target = builder.fibonacci_100(initial_a, initial_b);
+        builder.register_public_input(cur_target);
+
+        let stats = builder.stats();
+        assert_eq!(stats.num_gates, 5);
+        assert_eq!(stats.num_constraints, 100);
+        assert_eq!(stats.degree, 3);
+        // The last gate has one free operation, i.e. 4 free routed wires out of 5 * 80.
+        assert_eq!(stats.routed_wire_utilization, 396.0 / 400.0);
+    }
+
+    #[test]
+    fn test_degrees_of_freedom() {
+        const D: usize = 2;
+        type C = PoseidonGoldilocksConfig;
+        type F = <C as GenericConfig<D>>::F;
+        let config = CircuitConfig::standard_recursion_config();
+        let mut builder = CircuitBuilder::<F, D>::new(config);
+        assert_eq!(builder.degrees_of_freedom(), 0);
+
+        // Each of the 99 additions introduces one value, its sum, and one constraint on it, which
+        // leaves the two initial values free.
+        let initial_a = builder.add_virtual_public_input();
+        let initial_b = builder.add_virtual_public_input();
+        let cur_target = builder.fibonacci_100(initial_a, initial_b);
+        builder.register_public_input(cur_target);
+        assert_eq!(builder.degrees_of_freedom(), 2);
+
+        // A target read by no gate is another free value.
+        builder.add_virtual_target();
+        assert_eq!(builder.degrees_of_freedom(), 3);
+
+        // Pinning the initial values removes their freedom.
+        let zero = builder.zero();
+        let one = builder.one();
+        builder.connect(initial_a, zero);
+        builder.connect(initial_b, one);
+        assert_eq!(builder.degrees_of_freedom(), 1);
+    }
+
+    #[test]
+    fn test_register_public_input_if() -> Result<()> {
+        const D: usize = 2;
+        type C = PoseidonGoldilocksConfig;
+        type F = <C as GenericConfig<D>>::F;
+
+        let build = |expose_square: bool| {
+            let config = CircuitConfig::standard_recursion_config();
+            let mut builder = CircuitBuilder::<F, D>::new(config);
+            let x = builder.add_virtual_public_input();
+            let square = builder.square(x);
+            builder.register_public_input_if(expose_square, square);
+            let mut pw = PartialWitness::new();
+            pw.set_target(x, F::from_canonical_u64(3));
+            (builder.build::<C>(), pw)
+        };
+
+        let (with_square, pw) = build(true);
+        assert_eq!(with_square.common.num_public_inputs, 2);
+        let with_square_proof = with_square.prove(pw)?;
+        assert_eq!(
+            with_square_proof.public_inputs,
+            [F::from_canonical_u64(3), F::from_canonical_u64(9)]
+        );
+        with_square.verify(with_square_proof.clone())?;
+
+        let (without_square, pw) = build(false);
+        assert_eq!(without_square.common.num_public_inputs, 1);
+        let without_square_proof = without_square.prove(pw)?;
+        assert_eq!(
+            without_square_proof.public_inputs,
+            [F::from_canonical_u64(3)]
+        );
+        without_square.verify(without_square_proof.clone())?;
+
+        // Proofs of one variant are rejected by the verifier of the other.
+        assert!(without_square.verify(with_square_proof).is_err());
+        assert!(with_square.verify(without_square_proof).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_live_fingerprint() {
+        const D: usize = 2;
+        type C = PoseidonGoldilocksConfig;
+        type F = <C as GenericConfig<D>>::F;
+        let config = CircuitConfig::standard_recursion_config();
+
+        // Records the fingerprint after each of three different gates, added in the given order,
+        // and after a final copy constraint.
+        let fingerprint_after = |order: [usize; 3]| {
+            let mut builder = CircuitBuilder::<F, D>::new(config.clone());
+            let mut fingerprints = vec![builder.live_fingerprint()];
+            for i in order {
+                match i {
+                    0 => builder.add_gate(NoopGate, vec![]),
+                    1 => builder.add_gate(ConstantGate { num_consts: 2 }, vec![F::ONE]),
+                    _ => builder.add_gate(ArithmeticExtensionGate { num_ops: 1 }, vec![]),
+                };
+                fingerprints.push(builder.live_fingerprint());
+            }
+            let a = builder.add_virtual_target();
+            let b = builder.add_virtual_target();
+            builder.connect(a, b);
+            fingerprints.push(builder.live_fingerprint());
+            fingerprints
+        };
+
+        let fingerprints = fingerprint_after([0, 1, 2]);
+        for i in 1..fingerprints.len() {
+            assert!(!fingerprints[..i].contains(&fingerprints[i]));
+        }
+        assert_eq!(fingerprint_after([0, 1, 2]), fingerprints);
+        assert_ne!(fingerprint_after([2, 1, 0]).last(), fingerprints.last());
+    }
+
+    #[test]
+    fn test_set_to_constant() -> Result<()> {
+        const D: usize = 2;
+        type C = PoseidonGoldilocksConfig;
+        type F = <C as GenericConfig<D>>::F;
+        let config = CircuitConfig::standard_recursion_config();
+        let mut builder = CircuitBuilder::<F, D>::new(config);
+
+        let c = F::from_canonical_u64(12345);
+        let x = builder.add_virtual_target();
+        let y = builder.add_virtual_target();
+        builder.set_to_constant(x, c);
+        // `c` is now bound to `x`, so `y` is routed to it.
+        builder.set_to_constant(y, c);
+        assert_eq!(builder.num_gates(), 0);
+        assert_eq!(builder.target_as_constant(x), Some(c));
+        builder.register_public_input(x);
+        builder.register_public_input(y);
+
+        let data = builder.build::<C>();
+        let proof = data.prove(PartialWitness::new())?;
+        assert_eq!(proof.public_inputs, vec![c, c]);
+        data.verify(proof)
+    }
+
+    #[test]
+    fn test_free_routed_wires_in_current_row() {
+        const D: usize = 2;
+        type C = PoseidonGoldilocksConfig;
+        type F = <C as GenericConfig<D>>::F;
+        let config = CircuitConfig::standard_recursion_config();
+        let num_routed_wires = config.num_routed_wires;
+        let num_ops = ArithmeticExtensionGate::<D>::num_ops(&config);
+        let mut builder = CircuitBuilder::<F, D>::new(config);
+        assert_eq!(builder.free_routed_wires_in_current_row(), num_routed_wires);
+
+        let arithmetic_op = |builder: &mut CircuitBuilder<F, D>| {
+            let [a, b, c] = [(); 3].map(|_| builder.add_virtual_extension_target());
+            builder.arithmetic_extension(F::TWO, F::TWO, a, b, c);
+        };
+
+        arithmetic_op(&mut builder);
+        assert_eq!(
+            builder.free_routed_wires_in_current_row(),
+            num_routed_wires - 4 * D
+        );
+        for _ in 1..num_ops {
+            arithmetic_op(&mut builder);
+        }
+        assert_eq!(builder.num_gates(), 1);
+        assert_eq!(
+            builder.free_routed_wires_in_current_row(),
+            num_routed_wires - num_ops * 4 * D
+        );
+
+        // The next operation opens a new row.
+        arithmetic_op(&mut builder);
+        assert_eq!(builder.num_gates(), 2);
+        assert_eq!(
+            builder.free_routed_wires_in_current_row(),
+            num_routed_wires - 4 * D
+        );
+    }
+
+    #[test]
+    fn test_unconstrained_targets() {
+        const D: usize = 2;
+        type C = PoseidonGoldilocksConfig;
+        type F = <C as GenericConfig<D>>::F;
+        let config = CircuitConfig::standard_recursion_config();
+        let mut builder = CircuitBuilder::<F, D>::new(config);
+
+        let dangling = builder.add_virtual_target();
+        let x = builder.add_virtual_target();
+        let y = builder.add_virtual_target();
+        let pi = builder.add_virtual_public_input();
+        let z = builder.mul(x, y);
+        builder.connect(z, pi);
+        let c = builder.one();
+        // A target written by a generator is not reported.
+        let generated = builder.add_virtual_target();
+        builder.generate_copy(x, generated);
+
+        let unconstrained = builder.unconstrained_targets();
+        assert_eq!(unconstrained, vec![dangling]);
+        for t in [x, y, z, pi, c, generated] {
+            assert!(!unconstrained.contains(&t));
+        }
+    }
+
+    #[test]
+    fn test_unconstrained_targets_rejected_probe() {
+        const D: usize = 2;
+        type C = PoseidonGoldilocksConfig;
+        type F = <C as GenericConfig<D>>::F;
+        let config = CircuitConfig::standard_recursion_config();
+        let mut builder = CircuitBuilder::<F, D>::new(config);
+
+        // The generator of `assert_not_equal_ext` panics when both inputs are probed with one.
+        let x = builder.add_virtual_extension_target();
+        let y = builder.add_virtual_extension_target();
+        builder.register_public_inputs(&x.0);
+        builder.register_public_inputs(&y.0);
+        builder.assert_not_equal_ext(x, y);
+        let dangling = builder.add_virtual_target();
+
+        assert!(builder.unconstrained_targets().contains(&dangling));
+        builder.build::<C>();
+    }
+
+    #[test]
+    fn test_generator_introspection() {
+        const D: usize = 2;
+        type C = PoseidonGoldilocksConfig;
+        type F = <C as GenericConfig<D>>::F;
+
+        // The 100th Fibonacci number, starting from `initial_a, initial_b`.
+        let config = CircuitConfig::standard_recursion_config();
+        let mut builder = CircuitBuilder::<F, D>::new(config);
+        let initial_a = builder.add_virtual_target();
+        let initial_b = builder.add_virtual_target();
+        let cur_target = builder.fibonacci_100(initial_a, initial_b);
+
+        // The first addition is the first operation of the arithmetic gate in row 0, reading
+        // `initial_a` and `initial_b`. `initial_b` is also read by the second addition.
+        let arithmetic = vec!["ArithmeticBase".to_string()];
+        assert_eq!(builder.generators_depending_on(initial_a), arithmetic);
+        assert_eq!(
+            builder.generators_depending_on(Target::wire(
+                0,
+                ArithmeticGate::wire_ith_multiplicand_0(0)
+            )),
+            arithmetic
+        );
+        assert_eq!(builder.generators_depending_on(initial_b).len(), 2);
+        assert!(builder.generators_depending_on(cur_target).is_empty());
+
+        // Nothing sets the initial values, which must come from the witness.
+        assert!(builder.generators_writing(initial_a).is_empty());
+        assert_eq!(
+            builder.generators_writing(Target::wire(0, ArithmeticGate::wire_ith_output(0))),
+            arithmetic
+        );
+        assert_eq!(builder.generators_writing(cur_target), arithmetic);
+    }
+}
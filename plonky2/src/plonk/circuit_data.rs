@@ -1,10 +1,13 @@
 use alloc::boxed::Box;
 use alloc::collections::BTreeMap;
-use alloc::vec;
+use alloc::string::String;
 use alloc::vec::Vec;
+use alloc::{format, vec};
+use core::fmt::Debug;
 use core::ops::{Range, RangeFrom};
 
-use anyhow::Result;
+use anyhow::{anyhow, bail, ensure, Context, Result};
+use once_cell::unsync::OnceCell;
 
 use crate::field::extension::Extendable;
 use crate::field::fft::FftRootTable;
@@ -15,22 +18,41 @@ use crate::fri::structure::{
     FriBatchInfo, FriBatchInfoTarget, FriInstanceInfo, FriInstanceInfoTarget, FriOracleInfo,
     FriPolynomialInfo,
 };
-use crate::fri::{FriConfig, FriParams};
+use crate::fri::{FriConfig, FriParams, FriParamsExport};
+use crate::gates::arithmetic_extension::ArithmeticExtensionGate;
 use crate::gates::gate::GateRef;
 use crate::gates::selectors::SelectorsInfo;
 use crate::hash::hash_types::{HashOutTarget, MerkleCapTarget, RichField};
+use crate::hash::hashing::SPONGE_RATE;
+use crate::hash::merkle_proofs::{verify_merkle_proof, MerkleProof};
 use crate::hash::merkle_tree::MerkleCap;
 use crate::iop::ext_target::ExtensionTarget;
 use crate::iop::generator::WitnessGenerator;
 use crate::iop::target::Target;
-use crate::iop::witness::PartialWitness;
+use crate::iop::witness::{PartialWitness, PartitionWitness};
 use crate::plonk::circuit_builder::CircuitBuilder;
 use crate::plonk::config::{GenericConfig, Hasher};
-use crate::plonk::plonk_common::PlonkOracle;
+use crate::plonk::plonk_common::{salt_size, PlonkOracle};
 use crate::plonk::proof::{CompressedProofWithPublicInputs, ProofWithPublicInputs};
-use crate::plonk::prover::prove;
-use crate::plonk::verifier::verify;
+#[cfg(any(feature = "fixed_fri_queries", test))]
+use crate::plonk::prover::prove_with_fixed_query_indices;
+use crate::plonk::prover::{
+    prove, prove_and_return_witness, prove_until_commit, prove_with_dump, prove_with_oracle,
+    resume_from_checkpoint, PolynomialDump, ProverCheckpoint,
+};
+#[cfg(any(feature = "fixed_fri_queries", test))]
+use crate::plonk::verifier::verify_with_fixed_query_indices;
+use crate::plonk::verifier::{
+    verify, verify_all, verify_with_trace, VerificationFailure, VerificationTrace,
+};
+use crate::util::gate_serialization::GateSerializer;
+use crate::util::generator_serialization::WitnessGeneratorSerializer;
+use crate::util::partial_products::num_partial_products;
+#[cfg(feature = "std")]
+use crate::util::serialization::{Buffer, Remaining};
+use crate::util::serialization::{Read, Write};
 use crate::util::timing::TimingTree;
+use crate::util::{ceil_div_usize, log2_ceil, transpose};
 
 #[derive(Clone, Debug, Eq, PartialEq)]
 pub struct CircuitConfig {
@@ -48,6 +70,10 @@ pub struct CircuitConfig {
     /// A cap on the quotient polynomial's degree factor. The actual degree factor is derived
     /// systematically, but will never exceed this value.
     pub max_quotient_degree_factor: usize,
+    /// An optional cap on the number of operations packed into each row of the arithmetic gates,
+    /// which otherwise use as many operations as fit in the routed wires. Lowering it leaves
+    /// routed wires free for other gates in the same row.
+    pub max_arithmetic_ops_per_row: Option<usize>,
     pub fri_config: FriConfig,
 }
 
@@ -62,6 +88,94 @@ impl CircuitConfig {
         self.num_wires - self.num_routed_wires
     }
 
+    /// The number of operations of `wires_per_op` routed wires each that an arithmetic gate
+    /// packs into one row, respecting `max_arithmetic_ops_per_row`.
+    pub(crate) fn arithmetic_ops_per_row(&self, wires_per_op: usize) -> usize {
+        let num_ops = self.num_routed_wires / wires_per_op;
+        self.max_arithmetic_ops_per_row
+            .map_or(num_ops, |max_ops| num_ops.min(max_ops))
+    }
+
+    /// A rough estimate of the serialized size, in bytes, of a proof for a circuit of
+    /// `2^degree_bits` rows under this config, assuming a quadratic extension of a 64-bit field
+    /// and 4-element hashes. The polynomial counts are approximated from the config, as the exact
+    /// counts depend on the circuit's gates.
+    pub fn estimated_proof_size(&self, degree_bits: usize) -> usize {
+        const D: usize = 2;
+        const ELEMENT_BYTES: usize = 8;
+        const HASH_ELEMENTS: usize = 4;
+        const NUM_INITIAL_TREES: usize = 4;
+
+        let fri_params = self.fri_config.fri_params(degree_bits, self.zero_knowledge);
+        let cap_height = self.fri_config.cap_height;
+        let cap_elements = (1 << cap_height) * HASH_ELEMENTS;
+
+        let num_zs_partial_products = self.num_challenges
+            * (1 + num_partial_products(self.num_routed_wires, self.max_quotient_degree_factor));
+        let num_polys = self.num_constants
+            + self.num_routed_wires
+            + self.num_wires
+            + num_zs_partial_products
+            + self.num_challenges * self.max_quotient_degree_factor;
+
+        // Merkle caps of the initial trees, and the openings at `zeta` and `g * zeta`.
+        let mut total_elements = NUM_INITIAL_TREES * cap_elements;
+        total_elements += (num_polys + num_zs_partial_products) * D;
+
+        // Each query opens every initial tree, then one coset per folding step.
+        let lde_bits = fri_params.lde_bits();
+        let mut query_elements =
+            num_polys + NUM_INITIAL_TREES * (lde_bits - cap_height) * HASH_ELEMENTS;
+        let mut codeword_bits = lde_bits;
+        for &arity_bits in &fri_params.reduction_arity_bits {
+            codeword_bits -= arity_bits;
+            total_elements += cap_elements;
+            query_elements +=
+                (1 << arity_bits) * D + codeword_bits.saturating_sub(cap_height) * HASH_ELEMENTS;
+        }
+        total_elements += self.fri_config.num_query_rounds * query_elements;
+
+        // The final polynomial and the proof-of-work witness.
+        total_elements += fri_params.final_poly_len() * D + 1;
+
+        total_elements * ELEMENT_BYTES
+    }
+
+    /// Returns a copy of this config with the FRI rate, cap height and number of queries chosen to
+    /// minimize `estimated_proof_size(degree_bits)`, while keeping the conjectured FRI security at
+    /// least `security_bits`. Rates below `1/32` are not considered, as they make proving
+    /// prohibitively slow; the proof-of-work bits and reduction strategy are kept.
+    pub fn optimize_for_proof_size(&self, degree_bits: usize) -> Self {
+        const MAX_RATE_BITS: usize = 5;
+        const MAX_CAP_HEIGHT: usize = 8;
+
+        let pow_bits = self.fri_config.proof_of_work_bits as usize;
+        let query_security_bits = self.security_bits.saturating_sub(pow_bits);
+
+        let mut best = self.clone();
+        let mut best_size = self.estimated_proof_size(degree_bits);
+        for rate_bits in 1..=MAX_RATE_BITS {
+            for cap_height in 0..=MAX_CAP_HEIGHT.min(degree_bits + rate_bits) {
+                let mut candidate = self.clone();
+                candidate.fri_config.rate_bits = rate_bits;
+                candidate.fri_config.cap_height = cap_height;
+                candidate.fri_config.num_query_rounds =
+                    ceil_div_usize(query_security_bits, rate_bits);
+
+                let fri_params = candidate.fri_config.fri_params(degree_bits, false);
+                if fri_params.total_arities() > degree_bits + rate_bits - cap_height {
+                    continue;
+                }
+                let size = candidate.estimated_proof_size(degree_bits);
+                if size < best_size {
+                    best = candidate;
+                    best_size = size;
+                }
+            }
+        }
+        best
+    }
+
     /// A typical recursion config, without zero-knowledge, targeting ~100 bit security.
     pub fn standard_recursion_config() -> Self {
         Self {
@@ -73,6 +187,7 @@ impl CircuitConfig {
             num_challenges: 2,
             zero_knowledge: false,
             max_quotient_degree_factor: 8,
+            max_arithmetic_ops_per_row: None,
             fri_config: FriConfig {
                 rate_bits: 3,
                 cap_height: 4,
@@ -123,6 +238,15 @@ pub struct CircuitData<F: RichField + Extendable<D>, C: GenericConfig<D, F = F>,
 impl<F: RichField + Extendable<D>, C: GenericConfig<D, F = F>, const D: usize>
     CircuitData<F, C, D>
 {
+    /// A canonical trivial circuit, which only asserts `1 == 1` and has no public inputs. See
+    /// `TrivialCircuit` for a known-good proof of it.
+    pub fn trivial(config: CircuitConfig) -> Self {
+        let mut builder = CircuitBuilder::<F, D>::new(config);
+        let one = builder.one();
+        builder.assert_one(one);
+        builder.build::<C>()
+    }
+
     pub fn prove(&self, inputs: PartialWitness<F>) -> Result<ProofWithPublicInputs<F, C, D>> {
         prove(
             &self.prover_only,
@@ -132,10 +256,104 @@ impl<F: RichField + Extendable<D>, C: GenericConfig<D, F = F>, const D: usize>
         )
     }
 
+    /// Proves, also returning the witness generated for the proof; see `prove_and_return_witness`.
+    pub fn prove_and_return_witness(
+        &self,
+        inputs: PartialWitness<F>,
+    ) -> Result<(ProofWithPublicInputs<F, C, D>, PartitionWitness<F>)> {
+        prove_and_return_witness(
+            &self.prover_only,
+            &self.common,
+            inputs,
+            &mut TimingTree::default(),
+        )
+    }
+
+    /// Proves, also returning the coefficients of the committed polynomials; see `PolynomialDump`.
+    pub fn prove_with_dump(
+        &self,
+        inputs: PartialWitness<F>,
+    ) -> Result<(ProofWithPublicInputs<F, C, D>, PolynomialDump<F>)> {
+        prove_with_dump(
+            &self.prover_only,
+            &self.common,
+            inputs,
+            &mut TimingTree::default(),
+        )
+    }
+
+    /// Proves, resolving targets missing from the witness with `oracle`; see `prove_with_oracle`.
+    pub fn prove_with_oracle(
+        &self,
+        inputs: PartialWitness<F>,
+        oracle: impl Fn(Target) -> Option<F>,
+    ) -> Result<ProofWithPublicInputs<F, C, D>> {
+        prove_with_oracle(
+            &self.prover_only,
+            &self.common,
+            inputs,
+            oracle,
+            &mut TimingTree::default(),
+        )
+    }
+
+    /// Proves with the given FRI query indices instead of ones derived via Fiat-Shamir.
+    ///
+    /// WARNING: The resulting proof is NOT sound; see `prove_with_fixed_query_indices`.
+    #[cfg(any(feature = "fixed_fri_queries", test))]
+    pub fn prove_with_fixed_query_indices(
+        &self,
+        inputs: PartialWitness<F>,
+        fixed_query_indices: &[usize],
+    ) -> Result<ProofWithPublicInputs<F, C, D>> {
+        prove_with_fixed_query_indices(
+            &self.prover_only,
+            &self.common,
+            inputs,
+            fixed_query_indices,
+            &mut TimingTree::default(),
+        )
+    }
+
     pub fn verify(&self, proof_with_pis: ProofWithPublicInputs<F, C, D>) -> Result<()> {
         verify(proof_with_pis, &self.verifier_only, &self.common)
     }
 
+    /// Verifies a proof while recording the intermediate values of each check; see
+    /// `VerificationTrace`.
+    pub fn verify_with_trace(
+        &self,
+        proof_with_pis: ProofWithPublicInputs<F, C, D>,
+    ) -> Result<VerificationTrace<F, D>> {
+        verify_with_trace(proof_with_pis, &self.verifier_only, &self.common)
+    }
+
+    /// Verifies a proof, reporting every failing check rather than stopping at the first one; see
+    /// `VerificationFailure`. This is slower than `verify`, and meant for debugging.
+    pub fn verify_all(
+        &self,
+        proof_with_pis: ProofWithPublicInputs<F, C, D>,
+    ) -> Vec<VerificationFailure> {
+        verify_all(proof_with_pis, &self.verifier_only, &self.common)
+    }
+
+    /// Verifies a proof from `prove_with_fixed_query_indices`.
+    ///
+    /// WARNING: This is NOT a sound verifier; see `prove_with_fixed_query_indices`.
+    #[cfg(any(feature = "fixed_fri_queries", test))]
+    pub fn verify_with_fixed_query_indices(
+        &self,
+        proof_with_pis: ProofWithPublicInputs<F, C, D>,
+        fixed_query_indices: &[usize],
+    ) -> Result<()> {
+        verify_with_fixed_query_indices(
+            proof_with_pis,
+            fixed_query_indices,
+            &self.verifier_only,
+            &self.common,
+        )
+    }
+
     pub fn verify_compressed(
         &self,
         compressed_proof_with_pis: CompressedProofWithPublicInputs<F, C, D>,
@@ -157,6 +375,21 @@ impl<F: RichField + Extendable<D>, C: GenericConfig<D, F = F>, const D: usize>
         proof.decompress(&self.verifier_only.circuit_digest, &self.common)
     }
 
+    /// The values of the sigma polynomials over the subgroup, one vector per routed wire. They encode
+    /// the copy-constraint permutation: `sigmas[i][j] = k_is[i'] g^j'` if the permutation maps wire
+    /// `i` of row `j` to wire `i'` of row `j'`.
+    ///
+    /// These are part of the prover's setup data, rather than the common data.
+    pub fn sigma_polynomials(&self) -> Vec<Vec<F>> {
+        transpose(&self.prover_only.sigmas)
+    }
+
+    /// The total number of gate constraints over all rows of the circuit; see
+    /// `ProverOnlyCircuitData::total_gate_constraints`.
+    pub fn total_gate_constraints(&self) -> usize {
+        self.prover_only.total_gate_constraints(&self.common)
+    }
+
     pub fn verifier_data(self) -> VerifierCircuitData<F, C, D> {
         let CircuitData {
             verifier_only,
@@ -182,6 +415,32 @@ impl<F: RichField + Extendable<D>, C: GenericConfig<D, F = F>, const D: usize>
     }
 }
 
+/// The circuit from `CircuitData::trivial`, along with a proof of it which is made on first use and
+/// then cached. The proof is a known-good artifact for testing verifier infrastructure.
+#[derive(Clone)]
+pub struct TrivialCircuit<F: RichField + Extendable<D>, C: GenericConfig<D, F = F>, const D: usize>
+{
+    pub data: CircuitData<F, C, D>,
+    proof: OnceCell<ProofWithPublicInputs<F, C, D>>,
+}
+
+impl<F: RichField + Extendable<D>, C: GenericConfig<D, F = F>, const D: usize>
+    TrivialCircuit<F, C, D>
+{
+    pub fn new(config: CircuitConfig) -> Self {
+        Self {
+            data: CircuitData::trivial(config),
+            proof: OnceCell::new(),
+        }
+    }
+
+    /// A proof of the trivial circuit, which is only generated by the first call.
+    pub fn proof(&self) -> Result<&ProofWithPublicInputs<F, C, D>> {
+        self.proof
+            .get_or_try_init(|| self.data.prove(PartialWitness::new()))
+    }
+}
+
 /// Circuit data required by the prover. This may be thought of as a proving key, although it
 /// includes code for witness generation.
 ///
@@ -201,6 +460,12 @@ pub struct ProverCircuitData<
 impl<F: RichField + Extendable<D>, C: GenericConfig<D, F = F>, const D: usize>
     ProverCircuitData<F, C, D>
 {
+    /// The total number of gate constraints over all rows of the circuit; see
+    /// `ProverOnlyCircuitData::total_gate_constraints`.
+    pub fn total_gate_constraints(&self) -> usize {
+        self.prover_only.total_gate_constraints(&self.common)
+    }
+
     pub fn prove(&self, inputs: PartialWitness<F>) -> Result<ProofWithPublicInputs<F, C, D>> {
         prove(
             &self.prover_only,
@@ -209,10 +474,94 @@ impl<F: RichField + Extendable<D>, C: GenericConfig<D, F = F>, const D: usize>
             &mut TimingTree::default(),
         )
     }
+
+    /// Proves, also returning the witness generated for the proof; see `prove_and_return_witness`.
+    pub fn prove_and_return_witness(
+        &self,
+        inputs: PartialWitness<F>,
+    ) -> Result<(ProofWithPublicInputs<F, C, D>, PartitionWitness<F>)> {
+        prove_and_return_witness(
+            &self.prover_only,
+            &self.common,
+            inputs,
+            &mut TimingTree::default(),
+        )
+    }
+
+    /// Proves, also returning the coefficients of the committed polynomials; see `PolynomialDump`.
+    pub fn prove_with_dump(
+        &self,
+        inputs: PartialWitness<F>,
+    ) -> Result<(ProofWithPublicInputs<F, C, D>, PolynomialDump<F>)> {
+        prove_with_dump(
+            &self.prover_only,
+            &self.common,
+            inputs,
+            &mut TimingTree::default(),
+        )
+    }
+
+    /// Proves, resolving targets missing from the witness with `oracle`; see `prove_with_oracle`.
+    pub fn prove_with_oracle(
+        &self,
+        inputs: PartialWitness<F>,
+        oracle: impl Fn(Target) -> Option<F>,
+    ) -> Result<ProofWithPublicInputs<F, C, D>> {
+        prove_with_oracle(
+            &self.prover_only,
+            &self.common,
+            inputs,
+            oracle,
+            &mut TimingTree::default(),
+        )
+    }
+
+    /// Runs the prover up to the FRI phase; see `prove_until_commit`.
+    pub fn prove_until_commit(&self, inputs: PartialWitness<F>) -> ProverCheckpoint<F, C, D> {
+        prove_until_commit(
+            &self.prover_only,
+            &self.common,
+            inputs,
+            &mut TimingTree::default(),
+        )
+    }
+
+    /// Completes a proof from a checkpoint made by `prove_until_commit`.
+    pub fn resume_from_checkpoint(
+        &self,
+        checkpoint: ProverCheckpoint<F, C, D>,
+    ) -> Result<ProofWithPublicInputs<F, C, D>> {
+        resume_from_checkpoint(
+            &self.prover_only,
+            &self.common,
+            checkpoint,
+            &mut TimingTree::default(),
+        )
+    }
+
+    /// Serializes the witness generators of this circuit, so that they can be distributed
+    /// separately from the rest of the circuit; see `CommonCircuitData::deserialize_generators`.
+    /// Fails if some generator is not supported by `generator_serializer`.
+    pub fn serialize_generators<S: WitnessGeneratorSerializer<F, D>>(
+        &self,
+        generator_serializer: &S,
+    ) -> Result<Vec<u8>> {
+        let generators = &self.prover_only.generators;
+        let mut buffer = Vec::new();
+        buffer
+            .write_u32(generators.len() as u32)
+            .expect("Writing to a byte-vector cannot fail.");
+        for generator in generators {
+            generator_serializer
+                .write_generator(&mut buffer, generator.as_ref())
+                .map_err(|_| anyhow!("Cannot serialize generator {:?}", generator))?;
+        }
+        Ok(buffer)
+    }
 }
 
 /// Circuit data required by the prover.
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct VerifierCircuitData<
     F: RichField + Extendable<D>,
     C: GenericConfig<D, F = F>,
@@ -229,6 +578,202 @@ impl<F: RichField + Extendable<D>, C: GenericConfig<D, F = F>, const D: usize>
         verify(proof_with_pis, &self.verifier_only, &self.common)
     }
 
+    /// Serializes the common data, followed by the verifier key, using `gate_serializer` for the
+    /// gates.
+    pub fn to_bytes<G: GateSerializer<F, D>>(&self, gate_serializer: &G) -> Vec<u8> {
+        let mut buffer = Vec::new();
+        buffer
+            .write_common_circuit_data(&self.common, gate_serializer)
+            .and_then(|()| buffer.write_verifier_only_circuit_data(&self.verifier_only))
+            .expect("Writing to a byte-vector cannot fail.");
+        buffer
+    }
+
+    /// Deserializes verifier data written by `to_bytes`. Malformed or inconsistent data results in
+    /// an error rather than a panic, both here and when verifying proofs against the result.
+    #[cfg(feature = "std")]
+    pub fn from_bytes<G: GateSerializer<F, D>>(
+        bytes: Vec<u8>,
+        gate_serializer: &G,
+    ) -> Result<Self> {
+        let mut buffer = Buffer::new(bytes);
+        let common = buffer
+            .read_common_circuit_data(gate_serializer)
+            .map_err(anyhow::Error::msg)?;
+        let verifier_only = buffer
+            .read_verifier_only_circuit_data(&common)
+            .map_err(anyhow::Error::msg)?;
+        Ok(Self {
+            verifier_only,
+            common,
+        })
+    }
+
+    /// Verifies a proof while recording the intermediate values of each check; see
+    /// `VerificationTrace`.
+    pub fn verify_with_trace(
+        &self,
+        proof_with_pis: ProofWithPublicInputs<F, C, D>,
+    ) -> Result<VerificationTrace<F, D>> {
+        verify_with_trace(proof_with_pis, &self.verifier_only, &self.common)
+    }
+
+    /// Verifies a proof, reporting every failing check rather than stopping at the first one; see
+    /// `VerificationFailure`. This is slower than `verify`, and meant for debugging.
+    pub fn verify_all(
+        &self,
+        proof_with_pis: ProofWithPublicInputs<F, C, D>,
+    ) -> Vec<VerificationFailure> {
+        verify_all(proof_with_pis, &self.verifier_only, &self.common)
+    }
+
+    /// Verifies each of `proofs`, and checks that they all have the same public inputs. On a
+    /// mismatch, the error names the first proof and public input index which differ from the
+    /// first proof.
+    pub fn verify_batch_same_public_inputs(
+        &self,
+        proofs: &[ProofWithPublicInputs<F, C, D>],
+    ) -> Result<()> {
+        for (i, proof) in proofs.iter().enumerate() {
+            self.verify(proof.clone())
+                .with_context(|| format!("Proof {i} failed verification"))?;
+        }
+
+        if let Some(first) = proofs.first() {
+            for (i, proof) in proofs.iter().enumerate().skip(1) {
+                if let Some((j, (x, expected))) = proof
+                    .public_inputs
+                    .iter()
+                    .zip(&first.public_inputs)
+                    .enumerate()
+                    .find(|(_, (x, expected))| x != expected)
+                {
+                    bail!("Proof {i} has public input {j} = {x}, but proof 0 has {expected}");
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Verifies a proof, and checks that its public inputs form the leaf at `leaf_index` of an
+    /// external Merkle tree with root `root`, as witnessed by `path`. The external tree may use any
+    /// hasher `H`, independently of the hasher used by the circuit.
+    pub fn verify_with_external_pi_root<H: Hasher<F>>(
+        &self,
+        proof_with_pis: ProofWithPublicInputs<F, C, D>,
+        root: H::Hash,
+        leaf_index: usize,
+        path: &MerkleProof<F, H>,
+    ) -> Result<()> {
+        verify_merkle_proof(proof_with_pis.public_inputs.clone(), leaf_index, root, path)
+            .context("Public inputs are not committed to by the external root")?;
+        self.verify(proof_with_pis)
+    }
+
+    /// Verifies a proof claimed to be for the circuit with digest `circuit_digest`, e.g. as taken
+    /// from metadata sent along with the proof, against the `accepted` circuits, e.g. several
+    /// supported versions of a circuit. Only the accepted circuit with that digest is verified
+    /// against, and its index is returned. The circuit digest seeds the proof's Fiat-Shamir
+    /// challenges, so a proof claiming the wrong digest fails verification.
+    pub fn verify_against_any(
+        proof_with_pis: ProofWithPublicInputs<F, C, D>,
+        circuit_digest: &<<C as GenericConfig<D>>::Hasher as Hasher<C::F>>::Hash,
+        accepted: &[Self],
+    ) -> Result<usize> {
+        let index = accepted
+            .iter()
+            .position(|verifier_data| verifier_data.verifier_only.circuit_digest == *circuit_digest)
+            .ok_or_else(|| anyhow!("Circuit digest {:?} is not accepted", circuit_digest))?;
+        accepted[index].verify(proof_with_pis)?;
+        Ok(index)
+    }
+
+    /// Checks that the circuit was built with the `expected` config, e.g. before trusting proofs
+    /// made under a security policy. The error names the first field which differs.
+    pub fn assert_config(&self, expected: &CircuitConfig) -> Result<()> {
+        fn check<T: PartialEq + Debug>(name: &str, actual: &T, expected: &T) -> Result<()> {
+            ensure!(
+                actual == expected,
+                "The circuit's {} is {:?}, expected {:?}",
+                name,
+                actual,
+                expected
+            );
+            Ok(())
+        }
+
+        let config = &self.common.config;
+        check("num_wires", &config.num_wires, &expected.num_wires)?;
+        check(
+            "num_routed_wires",
+            &config.num_routed_wires,
+            &expected.num_routed_wires,
+        )?;
+        check(
+            "num_constants",
+            &config.num_constants,
+            &expected.num_constants,
+        )?;
+        check(
+            "use_base_arithmetic_gate",
+            &config.use_base_arithmetic_gate,
+            &expected.use_base_arithmetic_gate,
+        )?;
+        check(
+            "security_bits",
+            &config.security_bits,
+            &expected.security_bits,
+        )?;
+        check(
+            "num_challenges",
+            &config.num_challenges,
+            &expected.num_challenges,
+        )?;
+        check(
+            "zero_knowledge",
+            &config.zero_knowledge,
+            &expected.zero_knowledge,
+        )?;
+        check(
+            "max_quotient_degree_factor",
+            &config.max_quotient_degree_factor,
+            &expected.max_quotient_degree_factor,
+        )?;
+        check(
+            "max_arithmetic_ops_per_row",
+            &config.max_arithmetic_ops_per_row,
+            &expected.max_arithmetic_ops_per_row,
+        )?;
+
+        let (fri_config, expected_fri_config) = (&config.fri_config, &expected.fri_config);
+        check(
+            "fri_config.rate_bits",
+            &fri_config.rate_bits,
+            &expected_fri_config.rate_bits,
+        )?;
+        check(
+            "fri_config.cap_height",
+            &fri_config.cap_height,
+            &expected_fri_config.cap_height,
+        )?;
+        check(
+            "fri_config.proof_of_work_bits",
+            &fri_config.proof_of_work_bits,
+            &expected_fri_config.proof_of_work_bits,
+        )?;
+        check(
+            "fri_config.reduction_strategy",
+            &fri_config.reduction_strategy,
+            &expected_fri_config.reduction_strategy,
+        )?;
+        check(
+            "fri_config.num_query_rounds",
+            &fri_config.num_query_rounds,
+            &expected_fri_config.num_query_rounds,
+        )
+    }
+
     pub fn verify_compressed(
         &self,
         compressed_proof_with_pis: CompressedProofWithPublicInputs<F, C, D>,
@@ -264,6 +809,24 @@ pub struct ProverOnlyCircuitData<
     /// A digest of the "circuit" (i.e. the instance, minus public inputs), which can be used to
     /// seed Fiat-Shamir.
     pub circuit_digest: <<C as GenericConfig<D>>::Hasher as Hasher<F>>::Hash,
+    /// The number of rows using each gate in `CommonCircuitData::gates`, in the same order.
+    pub gate_instance_counts: Vec<usize>,
+}
+
+impl<F: RichField + Extendable<D>, C: GenericConfig<D, F = F>, const D: usize>
+    ProverOnlyCircuitData<F, C, D>
+{
+    /// The total number of gate constraints over all rows of the circuit, i.e. `num_constraints()`
+    /// summed over every gate instance. By contrast, `CommonCircuitData::num_gate_constraints` is
+    /// only the largest count of any single gate.
+    pub fn total_gate_constraints(&self, common: &CommonCircuitData<F, D>) -> usize {
+        common
+            .gates
+            .iter()
+            .zip(&self.gate_instance_counts)
+            .map(|(g, &count)| count * g.0.num_constraints())
+            .sum()
+    }
 }
 
 /// Circuit data required by the verifier, but not the prover.
@@ -336,6 +899,226 @@ impl<F: RichField + Extendable<D>, const D: usize> CommonCircuitData<F, D> {
         self.quotient_degree_factor * self.degree()
     }
 
+    /// The FRI parameters of this circuit, in a form which can be handed to external verifiers.
+    pub fn fri_params(&self) -> FriParamsExport {
+        FriParamsExport::from(&self.fri_params)
+    }
+
+    /// The arity of each FRI reduction step, i.e. the factor by which each step folds the
+    /// polynomial. See `FriParams::reduction_arity_bits` for the same arities in log form.
+    pub fn fri_reduction_arities(&self) -> Vec<usize> {
+        self.fri_params
+            .reduction_arity_bits
+            .iter()
+            .map(|&arity_bits| 1 << arity_bits)
+            .collect()
+    }
+
+    /// The size of each FFT (or inverse FFT) the prover performs for one proof, in order: the
+    /// interpolation and LDE of each wire polynomial, then of each Z and partial product
+    /// polynomial, the interpolation of each quotient polynomial from its coset evaluations, the
+    /// LDE of each quotient chunk, and finally the FFTs of the FFT-based FRI layers. Preprocessed
+    /// polynomials are committed when the circuit is built, so they are not included.
+    pub fn fft_sizes(&self) -> Vec<usize> {
+        let degree = self.degree();
+        let lde_size = self.lde_size();
+        let num_challenges = self.config.num_challenges;
+        let interpolate_and_lde = |num_polys: usize| {
+            let mut sizes = vec![degree; num_polys];
+            sizes.extend(vec![lde_size; num_polys]);
+            sizes
+        };
+
+        let mut sizes = interpolate_and_lde(self.config.num_wires);
+        sizes.extend(interpolate_and_lde(
+            num_challenges * (1 + self.num_partial_products),
+        ));
+        let quotient_size = degree << log2_ceil(self.quotient_degree_factor);
+        sizes.extend(vec![quotient_size; num_challenges]);
+        sizes.extend(vec![lde_size; num_challenges * self.quotient_degree_factor]);
+
+        // The LDE of the final FRI polynomial, then one FFT after each reduction.
+        let mut fri_size = lde_size;
+        sizes.push(fri_size);
+        for &arity_bits in &self.fri_params.reduction_arity_bits {
+            fri_size >>= arity_bits;
+            sizes.push(fri_size);
+        }
+        sizes
+    }
+
+    /// A rough estimate of the EVM gas consumed by the exported Solidity verifier, meant for
+    /// deployment budgeting rather than as an exact figure.
+    ///
+    /// The model charges:
+    /// - a fixed base cost, covering the transaction, calldata, transcript hashing and the final
+    ///   checks at `zeta`;
+    /// - a fixed overhead per gate library call, plus a fixed cost per gate constraint evaluated;
+    /// - for each FRI query round, one Poseidon permutation per Merkle path node, in the four
+    ///   initial trees and in every commit-phase tree, plus a fixed cost per folding step.
+    ///
+    /// Memory expansion and per-opening calldata are assumed to be absorbed into these constants.
+    pub fn estimated_verifier_gas(&self) -> u64 {
+        const BASE_GAS: u64 = 500_000;
+        const GATE_LIBRARY_GAS: u64 = 10_000;
+        const GATE_CONSTRAINT_GAS: u64 = 2_000;
+        const POSEIDON_PERMUTATION_GAS: u64 = 25_000;
+        const FRI_FOLDING_STEP_GAS: u64 = 20_000;
+
+        let gates_gas = self
+            .gates
+            .iter()
+            .map(|gate| GATE_LIBRARY_GAS + gate.0.num_constraints() as u64 * GATE_CONSTRAINT_GAS)
+            .sum::<u64>();
+
+        let cap_height = self.config.fri_config.cap_height;
+        let lde_bits = self.degree_bits() + self.config.fri_config.rate_bits;
+        let num_initial_trees = self.fri_oracles().len();
+        let mut path_nodes_per_query = num_initial_trees * lde_bits.saturating_sub(cap_height);
+        let mut codeword_bits = lde_bits;
+        for &arity_bits in &self.fri_params.reduction_arity_bits {
+            codeword_bits -= arity_bits;
+            path_nodes_per_query += codeword_bits.saturating_sub(cap_height);
+        }
+        let num_folding_steps = self.fri_params.reduction_arity_bits.len() as u64;
+        let query_gas = path_nodes_per_query as u64 * POSEIDON_PERMUTATION_GAS
+            + num_folding_steps * FRI_FOLDING_STEP_GAS;
+        let fri_gas = self.config.fri_config.num_query_rounds as u64 * query_gas;
+
+        BASE_GAS + gates_gas + fri_gas
+    }
+
+    /// A rough estimate of the number of gates in a circuit verifying a proof of this circuit,
+    /// meant for deciding whether recursion is affordable before building the recursive circuit.
+    ///
+    /// The model counts one gate per Poseidon permutation, and charges:
+    /// - a fixed base cost, covering the transcript and the public inputs hash;
+    /// - the evaluation of the gate constraints at `zeta`, as one extension operation per degree of
+    ///   each constraint, packed into `ArithmeticExtensionGate`s;
+    /// - for each FRI query round, the hashing of every opened leaf and one permutation per Merkle
+    ///   path node, in the four initial trees and in every commit-phase tree, the combination of
+    ///   the initial openings, and an interpolation gate per folding step.
+    pub fn recursion_circuit_size_estimate(&self) -> usize {
+        const BASE_GATES: usize = 200;
+
+        let ext_ops_per_gate = ArithmeticExtensionGate::<D>::new_from_config(&self.config).num_ops;
+        let constraint_ops = self
+            .gates
+            .iter()
+            .map(|gate| gate.0.num_constraints() * gate.0.degree())
+            .sum::<usize>();
+        let vanishing_gates = ceil_div_usize(constraint_ops, ext_ops_per_gate);
+
+        let cap_height = self.config.fri_config.cap_height;
+        let lde_bits = self.degree_bits() + self.config.fri_config.rate_bits;
+        let mut gates_per_query = 0;
+        let mut num_opened_elements = 0;
+        for oracle in self.fri_oracles() {
+            let leaf_len = oracle.num_polys + salt_size(oracle.blinding && self.fri_params.hiding);
+            num_opened_elements += leaf_len;
+            gates_per_query +=
+                ceil_div_usize(leaf_len, SPONGE_RATE) + lde_bits.saturating_sub(cap_height);
+        }
+        gates_per_query += ceil_div_usize(num_opened_elements, ext_ops_per_gate);
+        let mut codeword_bits = lde_bits;
+        for &arity_bits in &self.fri_params.reduction_arity_bits {
+            codeword_bits -= arity_bits;
+            let leaf_len = (1 << arity_bits) * D;
+            gates_per_query += ceil_div_usize(leaf_len, SPONGE_RATE)
+                + codeword_bits.saturating_sub(cap_height)
+                + 1;
+        }
+        let fri_gates = self.config.fri_config.num_query_rounds * gates_per_query;
+
+        BASE_GATES + vanishing_gates + fri_gates
+    }
+
+    /// Reports, for each gate of this circuit, whether its Solidity and Circom verifier code
+    /// exports are implemented.
+    pub fn export_compatibility(&self) -> ExportReport {
+        let gates = self
+            .gates
+            .iter()
+            .map(|gate| GateExportSupport {
+                gate_id: gate.0.id(),
+                num_constraints: gate.0.num_constraints(),
+                solidity: gate.0.supports_solidity_export(),
+                circom: gate.0.supports_circom_export(),
+            })
+            .collect();
+        ExportReport { gates }
+    }
+
+    /// Reports the structural differences between this circuit and `other`, in their gate sets,
+    /// configs, FRI parameters and public input counts.
+    pub fn diff(&self, other: &Self) -> CircuitDiff {
+        let gate_ids = |data: &Self| {
+            data.gates
+                .iter()
+                .map(|gate| gate.0.id())
+                .collect::<Vec<_>>()
+        };
+        let (ids, other_ids) = (gate_ids(self), gate_ids(other));
+        let only_in = |ids: &[String], other_ids: &[String]| {
+            ids.iter()
+                .filter(|id| !other_ids.contains(id))
+                .cloned()
+                .collect()
+        };
+
+        CircuitDiff {
+            removed_gates: only_in(&ids, &other_ids),
+            added_gates: only_in(&other_ids, &ids),
+            config: (self.config != other.config)
+                .then(|| (self.config.clone(), other.config.clone())),
+            fri_params: (self.fri_params != other.fri_params)
+                .then(|| (self.fri_params.clone(), other.fri_params.clone())),
+            num_public_inputs: (self.num_public_inputs != other.num_public_inputs)
+                .then_some((self.num_public_inputs, other.num_public_inputs)),
+        }
+    }
+
+    /// Serializes this data, without any verifier key, using `gate_serializer` for the gates.
+    /// Structurally identical circuits serialize to the same bytes.
+    pub fn to_bytes<G: GateSerializer<F, D>>(&self, gate_serializer: &G) -> Vec<u8> {
+        let mut buffer = Vec::new();
+        buffer
+            .write_common_circuit_data(self, gate_serializer)
+            .expect("Writing to a byte-vector cannot fail.");
+        buffer
+    }
+
+    #[cfg(feature = "std")]
+    pub fn from_bytes<G: GateSerializer<F, D>>(
+        bytes: Vec<u8>,
+        gate_serializer: &G,
+    ) -> Result<Self> {
+        let mut buffer = Buffer::new(bytes);
+        let common_data = buffer
+            .read_common_circuit_data(gate_serializer)
+            .map_err(anyhow::Error::msg)?;
+        Ok(common_data)
+    }
+
+    /// Reads witness generators written by `ProverCircuitData::serialize_generators` for a
+    /// circuit with this common data. They are returned in their original order, so they can
+    /// replace the `generators` of a `ProverOnlyCircuitData` for the same circuit.
+    #[cfg(feature = "std")]
+    pub fn deserialize_generators<S: WitnessGeneratorSerializer<F, D>>(
+        &self,
+        bytes: Vec<u8>,
+        generator_serializer: &S,
+    ) -> Result<Vec<Box<dyn WitnessGenerator<F>>>> {
+        let mut buffer = Buffer::new(bytes);
+        let num_generators = buffer.read_u32().map_err(anyhow::Error::msg)? as usize;
+        let generators = (0..num_generators)
+            .map(|_| generator_serializer.read_generator(&mut buffer, self))
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(anyhow::Error::msg)?;
+        ensure!(buffer.is_empty(), "Trailing bytes after the generators");
+        Ok(generators)
+    }
+
     /// Range of the constants polynomials in the `constants_sigmas_commitment`.
     pub fn constants_range(&self) -> Range<usize> {
         0..self.num_constants
@@ -356,6 +1139,15 @@ impl<F: RichField + Extendable<D>, const D: usize> CommonCircuitData<F, D> {
         self.config.num_challenges..
     }
 
+    /// Ranges of the quotient polynomial chunks in the `quotient_polys_commitment`, one range per
+    /// challenge. Each quotient polynomial has degree `quotient_degree()`, and is committed as
+    /// `quotient_degree_factor` chunks of degree `degree()`.
+    pub fn quotient_chunks(&self) -> Vec<Range<usize>> {
+        (0..self.config.num_challenges)
+            .map(|i| i * self.quotient_degree_factor..(i + 1) * self.quotient_degree_factor)
+            .collect()
+    }
+
     pub(crate) fn get_fri_instance(&self, zeta: F::Extension) -> FriInstanceInfo<F, D> {
         // All polynomials are opened at zeta.
         let zeta_batch = FriBatchInfo {
@@ -475,6 +1267,68 @@ impl<F: RichField + Extendable<D>, const D: usize> CommonCircuitData<F, D> {
     }
 }
 
+/// The structural differences between two circuits; see `CommonCircuitData::diff`. Each pair holds
+/// the value of the first circuit, then that of the second.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct CircuitDiff {
+    /// Ids of the gates used only by the first circuit.
+    pub removed_gates: Vec<String>,
+    /// Ids of the gates used only by the second circuit.
+    pub added_gates: Vec<String>,
+    pub config: Option<(CircuitConfig, CircuitConfig)>,
+    pub fri_params: Option<(FriParams, FriParams)>,
+    pub num_public_inputs: Option<(usize, usize)>,
+}
+
+impl CircuitDiff {
+    /// Whether the two circuits are structurally identical.
+    pub fn is_empty(&self) -> bool {
+        self.removed_gates.is_empty()
+            && self.added_gates.is_empty()
+            && self.config.is_none()
+            && self.fri_params.is_none()
+            && self.num_public_inputs.is_none()
+    }
+}
+
+/// Which verifier code exports are implemented for the gates of a circuit; see
+/// `CommonCircuitData::export_compatibility`.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ExportReport {
+    pub gates: Vec<GateExportSupport>,
+}
+
+impl ExportReport {
+    /// The report for the gate with id `gate_id`, if the circuit uses it.
+    pub fn gate(&self, gate_id: &str) -> Option<&GateExportSupport> {
+        self.gates.iter().find(|gate| gate.gate_id == gate_id)
+    }
+
+    /// Whether the circuit can be exported to Solidity. Gates without constraints, such as
+    /// `NoopGate`, contribute no verifier code, so they are not required to support exports.
+    pub fn solidity_supported(&self) -> bool {
+        self.gates
+            .iter()
+            .all(|gate| gate.solidity || gate.num_constraints == 0)
+    }
+
+    /// Whether the circuit can be exported to Circom; see `solidity_supported`.
+    pub fn circom_supported(&self) -> bool {
+        self.gates
+            .iter()
+            .all(|gate| gate.circom || gate.num_constraints == 0)
+    }
+}
+
+/// Whether a gate's verifier code exports are implemented.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct GateExportSupport {
+    pub gate_id: String,
+    pub num_constraints: usize,
+    pub solidity: bool,
+    pub circom: bool,
+}
+
 /// The `Target` version of `VerifierCircuitData`, for use inside recursive circuits. Note that this
 /// is intentionally missing certain fields, such as `CircuitConfig`, because we support only a
 /// limited form of dynamic inner circuits. We can't practically make things like the wire count
@@ -487,3 +1341,1009 @@ pub struct VerifierCircuitTarget {
     /// seed Fiat-Shamir.
     pub circuit_digest: HashOutTarget,
 }
+
+#[cfg(test)]
+mod tests {
+    use alloc::collections::BTreeSet;
+    use core::cell::RefCell;
+
+    use anyhow::Result;
+    use hashbrown::HashSet;
+    use rand::{Rng, SeedableRng};
+    use rand_chacha::ChaCha8Rng;
+
+    use crate::field::types::{Field, PrimeField64};
+    #[cfg(feature = "serde_json")]
+    use crate::fri::FriParamsExport;
+    use crate::gates::arithmetic_base::ArithmeticGate;
+    use crate::gates::arithmetic_extension::ArithmeticExtensionGate;
+    use crate::gates::gate::GateRef;
+    use crate::gates::mux4::Mux4Gate;
+    use crate::gates::noop::NoopGate;
+    use crate::gates::poseidon::PoseidonGate;
+    use crate::gates::public_input::PublicInputGate;
+    use crate::gates::selectors::UNUSED_SELECTOR;
+    use crate::gates::sum::SumGate;
+    use crate::hash::keccak::KeccakHash;
+    use crate::hash::merkle_tree::MerkleTree;
+    use crate::iop::target::Target;
+    use crate::iop::witness::{PartialWitness, Witness, WitnessWrite};
+    use crate::plonk::circuit_builder::CircuitBuilder;
+    use crate::plonk::circuit_data::{
+        CircuitConfig, CircuitData, CircuitDiff, CommonCircuitData, ProverCircuitData,
+        TrivialCircuit, VerifierCircuitData, VerifierCircuitTarget,
+    };
+    use crate::plonk::config::{GenericConfig, PoseidonGoldilocksConfig};
+    use crate::plonk::proof::ProofWithPublicInputs;
+    use crate::plonk::prover::ProverCheckpoint;
+    use crate::plonk::verifier::VerificationFailure;
+    use crate::util::gate_serialization::DefaultGateSerializer;
+    use crate::util::generator_serialization::{DefaultGeneratorSerializer, GeneratorId};
+    use crate::util::serialization::{Buffer, Read};
+
+    const D: usize = 2;
+    type C = PoseidonGoldilocksConfig;
+    type F = <C as GenericConfig<D>>::F;
+
+    fn common_data(config: CircuitConfig, num_rows: usize) -> CommonCircuitData<F, D> {
+        let mut builder = CircuitBuilder::<F, D>::new(config);
+        for _ in 0..num_rows {
+            builder.add_gate(NoopGate, vec![]);
+        }
+        builder.build::<C>().common
+    }
+
+    #[test]
+    fn test_verify_batch_same_public_inputs() -> Result<()> {
+        let config = CircuitConfig::standard_recursion_config();
+        let mut builder = CircuitBuilder::<F, D>::new(config);
+        let root = builder.add_virtual_public_input();
+        let leaf = builder.add_virtual_target();
+        let leaf_squared = builder.square(leaf);
+        builder.register_public_input(leaf_squared);
+        let data = builder.build::<C>();
+
+        let prove = |root_value: u64, leaf_value: u64| {
+            let mut pw = PartialWitness::new();
+            pw.set_target(root, F::from_canonical_u64(root_value));
+            pw.set_target(leaf, F::from_canonical_u64(leaf_value));
+            data.prove(pw)
+        };
+        let agreeing = vec![prove(7, 3)?, prove(7, 3)?, prove(7, 3)?];
+        let mut batch = agreeing.clone();
+        batch.push(prove(8, 3)?);
+
+        let verifier_data = data.verifier_data();
+        verifier_data.verify_batch_same_public_inputs(&agreeing)?;
+        let err = verifier_data
+            .verify_batch_same_public_inputs(&batch)
+            .unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "Proof 3 has public input 0 = 8, but proof 0 has 7"
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_verify_with_external_pi_root() -> Result<()> {
+        type H = KeccakHash<25>;
+
+        // Proves that the 100th Fibonacci number, starting from the public inputs `x0, x1`, is the
+        // public output.
+        let config = CircuitConfig::standard_recursion_config();
+        let mut builder = CircuitBuilder::<F, D>::new(config);
+        let initial_a = builder.add_virtual_target();
+        let initial_b = builder.add_virtual_target();
+        let cur_target = builder.fibonacci_100(initial_a, initial_b);
+        builder.register_public_input(initial_a);
+        builder.register_public_input(initial_b);
+        builder.register_public_input(cur_target);
+        let data = builder.build::<C>();
+
+        let mut pw = PartialWitness::new();
+        pw.set_target(initial_a, F::ZERO);
+        pw.set_target(initial_b, F::ONE);
+        let proof = data.prove(pw)?;
+
+        // An external tree, committing to these public inputs among other records.
+        let leaf_index = 5;
+        let mut leaves = (0..8)
+            .map(|i| vec![F::from_canonical_usize(i); 3])
+            .collect::<Vec<_>>();
+        leaves[leaf_index] = proof.public_inputs.clone();
+        let tree = MerkleTree::<F, H>::new(leaves, 0);
+        let root = tree.cap.0[0];
+
+        let verifier_data = data.verifier_data();
+        verifier_data.verify_with_external_pi_root(
+            proof.clone(),
+            root,
+            leaf_index,
+            &tree.prove(leaf_index),
+        )?;
+        assert!(verifier_data
+            .verify_with_external_pi_root(proof.clone(), root, 4, &tree.prove(4))
+            .is_err());
+
+        let mut tampered = proof;
+        tampered.public_inputs[2] += F::ONE;
+        assert!(verifier_data
+            .verify_with_external_pi_root(tampered, root, leaf_index, &tree.prove(leaf_index))
+            .is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_verify_against_any() -> Result<()> {
+        // Two versions of a circuit proving `x^n` for a public `x`, with `n = 2` and `n = 3`.
+        let build = |n: usize| {
+            let config = CircuitConfig::standard_recursion_config();
+            let mut builder = CircuitBuilder::<F, D>::new(config);
+            let x = builder.add_virtual_public_input();
+            let y = builder.exp_u64(x, n as u64);
+            builder.register_public_input(y);
+            (builder.build::<C>(), x)
+        };
+        let (old, old_x) = build(2);
+        let (new, new_x) = build(3);
+        let prove = |data: &CircuitData<F, C, D>, x| {
+            let mut pw = PartialWitness::new();
+            pw.set_target(x, F::from_canonical_u64(5));
+            data.prove(pw)
+        };
+        let old_proof = prove(&old, old_x)?;
+        let new_proof = prove(&new, new_x)?;
+
+        let old_digest = old.verifier_only.circuit_digest;
+        let new_digest = new.verifier_only.circuit_digest;
+        let accepted = [old.verifier_data(), new.verifier_data()];
+        assert_eq!(
+            VerifierCircuitData::verify_against_any(old_proof.clone(), &old_digest, &accepted)?,
+            0
+        );
+        assert_eq!(
+            VerifierCircuitData::verify_against_any(new_proof.clone(), &new_digest, &accepted)?,
+            1
+        );
+
+        // A digest which is not accepted is rejected without verifying.
+        let err = VerifierCircuitData::verify_against_any(new_proof, &new_digest, &accepted[..1])
+            .unwrap_err();
+        assert!(err.to_string().contains("is not accepted"));
+        // A proof claiming another accepted circuit's digest fails verification.
+        assert!(
+            VerifierCircuitData::verify_against_any(old_proof, &new_digest, &accepted).is_err()
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_sigma_polynomials() {
+        // The 100th Fibonacci number, starting from the public inputs `x0, x1`.
+        let config = CircuitConfig::standard_recursion_config();
+        let mut builder = CircuitBuilder::<F, D>::new(config);
+        let initial_a = builder.add_virtual_public_input();
+        let initial_b = builder.add_virtual_public_input();
+        let cur_target = builder.fibonacci_100(initial_a, initial_b);
+        builder.register_public_input(cur_target);
+        let data = builder.build::<C>();
+
+        let sigmas = data.sigma_polynomials();
+        let degree = data.common.degree();
+        assert_eq!(sigmas.len(), data.common.config.num_routed_wires);
+        assert!(sigmas.iter().all(|sigma| sigma.len() == degree));
+
+        // The sigmas permute the identity values `k_i g^j`, so each of them appears exactly once.
+        let identity = data
+            .common
+            .k_is
+            .iter()
+            .flat_map(|&k_i| data.prover_only.subgroup.iter().map(move |&g_j| k_i * g_j))
+            .collect::<HashSet<_>>();
+        let values = sigmas.iter().flatten().copied().collect::<HashSet<_>>();
+        assert_eq!(values.len(), degree * sigmas.len());
+        assert_eq!(values, identity);
+
+        // The copy constraints are not trivial.
+        let identity_order = data
+            .common
+            .k_is
+            .iter()
+            .flat_map(|&k_i| data.prover_only.subgroup.iter().map(move |&g_j| k_i * g_j));
+        assert!(sigmas.iter().flatten().copied().ne(identity_order));
+    }
+
+    #[test]
+    fn test_assert_config() {
+        let config = CircuitConfig::standard_recursion_config();
+        let mut builder = CircuitBuilder::<F, D>::new(config.clone());
+        let x = builder.add_virtual_public_input();
+        let y = builder.square(x);
+        builder.register_public_input(y);
+        let data = builder.build::<C>().verifier_data();
+        data.assert_config(&config).unwrap();
+
+        let mut expected = config.clone();
+        expected.num_routed_wires = 64;
+        assert_eq!(
+            data.assert_config(&expected).unwrap_err().to_string(),
+            "The circuit's num_routed_wires is 80, expected 64"
+        );
+
+        let mut expected = config.clone();
+        expected.zero_knowledge = true;
+        assert_eq!(
+            data.assert_config(&expected).unwrap_err().to_string(),
+            "The circuit's zero_knowledge is false, expected true"
+        );
+
+        let mut expected = config;
+        expected.fri_config.num_query_rounds += 1;
+        assert_eq!(
+            data.assert_config(&expected).unwrap_err().to_string(),
+            "The circuit's fri_config.num_query_rounds is 28, expected 29"
+        );
+    }
+
+    #[test]
+    fn test_total_gate_constraints() {
+        // The 100th Fibonacci number, starting from the public inputs `x0, x1`.
+        let config = CircuitConfig::standard_recursion_config();
+        let mut builder = CircuitBuilder::<F, D>::new(config);
+        let initial_a = builder.add_virtual_public_input();
+        let initial_b = builder.add_virtual_public_input();
+        let cur_target = builder.fibonacci_100(initial_a, initial_b);
+        builder.register_public_input(cur_target);
+        let data = builder.build::<C>();
+        let common = &data.common;
+
+        // Recover the gate of each row from the selector polynomials, and sum its constraints.
+        let selectors = data.prover_only.constants_sigmas_commitment.polynomials
+            [..common.selectors_info.num_selectors()]
+            .iter()
+            .map(|p| p.clone().fft())
+            .collect::<Vec<_>>();
+        let expected = (0..common.degree())
+            .map(|row| {
+                let gate_index = selectors
+                    .iter()
+                    .map(|s| s.values[row].to_canonical_u64() as usize)
+                    .find(|&i| i != UNUSED_SELECTOR)
+                    .unwrap();
+                common.gates[gate_index].0.num_constraints()
+            })
+            .sum::<usize>();
+        assert_eq!(data.total_gate_constraints(), expected);
+        assert!(data.total_gate_constraints() > common.num_gate_constraints);
+    }
+
+    #[test]
+    fn test_resume_from_checkpoint() -> Result<()> {
+        // Proves that the 100th Fibonacci number, starting from `0, 1`, is the public output.
+        let config = CircuitConfig::standard_recursion_config();
+        let mut builder = CircuitBuilder::<F, D>::new(config);
+        let initial_a = builder.add_virtual_target();
+        let initial_b = builder.add_virtual_target();
+        let cur_target = builder.fibonacci_100(initial_a, initial_b);
+        builder.register_public_input(cur_target);
+        let data = builder.build::<C>();
+
+        let mut pw = PartialWitness::new();
+        pw.set_target(initial_a, F::ZERO);
+        pw.set_target(initial_b, F::ONE);
+        let one_shot = data.prove(pw.clone())?;
+
+        let prover_data = ProverCircuitData {
+            prover_only: data.prover_only.clone(),
+            common: data.common.clone(),
+        };
+        let checkpoint = prover_data.prove_until_commit(pw);
+        let resumed = prover_data.resume_from_checkpoint(checkpoint.clone())?;
+        assert_eq!(
+            resumed.proof.wires_cap,
+            checkpoint.wires_commitment.merkle_tree.cap
+        );
+        assert_eq!(
+            resumed.proof.quotient_polys_cap,
+            checkpoint.quotient_polys_commitment.merkle_tree.cap
+        );
+        // Each proof randomizes a few unused wires, so only the public inputs match a separate
+        // one-shot proof.
+        assert_eq!(resumed.public_inputs, one_shot.public_inputs);
+
+        // The checkpoint survives serialization, as if the FRI phase ran in another process, and
+        // resuming from it is deterministic.
+        let bytes = checkpoint.to_bytes();
+        let restored = ProverCheckpoint::from_bytes(bytes, &prover_data.common)?;
+        assert_eq!(prover_data.resume_from_checkpoint(restored)?, resumed);
+
+        data.verify(one_shot)?;
+        data.verify(resumed)
+    }
+
+    #[test]
+    fn test_estimated_verifier_gas_scaling() {
+        let config = CircuitConfig::standard_recursion_config();
+        let base = common_data(config.clone(), 100).estimated_verifier_gas();
+
+        let mut more_queries_config = config.clone();
+        more_queries_config.fri_config.num_query_rounds *= 2;
+        let more_queries = common_data(more_queries_config, 100).estimated_verifier_gas();
+        assert!(more_queries > base);
+
+        let more_rows = common_data(config, 10_000).estimated_verifier_gas();
+        assert!(more_rows > base);
+    }
+
+    #[test]
+    fn test_recursion_circuit_size_estimate() {
+        let config = CircuitConfig::standard_recursion_config();
+        let base = common_data(config.clone(), 100).recursion_circuit_size_estimate();
+
+        let mut more_queries_config = config.clone();
+        more_queries_config.fri_config.num_query_rounds *= 2;
+        let more_queries = common_data(more_queries_config, 100).recursion_circuit_size_estimate();
+        assert!(more_queries > base);
+
+        let common = common_data(config.clone(), 10_000);
+        let more_rows = common.recursion_circuit_size_estimate();
+        assert!(more_rows > base);
+
+        // The estimate is within a small factor of the size of an actual recursive verifier.
+        let mut builder = CircuitBuilder::<F, D>::new(config);
+        let proof = builder.add_virtual_proof_with_pis::<C>(&common);
+        let verifier_data = VerifierCircuitTarget {
+            constants_sigmas_cap: builder.add_virtual_cap(common.config.fri_config.cap_height),
+            circuit_digest: builder.add_virtual_hash(),
+        };
+        builder.verify_proof::<C>(&proof, &verifier_data, &common);
+        let num_gates = builder.num_gates();
+        assert!(more_rows <= 2 * num_gates && num_gates <= 2 * more_rows);
+    }
+
+    #[test]
+    fn test_optimize_for_proof_size() -> Result<()> {
+        let config = CircuitConfig::standard_recursion_config();
+        let degree_bits = common_data(config.clone(), 4000).degree_bits();
+
+        let optimized = config.optimize_for_proof_size(degree_bits);
+        assert!(
+            optimized.estimated_proof_size(degree_bits) < config.estimated_proof_size(degree_bits)
+        );
+        assert!(optimized.fri_config.query_security_bits() >= config.security_bits);
+
+        // The optimized config must still be usable.
+        let mut builder = CircuitBuilder::<F, D>::new(optimized);
+        for _ in 0..4000 {
+            builder.add_gate(NoopGate, vec![]);
+        }
+        let data = builder.build::<C>();
+        assert_eq!(data.common.degree_bits(), degree_bits);
+        let proof = data.prove(PartialWitness::new())?;
+        data.verify(proof)
+    }
+
+    #[test]
+    fn test_max_arithmetic_ops_per_row() -> Result<()> {
+        let build = |config: CircuitConfig| {
+            let mut builder = CircuitBuilder::<F, D>::new(config);
+            let mut pw = PartialWitness::new();
+            for i in 0..20 {
+                let x = builder.add_virtual_target();
+                pw.set_target(x, F::from_canonical_usize(i));
+                let y = builder.mul(x, x);
+                builder.register_public_input(y);
+            }
+            let num_gates = builder.num_gates();
+            (builder.build::<C>(), pw, num_gates)
+        };
+
+        let config = CircuitConfig::standard_recursion_config();
+        let (data, _, num_gates) = build(config.clone());
+        assert!(data
+            .common
+            .gates
+            .iter()
+            .any(|gate| gate.0.id() == "ArithmeticGate { num_ops: 20 }"));
+
+        let capped_config = CircuitConfig {
+            max_arithmetic_ops_per_row: Some(5),
+            ..config
+        };
+        let (data, pw, capped_num_gates) = build(capped_config);
+        assert!(data
+            .common
+            .gates
+            .iter()
+            .any(|gate| gate.0.id() == "ArithmeticGate { num_ops: 5 }"));
+        // The 20 operations take four rows instead of one.
+        assert_eq!(capped_num_gates, num_gates + 3);
+
+        let proof = data.prove(pw)?;
+        data.verify(proof)
+    }
+
+    #[test]
+    fn test_diff() {
+        let build = |num_ops| {
+            let mut builder =
+                CircuitBuilder::<F, D>::new(CircuitConfig::standard_recursion_config());
+            builder.add_gate(ArithmeticGate { num_ops }, vec![F::ONE, F::ONE]);
+            builder.build::<C>().common
+        };
+        let common = build(3);
+        let other = build(4);
+
+        assert!(common.diff(&common).is_empty());
+        assert_eq!(
+            common.diff(&other),
+            CircuitDiff {
+                removed_gates: vec!["ArithmeticGate { num_ops: 3 }".into()],
+                added_gates: vec!["ArithmeticGate { num_ops: 4 }".into()],
+                config: None,
+                fri_params: None,
+                num_public_inputs: None,
+            }
+        );
+    }
+
+    #[test]
+    fn test_export_compatibility() {
+        let config = CircuitConfig::standard_recursion_config();
+        let mut builder = CircuitBuilder::<F, D>::new(config.clone());
+        let x = builder.add_virtual_extension_target();
+        let y = builder.add_virtual_extension_target();
+        let z = builder.mul_add_extension(x, y, x);
+        builder.register_public_inputs(&z.0);
+        let terms = builder.add_virtual_targets(4);
+        let sum = builder.sum(&terms);
+        let s0 = builder.add_virtual_bool_target_safe();
+        let s1 = builder.add_virtual_bool_target_safe();
+        let selected = builder.mux4(s0, s1, [terms[0], terms[1], terms[2], terms[3]]);
+        builder.register_public_input(sum);
+        builder.register_public_input(selected);
+        let report = builder.build::<C>().common.export_compatibility();
+
+        let arithmetic_extension =
+            GateRef::<F, D>::new(ArithmeticExtensionGate::new_from_config(&config))
+                .0
+                .id();
+        let support = report.gate(&arithmetic_extension).unwrap();
+        assert!(support.solidity && support.circom);
+
+        for gate in [
+            GateRef::<F, D>::new(SumGate::new_from_config(&config)),
+            GateRef::<F, D>::new(Mux4Gate::new_from_config(&config)),
+        ] {
+            let support = report.gate(&gate.0.id()).unwrap();
+            assert!(support.solidity && support.circom);
+        }
+
+        // The public inputs are hashed with `PoseidonGate`, which has no Solidity export.
+        let poseidon = GateRef::<F, D>::new(PoseidonGate::<F, D>::new()).0.id();
+        let support = report.gate(&poseidon).unwrap();
+        assert!(!support.solidity && support.circom);
+        assert!(!report.solidity_supported());
+        assert!(report.circom_supported());
+        assert!(report.gate("NoSuchGate").is_none());
+    }
+
+    #[test]
+    fn test_fri_params_export() {
+        let config = CircuitConfig::standard_recursion_config();
+        let common = common_data(config.clone(), 5000);
+
+        let exported = common.fri_params();
+        let fri_config = &config.fri_config;
+        assert_eq!(exported.rate_bits, fri_config.rate_bits);
+        assert_eq!(exported.cap_height, fri_config.cap_height);
+        assert_eq!(exported.num_query_rounds, fri_config.num_query_rounds);
+        assert_eq!(exported.proof_of_work_bits, fri_config.proof_of_work_bits);
+        assert_eq!(
+            exported.reduction_arity_bits,
+            common.fri_params.reduction_arity_bits
+        );
+        assert!(!exported.reduction_arity_bits.is_empty());
+        assert_eq!(exported.degree_bits, common.degree_bits());
+        assert_eq!(exported.hiding, config.zero_knowledge);
+    }
+
+    #[cfg(feature = "serde_json")]
+    #[test]
+    fn test_fri_params_json() {
+        let common = common_data(CircuitConfig::standard_recursion_config(), 5000);
+        let exported = common.fri_params();
+        let json = exported.to_json();
+        assert_eq!(FriParamsExport::from_json(&json).unwrap(), exported);
+        assert!(FriParamsExport::from_json("{}").is_err());
+    }
+
+    #[test]
+    fn test_fri_reduction_arities() {
+        let config = CircuitConfig::standard_recursion_config();
+        let common = common_data(config, 5000);
+
+        let arities = common.fri_reduction_arities();
+        assert!(!arities.is_empty());
+        assert!(arities
+            .iter()
+            .all(|&arity| arity.is_power_of_two() && arity > 1));
+        let final_poly_bits = common.fri_params.final_poly_bits();
+        assert_eq!(
+            arities.iter().product::<usize>(),
+            1 << (common.degree_bits() - final_poly_bits)
+        );
+    }
+
+    #[test]
+    fn test_fft_sizes() {
+        let config = CircuitConfig::standard_recursion_config();
+        let common = common_data(config.clone(), 5000);
+
+        let sizes = common.fft_sizes();
+        assert_eq!(
+            sizes.iter().max(),
+            Some(&(common.degree() << config.fri_config.rate_bits))
+        );
+        assert!(sizes.iter().all(|size| size.is_power_of_two()));
+        // Each wire, Z and partial product polynomial is interpolated over the subgroup.
+        assert_eq!(
+            sizes
+                .iter()
+                .filter(|&&size| size == common.degree())
+                .count(),
+            config.num_wires + config.num_challenges * (1 + common.num_partial_products)
+        );
+    }
+
+    #[test]
+    fn test_common_data_serialization() -> Result<()> {
+        fn build() -> CommonCircuitData<F, D> {
+            let config = CircuitConfig::standard_recursion_config();
+            let mut builder = CircuitBuilder::<F, D>::new(config);
+            let x = builder.add_virtual_public_input();
+            let y = builder.add_virtual_target();
+            let z = builder.mul(x, y);
+            let w = builder.inverse(z);
+            let bits = builder.split_le(w, 64);
+            let sum = builder.add_many(bits.iter().map(|b| b.target));
+            builder.register_public_input(sum);
+            builder.assert_u8(y);
+            let _ = builder.exp_u64(x, 12345);
+            let _ = builder.random_access(y, vec![x, y, z, w]);
+            builder.build::<C>().common
+        }
+
+        let common = build();
+        let bytes = common.to_bytes(&DefaultGateSerializer);
+        assert_eq!(
+            CommonCircuitData::<F, D>::from_bytes(bytes.clone(), &DefaultGateSerializer)?,
+            common
+        );
+        // Building the same circuit again yields the same bytes.
+        assert_eq!(build().to_bytes(&DefaultGateSerializer), bytes);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_verifier_data_malformed_bytes() -> Result<()> {
+        let config = CircuitConfig::standard_recursion_config();
+        let mut builder = CircuitBuilder::<F, D>::new(config);
+        let x = builder.add_virtual_public_input();
+        let y = builder.square(x);
+        builder.register_public_input(y);
+        let data = builder.build::<C>();
+        let mut pw = PartialWitness::new();
+        pw.set_target(x, F::from_canonical_u64(3));
+        let proof = data.prove(pw)?;
+        let verifier_data = data.verifier_data();
+
+        let bytes = verifier_data.to_bytes(&DefaultGateSerializer);
+        let decoded =
+            VerifierCircuitData::<F, C, D>::from_bytes(bytes.clone(), &DefaultGateSerializer)?;
+        assert_eq!(decoded.common, verifier_data.common);
+        assert_eq!(decoded.verifier_only, verifier_data.verifier_only);
+        let proof_bytes = proof.to_bytes();
+
+        let mut rng = ChaCha8Rng::seed_from_u64(0x6feb51b7ec230f25);
+        for _ in 0..1000 {
+            let length = rng.gen_range(0..2 * bytes.len());
+            let random = (0..length).map(|_| rng.gen()).collect();
+            assert!(
+                VerifierCircuitData::<F, C, D>::from_bytes(random, &DefaultGateSerializer).is_err()
+            );
+        }
+        for length in 0..bytes.len() {
+            let truncated = bytes[..length].to_vec();
+            assert!(
+                VerifierCircuitData::<F, C, D>::from_bytes(truncated, &DefaultGateSerializer)
+                    .is_err()
+            );
+        }
+
+        // Corrupted data may happen to decode, but neither decoding nor verifying may panic.
+        for _ in 0..200 {
+            let mut corrupted = bytes.clone();
+            corrupted[rng.gen_range(0..bytes.len())] ^= rng.gen_range(1..=u8::MAX);
+            if let Ok(corrupted_data) =
+                VerifierCircuitData::<F, C, D>::from_bytes(corrupted, &DefaultGateSerializer)
+            {
+                if let Ok(proof) =
+                    ProofWithPublicInputs::from_bytes(proof_bytes.clone(), &corrupted_data.common)
+                {
+                    let _ = corrupted_data.verify(proof);
+                }
+            }
+
+            let mut corrupted = proof_bytes.clone();
+            corrupted[rng.gen_range(0..proof_bytes.len())] ^= rng.gen_range(1..=u8::MAX);
+            if let Ok(proof) = ProofWithPublicInputs::from_bytes(corrupted, &verifier_data.common) {
+                let _ = verifier_data.verify(proof);
+            }
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_quotient_chunks() -> Result<()> {
+        let config = CircuitConfig::standard_recursion_config();
+        let mut builder = CircuitBuilder::<F, D>::new(config.clone());
+        let x = builder.add_virtual_public_input();
+        let y = builder.mul(x, x);
+        builder.register_public_input(y);
+        let data = builder.build::<C>();
+        let common = &data.common;
+
+        // The factor is the configured cap, which must leave room for every constraint along with
+        // its selector.
+        assert_eq!(
+            common.quotient_degree_factor,
+            config.max_quotient_degree_factor
+        );
+        assert!(common.constraint_degree() - 1 <= common.quotient_degree_factor);
+
+        let chunks = common.quotient_chunks();
+        assert_eq!(chunks.len(), config.num_challenges);
+        assert!(chunks
+            .iter()
+            .all(|chunk| chunk.len() == common.quotient_degree_factor));
+
+        let mut pw = PartialWitness::new();
+        pw.set_target(x, F::from_canonical_u64(3));
+        let proof = data.prove(pw)?;
+        assert_eq!(
+            chunks.last().unwrap().end,
+            proof.proof.openings.quotient_polys.len()
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_verify_with_trace() -> Result<()> {
+        let config = CircuitConfig::standard_recursion_config();
+        let mut builder = CircuitBuilder::<F, D>::new(config.clone());
+        let x = builder.add_virtual_public_input();
+        let y = builder.mul(x, x);
+        builder.register_public_input(y);
+        // Make the circuit large enough for FRI to fold at least once.
+        for _ in 0..4000 {
+            builder.add_gate(NoopGate, vec![]);
+        }
+        let data = builder.build::<C>();
+
+        let mut pw = PartialWitness::new();
+        pw.set_target(x, F::from_canonical_u64(3));
+        let proof = data.prove(pw)?;
+
+        let trace = data.verify_with_trace(proof.clone())?;
+        assert!(trace.passed());
+        assert_eq!(trace.constraint_checks.len(), config.num_challenges);
+        assert_eq!(
+            trace.fri.query_rounds.len(),
+            config.fri_config.num_query_rounds
+        );
+        let num_folding_steps = data.common.fri_params.reduction_arity_bits.len();
+        assert!(num_folding_steps > 0);
+        for round in &trace.fri.query_rounds {
+            assert_eq!(round.steps.len(), num_folding_steps);
+        }
+
+        // A tampered opening is recorded as failing checks rather than an error.
+        let mut bad_proof = proof;
+        bad_proof.proof.openings.wires[0] += <C as GenericConfig<D>>::FE::ONE;
+        let trace = data.verify_with_trace(bad_proof)?;
+        assert!(!trace.passed());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_verify_all() -> Result<()> {
+        let config = CircuitConfig::standard_recursion_config();
+        let mut builder = CircuitBuilder::<F, D>::new(config);
+        let x = builder.add_virtual_public_input();
+        let y = builder.mul(x, x);
+        builder.register_public_input(y);
+        for _ in 0..4000 {
+            builder.add_gate(NoopGate, vec![]);
+        }
+        let data = builder.build::<C>();
+
+        let mut pw = PartialWitness::new();
+        pw.set_target(x, F::from_canonical_u64(3));
+        let proof = data.prove(pw)?;
+        assert_eq!(data.verify_all(proof.clone()), vec![]);
+
+        // Tamper with both a leaf opened in the first query round and the proof of work.
+        let mut bad_proof = proof.clone();
+        let opening_proof = &mut bad_proof.proof.opening_proof;
+        opening_proof.query_round_proofs[0]
+            .initial_trees_proof
+            .evals_proofs[1]
+            .0[0] += F::ONE;
+        opening_proof.pow_witness += F::ONE;
+        let failures = data.verify_all(bad_proof);
+        assert!(failures.len() > 1);
+        assert!(failures.contains(&VerificationFailure::ProofOfWork));
+        assert!(failures
+            .iter()
+            .any(|f| matches!(f, VerificationFailure::InitialMerkleProof { .. })));
+
+        let mut malformed = proof;
+        malformed.public_inputs.pop();
+        assert!(matches!(
+            data.verify_all(malformed).as_slice(),
+            [VerificationFailure::Malformed(_)]
+        ));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_prove_and_return_witness() -> Result<()> {
+        let config = CircuitConfig::standard_recursion_config();
+        let mut builder = CircuitBuilder::<F, D>::new(config);
+        let initial_a = builder.add_virtual_target();
+        let initial_b = builder.add_virtual_target();
+        let cur_target = builder.fibonacci_100(initial_a, initial_b);
+        builder.register_public_input(cur_target);
+        let data = builder.build::<C>();
+
+        let mut pw = PartialWitness::new();
+        pw.set_target(initial_a, F::ZERO);
+        pw.set_target(initial_b, F::ONE);
+        let (proof, witness) = data.prove_and_return_witness(pw)?;
+
+        // The 100th Fibonacci number, modulo the Goldilocks order.
+        let expected = F::from_canonical_u64(3736710860384812976);
+        assert_eq!(witness.get_target(cur_target), expected);
+        assert_eq!(proof.public_inputs, vec![expected]);
+
+        data.verify(proof)
+    }
+
+    #[test]
+    fn test_prove_with_dump() -> Result<()> {
+        let config = CircuitConfig::standard_recursion_config();
+        let mut builder = CircuitBuilder::<F, D>::new(config);
+        let initial_a = builder.add_virtual_target();
+        let initial_b = builder.add_virtual_target();
+        let cur_target = builder.fibonacci_100(initial_a, initial_b);
+        builder.register_public_input(cur_target);
+        let data = builder.build::<C>();
+        let common = &data.common;
+
+        let witness = || {
+            let mut pw = PartialWitness::new();
+            pw.set_target(initial_a, F::ZERO);
+            pw.set_target(initial_b, F::ONE);
+            pw
+        };
+        let (proof, dump) = data.prove_with_dump(witness())?;
+        let num_challenges = common.config.num_challenges;
+        assert_eq!(dump.wires.len(), common.config.num_wires);
+        assert_eq!(
+            dump.plonk_zs_partial_products.len(),
+            num_challenges * (1 + common.num_partial_products)
+        );
+        assert_eq!(
+            dump.quotient_polys.len(),
+            num_challenges * common.quotient_degree_factor
+        );
+
+        // The dumped polynomials are the ones opened by the proof.
+        let zeta = proof
+            .get_challenges(
+                proof.get_public_inputs_hash(),
+                &data.verifier_only.circuit_digest,
+                common,
+            )?
+            .plonk_zeta;
+        let openings = &proof.proof.openings;
+        for (polys, opened) in [
+            (&dump.wires[..], &openings.wires[..]),
+            (
+                &dump.plonk_zs_partial_products[..num_challenges],
+                &openings.plonk_zs[..],
+            ),
+            (
+                &dump.plonk_zs_partial_products[num_challenges..],
+                &openings.partial_products[..],
+            ),
+            (&dump.quotient_polys[..], &openings.quotient_polys[..]),
+        ] {
+            for (poly, &value) in polys.iter().zip(opened) {
+                assert_eq!(poly.to_extension::<D>().eval(zeta), value);
+            }
+        }
+
+        // The wire polynomials evaluate to the witness on the subgroup. The builder randomizes the
+        // unused wires of the public input gate, so these differ from a separately generated
+        // witness.
+        let (_, partition_witness) = data.prove_and_return_witness(witness())?;
+        let wire_values = partition_witness.full_witness().wire_values;
+        let random_wires = PublicInputGate::wires_public_inputs_hash().end..common.config.num_wires;
+        let mut mismatched_rows = BTreeSet::new();
+        for (column, (wire, values)) in dump.wires.iter().zip(&wire_values).enumerate() {
+            let evaluations = wire.clone().fft().values;
+            assert_eq!(evaluations.len(), common.degree());
+            for (row, (evaluation, value)) in evaluations.iter().zip(values).enumerate() {
+                if evaluation != value {
+                    assert!(random_wires.contains(&column));
+                    mismatched_rows.insert(row);
+                }
+            }
+        }
+        assert!(mismatched_rows.len() <= 1);
+
+        data.verify(proof)
+    }
+
+    #[test]
+    fn test_generator_serialization() -> Result<()> {
+        let config = CircuitConfig::standard_recursion_config();
+        let mut builder = CircuitBuilder::<F, D>::new(config.clone());
+        let initial_a = builder.add_virtual_target();
+        let initial_b = builder.add_virtual_target();
+        let cur_target = builder.fibonacci_100(initial_a, initial_b);
+        builder.register_public_input(cur_target);
+        // Exercise generators of other gates and gadgets too.
+        let inv = builder.inverse(cur_target);
+        let inv_packed = builder.inverse_packed(cur_target);
+        builder.connect(inv, inv_packed);
+        let inv_sum = builder.inv_sum(initial_b, cur_target);
+        builder.register_public_input(inv_sum);
+        let bits = builder.split_le(initial_b, 8);
+        let selected = builder.mux4(bits[0], bits[1], [initial_a, initial_b, inv, inv_sum]);
+        builder.register_public_input(selected);
+        let data = builder.build::<C>();
+        let prover_data = ProverCircuitData {
+            prover_only: data.prover_only.clone(),
+            common: data.common.clone(),
+        };
+
+        let bytes = prover_data.serialize_generators(&DefaultGeneratorSerializer)?;
+        let generators = data
+            .common
+            .deserialize_generators(bytes, &DefaultGeneratorSerializer)?;
+        assert_eq!(
+            format!("{:?}", generators),
+            format!("{:?}", data.prover_only.generators)
+        );
+        // A single generator with an unknown tag is rejected.
+        assert!(data
+            .common
+            .deserialize_generators(vec![1, 0, 0, 0, u8::MAX], &DefaultGeneratorSerializer)
+            .is_err());
+
+        let mut reloaded_only = data.prover_only.clone();
+        reloaded_only.generators = generators;
+        let reloaded = ProverCircuitData {
+            prover_only: reloaded_only,
+            common: data.common.clone(),
+        };
+
+        let mut pw = PartialWitness::new();
+        pw.set_target(initial_a, F::ZERO);
+        pw.set_target(initial_b, F::ONE);
+        let (_, witness) = prover_data.prove_and_return_witness(pw.clone())?;
+        let (proof, reloaded_witness) = reloaded.prove_and_return_witness(pw)?;
+        data.verify(proof)?;
+
+        // Random value generators fill their targets differently on each run, so those are left
+        // out; every other wire of the two witnesses must match.
+        let random_targets = data
+            .prover_only
+            .generators
+            .iter()
+            .filter(|generator| generator.id() == Some(GeneratorId::RandomValue))
+            .map(|generator| {
+                let mut bytes = Vec::new();
+                generator.serialize(&mut bytes)?;
+                Buffer::new(bytes).read_target()
+            })
+            .collect::<Result<HashSet<_>, _>>()
+            .map_err(anyhow::Error::msg)?;
+        assert!(!random_targets.is_empty());
+        let witness = witness.full_witness();
+        let reloaded_witness = reloaded_witness.full_witness();
+        for row in 0..data.common.degree() {
+            for column in 0..config.num_wires {
+                if !random_targets.contains(&Target::wire(row, column)) {
+                    assert_eq!(
+                        witness.get_wire(row, column),
+                        reloaded_witness.get_wire(row, column),
+                        "Wire {} of row {} differs",
+                        column,
+                        row
+                    );
+                }
+            }
+        }
+
+        // Generators without serialization support are reported.
+        let mut builder = CircuitBuilder::<F, D>::new(config);
+        let x = builder.add_virtual_target();
+        let y = builder.add_virtual_target();
+        let x_is_y = builder.is_equal(x, y);
+        builder.register_public_input(x_is_y.target);
+        let data = builder.build_prover::<C>();
+        assert!(data
+            .serialize_generators(&DefaultGeneratorSerializer)
+            .is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_prove_with_oracle() -> Result<()> {
+        let config = CircuitConfig::standard_recursion_config();
+        let mut builder = CircuitBuilder::<F, D>::new(config);
+        let x = builder.add_virtual_target();
+        let y = builder.add_virtual_target();
+        let z = builder.mul_add(x, y, x);
+        builder.register_public_input(z);
+        let data = builder.build::<C>();
+
+        let prove = || {
+            let calls = RefCell::new(Vec::new());
+            let proof = data.prove_with_oracle(PartialWitness::new(), |target| {
+                calls.borrow_mut().push(target);
+                [(x, F::from_canonical_u64(3)), (y, F::from_canonical_u64(5))]
+                    .into_iter()
+                    .find_map(|(t, v)| (t == target).then_some(v))
+            })?;
+            Ok::<_, anyhow::Error>((proof, calls.into_inner()))
+        };
+        let (proof, calls) = prove()?;
+        assert_eq!(proof.public_inputs, vec![F::from_canonical_u64(18)]);
+        assert!(calls.contains(&x) && calls.contains(&y));
+        // Each target is asked about at most once, in the same order on every run.
+        assert_eq!(calls.iter().collect::<BTreeSet<_>>().len(), calls.len());
+        assert_eq!(prove()?.1, calls);
+
+        data.verify(proof)
+    }
+
+    #[test]
+    fn test_trivial_circuit() -> Result<()> {
+        let trivial = TrivialCircuit::<F, C, D>::new(CircuitConfig::standard_recursion_config());
+        assert_eq!(trivial.data.common.num_public_inputs, 0);
+
+        let proof = trivial.proof()?;
+        // The size only depends on the proof's shape, so it's fixed for a given config.
+        assert_eq!(proof.to_bytes().len(), 62699);
+        trivial.data.verify(proof.clone())?;
+
+        // Later calls return the cached proof rather than proving again.
+        assert!(core::ptr::eq(trivial.proof()?, proof));
+        Ok(())
+    }
+}
@@ -1,4 +1,5 @@
 use alloc::string::String;
+use core::fmt::{Display, Formatter};
 
 use crate::iop::target::Target;
 
@@ -22,3 +23,22 @@ impl CopyConstraint {
         Self { pair, name }
     }
 }
+
+/// An inconsistency among a circuit's copy constraints, which makes it unprovable.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum ConstraintError<F> {
+    /// Copy constraints connect the targets of two distinct constants.
+    ConflictingConstants { first: F, second: F },
+}
+
+impl<F: Display> Display for ConstraintError<F> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        match self {
+            ConstraintError::ConflictingConstants { first, second } => write!(
+                f,
+                "Copy constraints connect the distinct constants {} and {}",
+                first, second
+            ),
+        }
+    }
+}
@@ -1,7 +1,7 @@
-use alloc::vec;
 use alloc::vec::Vec;
+use alloc::{format, vec};
 
-use anyhow::ensure;
+use anyhow::{ensure, Context};
 use maybe_rayon::*;
 use serde::{Deserialize, Serialize};
 
@@ -18,12 +18,14 @@ use crate::hash::hash_types::{MerkleCapTarget, RichField};
 use crate::hash::merkle_tree::MerkleCap;
 use crate::iop::ext_target::ExtensionTarget;
 use crate::iop::target::Target;
-use crate::plonk::circuit_data::{CommonCircuitData, VerifierOnlyCircuitData};
+use crate::plonk::circuit_data::{CommonCircuitData, VerifierCircuitData, VerifierOnlyCircuitData};
 use crate::plonk::config::{GenericConfig, Hasher};
+use crate::plonk::validate_shape::validate_proof_with_pis_structure;
 use crate::plonk::verifier::verify_with_challenges;
-use crate::util::serialization::Write;
+use crate::util::gate_serialization::GateSerializer;
 #[cfg(feature = "std")]
-use crate::util::serialization::{Buffer, Read};
+use crate::util::serialization::{is_sectioned_proof, Buffer, Read};
+use crate::util::serialization::{IoError, IoResult, Remaining, Write};
 
 #[derive(Serialize, Deserialize, Clone, Debug, Eq, PartialEq)]
 #[serde(bound = "")]
@@ -97,12 +99,57 @@ impl<F: RichField + Extendable<D>, C: GenericConfig<D, F = F>, const D: usize>
         })
     }
 
+    /// The purported values of the circuit's polynomials at the challenge point `zeta`, and of the
+    /// permutation polynomials at `g zeta`.
+    pub fn openings(&self) -> &OpeningSet<F, D> {
+        &self.proof.openings
+    }
+
+    /// Checks that the lengths of all vectors in the proof, such as openings, Merkle caps and FRI
+    /// query rounds, match what the circuit described by `common_data` expects. This is much
+    /// cheaper than verification, and rejects malformed proofs early, but says nothing about
+    /// whether the proof is valid.
+    pub fn structural_check(&self, common_data: &CommonCircuitData<F, D>) -> anyhow::Result<()> {
+        validate_proof_with_pis_structure(self, common_data)
+    }
+
     pub fn get_public_inputs_hash(
         &self,
     ) -> <<C as GenericConfig<D>>::InnerHasher as Hasher<F>>::Hash {
         C::InnerHasher::hash_no_pad(&self.public_inputs)
     }
 
+    /// Permutes the public inputs, so that the `i`th public input of the result is the `perm[i]`th
+    /// public input of `self`. Nothing else is changed or re-derived. The proof stays bound to its
+    /// circuit's commitments and to the hash of the original public inputs, so the result fails
+    /// verification against every circuit whenever the order changes. It is only meant for handing
+    /// the public inputs to external verifiers which expect a different order.
+    pub fn with_reordered_public_inputs(self, perm: &[usize]) -> anyhow::Result<Self> {
+        let num_public_inputs = self.public_inputs.len();
+        ensure!(
+            perm.len() == num_public_inputs,
+            "Permutation has length {}, but there are {} public inputs",
+            perm.len(),
+            num_public_inputs
+        );
+        let mut seen = vec![false; num_public_inputs];
+        for &i in perm {
+            ensure!(
+                i < num_public_inputs,
+                "Permutation index {} out of range",
+                i
+            );
+            ensure!(!seen[i], "Permutation repeats index {}", i);
+            seen[i] = true;
+        }
+
+        let public_inputs = perm.iter().map(|&i| self.public_inputs[i]).collect();
+        Ok(Self {
+            proof: self.proof,
+            public_inputs,
+        })
+    }
+
     pub fn to_bytes(&self) -> Vec<u8> {
         let mut buffer = Vec::new();
         buffer
@@ -116,10 +163,15 @@ impl<F: RichField + Extendable<D>, C: GenericConfig<D, F = F>, const D: usize>
         bytes: Vec<u8>,
         common_data: &CommonCircuitData<F, D>,
     ) -> anyhow::Result<Self> {
+        // Proofs serialized before the sectioned format are still accepted.
+        let sectioned = is_sectioned_proof(&bytes);
         let mut buffer = Buffer::new(bytes);
-        let proof = buffer
-            .read_proof_with_public_inputs(common_data)
-            .map_err(anyhow::Error::msg)?;
+        let proof = if sectioned {
+            buffer.read_proof_with_public_inputs(common_data)
+        } else {
+            buffer.read_legacy_proof_with_public_inputs(common_data)
+        }
+        .map_err(anyhow::Error::msg)?;
         Ok(proof)
     }
 }
@@ -246,10 +298,15 @@ impl<F: RichField + Extendable<D>, C: GenericConfig<D, F = F>, const D: usize>
         bytes: Vec<u8>,
         common_data: &CommonCircuitData<F, D>,
     ) -> anyhow::Result<Self> {
+        // Proofs serialized before the sectioned format are still accepted.
+        let sectioned = is_sectioned_proof(&bytes);
         let mut buffer = Buffer::new(bytes);
-        let proof = buffer
-            .read_compressed_proof_with_public_inputs(common_data)
-            .map_err(anyhow::Error::msg)?;
+        let proof = if sectioned {
+            buffer.read_compressed_proof_with_public_inputs(common_data)
+        } else {
+            buffer.read_legacy_compressed_proof_with_public_inputs(common_data)
+        }
+        .map_err(anyhow::Error::msg)?;
         Ok(proof)
     }
 }
@@ -283,6 +340,117 @@ pub(crate) struct FriInferredElements<F: RichField + Extendable<D>, const D: usi
     pub Vec<F::Extension>,
 );
 
+/// Many proofs of a single circuit, stored with one shared copy of the circuit's verifier data.
+#[derive(Debug)]
+pub struct ProofBundle<F: RichField + Extendable<D>, C: GenericConfig<D, F = F>, const D: usize> {
+    pub verifier_data: VerifierCircuitData<F, C, D>,
+    pub proofs: Vec<ProofWithPublicInputs<F, C, D>>,
+}
+
+impl<F: RichField + Extendable<D>, C: GenericConfig<D, F = F>, const D: usize>
+    ProofBundle<F, C, D>
+{
+    /// An empty bundle for proofs of the circuit described by `verifier_data`.
+    pub fn new(verifier_data: VerifierCircuitData<F, C, D>) -> Self {
+        Self {
+            verifier_data,
+            proofs: Vec::new(),
+        }
+    }
+
+    pub fn push(&mut self, proof_with_pis: ProofWithPublicInputs<F, C, D>) {
+        self.proofs.push(proof_with_pis);
+    }
+
+    pub fn len(&self) -> usize {
+        self.proofs.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.proofs.is_empty()
+    }
+
+    pub fn iter(&self) -> core::slice::Iter<ProofWithPublicInputs<F, C, D>> {
+        self.proofs.iter()
+    }
+
+    /// Verifies every proof in the bundle against `verifier_data`, the verifier data of the
+    /// expected circuit. The bundle's own copy must match it; since it is read along with the
+    /// proofs, it cannot be trusted on its own.
+    pub fn verify(&self, verifier_data: &VerifierCircuitData<F, C, D>) -> anyhow::Result<()> {
+        ensure!(
+            self.verifier_data.verifier_only == verifier_data.verifier_only
+                && self.verifier_data.common == verifier_data.common,
+            "The bundle is for a different circuit"
+        );
+        for (i, proof_with_pis) in self.iter().enumerate() {
+            verifier_data
+                .verify(proof_with_pis.clone())
+                .with_context(|| format!("Proof {} of the bundle is invalid", i))?;
+        }
+        Ok(())
+    }
+
+    /// Writes the verifier data, using `gate_serializer` for the gates, followed by the
+    /// length-prefixed proofs.
+    pub fn write<W: Write + ?Sized, G: GateSerializer<F, D>>(
+        &self,
+        dst: &mut W,
+        gate_serializer: &G,
+    ) -> IoResult<()> {
+        dst.write_common_circuit_data(&self.verifier_data.common, gate_serializer)?;
+        dst.write_verifier_only_circuit_data(&self.verifier_data.verifier_only)?;
+        dst.write_u32(self.proofs.len() as u32)?;
+        for proof_with_pis in &self.proofs {
+            let bytes = proof_with_pis.to_bytes();
+            dst.write_u32(bytes.len() as u32)?;
+            dst.write_all(&bytes)?;
+        }
+        Ok(())
+    }
+
+    /// Reads a bundle written by `write`, using `gate_serializer` for the gates.
+    #[cfg(feature = "std")]
+    pub fn read<R: Remaining + ?Sized, G: GateSerializer<F, D>>(
+        src: &mut R,
+        gate_serializer: &G,
+    ) -> IoResult<Self> {
+        let common = src.read_common_circuit_data(gate_serializer)?;
+        let verifier_only = src.read_verifier_only_circuit_data(&common)?;
+        let num_proofs = src.read_u32()? as usize;
+        let proofs = (0..num_proofs)
+            .map(|_| {
+                let length = src.read_u32()? as usize;
+                // Check the length before allocating, since it is untrusted.
+                if length > src.remaining() {
+                    return Err(IoError);
+                }
+                let mut bytes = vec![0; length];
+                src.read_exact(&mut bytes)?;
+                Buffer::new(bytes).read_proof_with_public_inputs(&common)
+            })
+            .collect::<IoResult<Vec<_>>>()?;
+        Ok(Self {
+            verifier_data: VerifierCircuitData {
+                verifier_only,
+                common,
+            },
+            proofs,
+        })
+    }
+}
+
+impl<'a, F: RichField + Extendable<D>, C: GenericConfig<D, F = F>, const D: usize> IntoIterator
+    for &'a ProofBundle<F, C, D>
+{
+    type Item = &'a ProofWithPublicInputs<F, C, D>;
+    type IntoIter = core::slice::Iter<'a, ProofWithPublicInputs<F, C, D>>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
 #[derive(Clone, Debug)]
 pub struct ProofWithPublicInputsTarget<const D: usize> {
     pub proof: ProofTarget<D>,
@@ -333,6 +501,41 @@ impl<F: RichField + Extendable<D>, const D: usize> OpeningSet<F, D> {
         }
     }
 
+    /// The values of the constant polynomials, including selectors, at `zeta`.
+    pub fn constants(&self) -> &[F::Extension] {
+        &self.constants
+    }
+
+    /// The values of the permutation argument's sigma polynomials at `zeta`, one per routed wire.
+    pub fn plonk_sigmas(&self) -> &[F::Extension] {
+        &self.plonk_sigmas
+    }
+
+    /// The values of the wire polynomials at `zeta`.
+    pub fn wires(&self) -> &[F::Extension] {
+        &self.wires
+    }
+
+    /// The values of the permutation argument's `Z` polynomials at `zeta`, one per challenge.
+    pub fn plonk_zs(&self) -> &[F::Extension] {
+        &self.plonk_zs
+    }
+
+    /// The values of the permutation argument's `Z` polynomials at `g zeta`.
+    pub fn plonk_zs_next(&self) -> &[F::Extension] {
+        &self.plonk_zs_next
+    }
+
+    /// The values of the permutation argument's partial products at `zeta`.
+    pub fn partial_products(&self) -> &[F::Extension] {
+        &self.partial_products
+    }
+
+    /// The values of the quotient polynomial chunks at `zeta`.
+    pub fn quotient_polys(&self) -> &[F::Extension] {
+        &self.quotient_polys
+    }
+
     pub(crate) fn to_fri_openings(&self) -> FriOpenings<F, D> {
         let zeta_batch = FriOpeningBatch {
             values: [
@@ -390,16 +593,26 @@ impl<const D: usize> OpeningSetTarget<D> {
 
 #[cfg(test)]
 mod tests {
+    use core::iter::repeat;
+
     use anyhow::Result;
 
-    use crate::field::types::Sample;
+    use crate::field::types::{Field, Sample};
     use crate::fri::reduction_strategies::FriReductionStrategy;
     use crate::gates::noop::NoopGate;
-    use crate::iop::witness::PartialWitness;
+    use crate::hash::hash_types::{HashOut, NUM_HASH_OUT_ELTS};
+    use crate::hash::hashing::PlonkyPermutation;
+    use crate::hash::poseidon::PoseidonPermutation;
+    use crate::iop::witness::{PartialWitness, WitnessWrite};
     use crate::plonk::circuit_builder::CircuitBuilder;
     use crate::plonk::circuit_data::CircuitConfig;
     use crate::plonk::config::{GenericConfig, PoseidonGoldilocksConfig};
+    use crate::plonk::proof::{
+        CompressedProofWithPublicInputs, ProofBundle, ProofWithPublicInputs,
+    };
     use crate::plonk::verifier::verify;
+    use crate::util::gate_serialization::DefaultGateSerializer;
+    use crate::util::serialization::{Buffer, Write};
 
     #[test]
     fn test_proof_compression() -> Result<()> {
@@ -438,4 +651,419 @@ mod tests {
         verify(proof, &data.verifier_only, &data.common)?;
         data.verify_compressed(compressed_proof)
     }
+
+    #[test]
+    fn test_many_public_inputs() -> Result<()> {
+        const D: usize = 2;
+        type C = PoseidonGoldilocksConfig;
+        type F = <C as GenericConfig<D>>::F;
+
+        let config = CircuitConfig::standard_recursion_config();
+        let mut pw = PartialWitness::new();
+        let mut builder = CircuitBuilder::<F, D>::new(config);
+
+        let public_inputs = F::rand_vec(100);
+        for &x in &public_inputs {
+            let t = builder.add_virtual_public_input();
+            pw.set_target(t, x);
+        }
+        let data = builder.build::<C>();
+        let proof = data.prove(pw)?;
+        assert_eq!(proof.public_inputs, public_inputs);
+
+        // Absorb all public inputs with a host sponge in overwrite mode, then squeeze the hash.
+        let mut perm = PoseidonPermutation::new(repeat(F::ZERO));
+        for chunk in public_inputs.chunks(PoseidonPermutation::<F>::RATE) {
+            perm.set_from_slice(chunk, 0);
+            perm.permute();
+        }
+        let expected_hash = HashOut::from_partial(&perm.squeeze()[..NUM_HASH_OUT_ELTS]);
+        assert_eq!(proof.get_public_inputs_hash(), expected_hash);
+
+        verify(proof, &data.verifier_only, &data.common)
+    }
+
+    #[test]
+    fn test_domain_separator() -> Result<()> {
+        const D: usize = 2;
+        type C = PoseidonGoldilocksConfig;
+        type F = <C as GenericConfig<D>>::F;
+
+        let x_value = F::rand();
+        let circuit_under = |domain_separator: Option<Vec<F>>| {
+            let config = CircuitConfig::standard_recursion_config();
+            let mut pw = PartialWitness::new();
+            let mut builder = CircuitBuilder::<F, D>::new(config);
+            if let Some(separator) = domain_separator {
+                builder.set_domain_separator(separator);
+            }
+            let x = builder.add_virtual_public_input();
+            pw.set_target(x, x_value);
+            let y = builder.square(x);
+            builder.register_public_input(y);
+            (builder.build::<C>(), pw)
+        };
+        let domain_a = [1, 2].map(F::from_canonical_u64).to_vec();
+        let (data, pw) = circuit_under(Some(domain_a));
+        let proof = data.prove(pw)?;
+
+        // The same circuit under any other domain, including a longer one with the same prefix and
+        // no domain at all, must reject the proof.
+        for domain_b in [
+            Some(vec![F::TWO, F::ONE]),
+            Some(vec![F::ONE, F::TWO, F::ZERO]),
+            None,
+        ] {
+            let (data_b, _) = circuit_under(domain_b);
+            assert!(data_b.verify(proof.clone()).is_err());
+        }
+
+        let compressed_proof = data.compress(proof.clone())?;
+        data.verify_compressed(compressed_proof)?;
+        data.verify(proof)
+    }
+
+    #[test]
+    fn test_fixed_query_indices() -> Result<()> {
+        const D: usize = 2;
+        type C = PoseidonGoldilocksConfig;
+        type F = <C as GenericConfig<D>>::F;
+
+        let config = CircuitConfig::standard_recursion_config();
+        let num_query_rounds = config.fri_config.num_query_rounds;
+        let pw = PartialWitness::new();
+        let mut builder = CircuitBuilder::<F, D>::new(config);
+        let x = builder.constant(F::rand());
+        let y = builder.constant(F::rand());
+        let z = builder.mul(x, y);
+        builder.register_public_input(z);
+        let data = builder.build::<C>();
+
+        let lde_size = data.common.lde_size();
+        let indices = (0..num_query_rounds)
+            .map(|i| (i * 37 + 5) % lde_size)
+            .collect::<Vec<_>>();
+        let mut out_of_range = indices.clone();
+        out_of_range[0] = lde_size;
+        assert!(data
+            .prove_with_fixed_query_indices(pw.clone(), &out_of_range)
+            .is_err());
+        let proof = data.prove_with_fixed_query_indices(pw, &indices)?;
+
+        // Each query round must open exactly the pinned position.
+        let constants_sigmas_tree = &data.prover_only.constants_sigmas_commitment.merkle_tree;
+        for (round, &index) in proof
+            .proof
+            .opening_proof
+            .query_round_proofs
+            .iter()
+            .zip(&indices)
+        {
+            assert_eq!(
+                round.initial_trees_proof.evals_proofs[0].0,
+                constants_sigmas_tree.get(index)
+            );
+        }
+
+        assert!(data.verify(proof.clone()).is_err());
+        assert!(data
+            .verify_with_fixed_query_indices(proof.clone(), &out_of_range)
+            .is_err());
+        data.verify_with_fixed_query_indices(proof, &indices)
+    }
+
+    #[test]
+    fn test_unknown_trailing_sections() -> Result<()> {
+        const D: usize = 2;
+        type C = PoseidonGoldilocksConfig;
+        type F = <C as GenericConfig<D>>::F;
+
+        let config = CircuitConfig::standard_recursion_config();
+        let mut pw = PartialWitness::new();
+        let mut builder = CircuitBuilder::<F, D>::new(config);
+        let x = builder.add_virtual_public_input();
+        pw.set_target(x, F::rand());
+        let y = builder.square(x);
+        builder.register_public_input(y);
+        let data = builder.build::<C>();
+        let proof = data.prove(pw)?;
+
+        // A section with an unknown tag, as a future format version might append.
+        let unknown_section = [7, 3, 0, 0, 0, 1, 2, 3];
+
+        let mut bytes = proof.to_bytes();
+        bytes.extend(unknown_section);
+        let read = ProofWithPublicInputs::<F, C, D>::from_bytes(bytes, &data.common)?;
+        assert_eq!(read, proof);
+
+        let compressed_proof = data.compress(proof.clone())?;
+        let mut compressed_bytes = compressed_proof.to_bytes();
+        compressed_bytes.extend(unknown_section);
+        let read_compressed =
+            CompressedProofWithPublicInputs::<F, C, D>::from_bytes(compressed_bytes, &data.common)?;
+        assert_eq!(read_compressed, compressed_proof);
+
+        // Trailing sections must still be well-formed, and the core sections are mandatory.
+        let mut bytes = proof.to_bytes();
+        bytes.extend(&unknown_section[..6]);
+        assert!(ProofWithPublicInputs::<F, C, D>::from_bytes(bytes, &data.common).is_err());
+        let mut bytes = proof.to_bytes();
+        bytes.truncate(bytes.len() - (4 + 1 + 2 * 8));
+        assert!(ProofWithPublicInputs::<F, C, D>::from_bytes(bytes, &data.common).is_err());
+
+        // Unknown format versions are rejected.
+        let mut bytes = proof.to_bytes();
+        bytes[8] += 1;
+        assert!(ProofWithPublicInputs::<F, C, D>::from_bytes(bytes, &data.common).is_err());
+
+        // Proofs in the legacy layout, without a format marker or sections, are still read.
+        let mut legacy_bytes = Vec::new();
+        legacy_bytes.write_proof(&proof.proof).unwrap();
+        legacy_bytes.write_field_vec(&proof.public_inputs).unwrap();
+        let read_legacy = ProofWithPublicInputs::<F, C, D>::from_bytes(legacy_bytes, &data.common)?;
+        assert_eq!(read_legacy, proof);
+
+        let mut legacy_compressed_bytes = Vec::new();
+        legacy_compressed_bytes
+            .write_compressed_proof(&compressed_proof.proof)
+            .unwrap();
+        legacy_compressed_bytes
+            .write_field_vec(&compressed_proof.public_inputs)
+            .unwrap();
+        let read_legacy_compressed = CompressedProofWithPublicInputs::<F, C, D>::from_bytes(
+            legacy_compressed_bytes,
+            &data.common,
+        )?;
+        assert_eq!(read_legacy_compressed, compressed_proof);
+
+        data.verify(read)
+    }
+
+    #[test]
+    fn test_proof_bundle() -> Result<()> {
+        const D: usize = 2;
+        type C = PoseidonGoldilocksConfig;
+        type F = <C as GenericConfig<D>>::F;
+
+        // Proves the 100th element of the Fibonacci-like sequence with the given first elements.
+        let config = CircuitConfig::standard_recursion_config();
+        let mut builder = CircuitBuilder::<F, D>::new(config);
+        let initial_a = builder.add_virtual_public_input();
+        let initial_b = builder.add_virtual_public_input();
+        let cur_target = builder.fibonacci_100(initial_a, initial_b);
+        builder.register_public_input(cur_target);
+        let data = builder.build::<C>();
+
+        let mut proofs = Vec::new();
+        for i in 0..5 {
+            let mut pw = PartialWitness::new();
+            pw.set_target(initial_a, F::from_canonical_u64(i));
+            pw.set_target(initial_b, F::from_canonical_u64(i + 1));
+            proofs.push(data.prove(pw)?);
+        }
+        let verifier_data = data.verifier_data();
+        let mut bundle = ProofBundle::new(verifier_data.clone());
+        for proof in &proofs {
+            bundle.push(proof.clone());
+        }
+
+        let mut bytes = Vec::new();
+        bundle.write(&mut bytes, &DefaultGateSerializer).unwrap();
+        // The verifier data is only stored once.
+        let proof_bytes = proofs.iter().map(|p| 4 + p.to_bytes().len()).sum::<usize>();
+        let verifier_bytes = bytes.len() - 4 - proof_bytes;
+        assert!(verifier_bytes < proofs[0].to_bytes().len());
+
+        let read = ProofBundle::<F, C, D>::read(&mut Buffer::new(bytes), &DefaultGateSerializer)
+            .map_err(anyhow::Error::msg)?;
+        assert_eq!(read.len(), 5);
+        assert_eq!(read.verifier_data.common, bundle.verifier_data.common);
+        assert_eq!(
+            read.verifier_data.verifier_only,
+            bundle.verifier_data.verifier_only
+        );
+        for (read_proof, proof) in read.iter().zip(&proofs) {
+            assert_eq!(read_proof, proof);
+            read.verifier_data.verify(read_proof.clone())?;
+        }
+        read.verify(&verifier_data)?;
+
+        // A bundle of proofs of another circuit is rejected, even though it is self-consistent.
+        let mut builder = CircuitBuilder::<F, D>::new(CircuitConfig::standard_recursion_config());
+        let x = builder.add_virtual_public_input();
+        let square = builder.square(x);
+        builder.register_public_input(square);
+        let other = builder.build::<C>();
+        let mut pw = PartialWitness::new();
+        pw.set_target(x, F::TWO);
+        let other_proof = other.prove(pw)?;
+        let other_verifier_data = other.verifier_data();
+        let mut forged = ProofBundle::new(other_verifier_data.clone());
+        forged.push(other_proof);
+        forged.verify(&other_verifier_data)?;
+        assert!(forged.verify(&verifier_data).is_err());
+
+        // A proof length beyond the end of the data is rejected before allocating it.
+        let mut bytes = Vec::new();
+        ProofBundle::new(verifier_data)
+            .write(&mut bytes, &DefaultGateSerializer)
+            .unwrap();
+        bytes.truncate(bytes.len() - 4);
+        bytes.write_u32(1).unwrap();
+        bytes.write_u32(u32::MAX).unwrap();
+        assert!(
+            ProofBundle::<F, C, D>::read(&mut Buffer::new(bytes), &DefaultGateSerializer).is_err()
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_openings() -> Result<()> {
+        const D: usize = 2;
+        type C = PoseidonGoldilocksConfig;
+        type F = <C as GenericConfig<D>>::F;
+
+        let config = CircuitConfig::standard_recursion_config();
+        let mut pw = PartialWitness::new();
+        let mut builder = CircuitBuilder::<F, D>::new(config);
+        let x = builder.add_virtual_target();
+        let y = builder.add_virtual_target();
+        let z = builder.mul(x, y);
+        builder.register_public_input(z);
+        pw.set_target(x, F::rand());
+        pw.set_target(y, F::rand());
+        let data = builder.build::<C>();
+        let proof = data.prove(pw)?;
+
+        let common = &data.common;
+        let num_challenges = common.config.num_challenges;
+        let openings = proof.openings();
+        assert_eq!(openings.constants().len(), common.num_constants);
+        assert_eq!(
+            openings.plonk_sigmas().len(),
+            common.config.num_routed_wires
+        );
+        assert_eq!(openings.wires().len(), common.config.num_wires);
+        assert_eq!(openings.plonk_zs().len(), num_challenges);
+        assert_eq!(openings.plonk_zs_next().len(), num_challenges);
+        assert_eq!(
+            openings.partial_products().len(),
+            num_challenges * common.num_partial_products
+        );
+        assert_eq!(
+            openings.quotient_polys().len(),
+            num_challenges * common.quotient_degree_factor
+        );
+
+        data.verify(proof)
+    }
+
+    #[test]
+    fn test_query_path_lengths() -> Result<()> {
+        const D: usize = 2;
+        type C = PoseidonGoldilocksConfig;
+        type F = <C as GenericConfig<D>>::F;
+
+        let config = CircuitConfig::standard_recursion_config();
+        let mut pw = PartialWitness::new();
+        let mut builder = CircuitBuilder::<F, D>::new(config);
+        let x = builder.add_virtual_target();
+        pw.set_target(x, F::rand());
+        let y = builder.exp_u64(x, 1 << 40);
+        builder.register_public_input(y);
+        let data = builder.build::<C>();
+        let proof = data.prove(pw)?;
+
+        let fri_config = &data.common.config.fri_config;
+        let expected = data.common.degree_bits() + fri_config.rate_bits - fri_config.cap_height;
+        let lengths = proof.proof.opening_proof.query_path_lengths();
+        assert_eq!(lengths.len(), fri_config.num_query_rounds);
+        assert!(lengths.iter().all(|&length| length == expected));
+        for round in &proof.proof.opening_proof.query_round_proofs {
+            for (_, merkle_proof) in &round.initial_trees_proof.evals_proofs {
+                assert_eq!(merkle_proof.len(), expected);
+            }
+        }
+
+        data.verify(proof)
+    }
+
+    #[test]
+    fn test_structural_check() -> Result<()> {
+        const D: usize = 2;
+        type C = PoseidonGoldilocksConfig;
+        type F = <C as GenericConfig<D>>::F;
+
+        let config = CircuitConfig::standard_recursion_config();
+        let mut pw = PartialWitness::new();
+        let mut builder = CircuitBuilder::<F, D>::new(config);
+        let x = builder.add_virtual_public_input();
+        let y = builder.square(x);
+        builder.register_public_input(y);
+        pw.set_target(x, F::rand());
+        let data = builder.build::<C>();
+        let proof = data.prove(pw)?;
+        proof.structural_check(&data.common)?;
+
+        let mut missing_round = proof.clone();
+        missing_round.proof.opening_proof.query_round_proofs.pop();
+        let err = missing_round.structural_check(&data.common).unwrap_err();
+        assert!(err.to_string().contains("query rounds"));
+
+        let mut missing_input = proof.clone();
+        missing_input.public_inputs.pop();
+        assert!(missing_input.structural_check(&data.common).is_err());
+
+        let mut short_final_poly = proof.clone();
+        short_final_poly.proof.opening_proof.final_poly.coeffs.pop();
+        assert!(short_final_poly.structural_check(&data.common).is_err());
+
+        data.verify(proof)
+    }
+
+    #[test]
+    fn test_with_reordered_public_inputs() -> Result<()> {
+        const D: usize = 2;
+        type C = PoseidonGoldilocksConfig;
+        type F = <C as GenericConfig<D>>::F;
+
+        let values = [1, 2, 3].map(F::from_canonical_u64);
+        let prove_in_order = |order: [usize; 3]| {
+            let config = CircuitConfig::standard_recursion_config();
+            let mut pw = PartialWitness::new();
+            let mut builder = CircuitBuilder::<F, D>::new(config);
+            for i in order {
+                let t = builder.add_virtual_public_input();
+                pw.set_target(t, values[i]);
+            }
+            let data = builder.build::<C>();
+            let proof = data.prove(pw)?;
+            Ok::<_, anyhow::Error>((data, proof))
+        };
+
+        let (data, proof) = prove_in_order([0, 1, 2])?;
+        let (reordered_data, reordered_circuit_proof) = prove_in_order([2, 0, 1])?;
+
+        // The permuted proof carries the public inputs of a circuit registering them in the
+        // permuted order, but verifies against no circuit.
+        let reordered = proof.clone().with_reordered_public_inputs(&[2, 0, 1])?;
+        assert_eq!(
+            reordered.public_inputs,
+            reordered_circuit_proof.public_inputs
+        );
+        assert!(data.verify(reordered.clone()).is_err());
+        assert!(reordered_data.verify(reordered).is_err());
+
+        assert!(proof.clone().with_reordered_public_inputs(&[0, 1]).is_err());
+        assert!(proof
+            .clone()
+            .with_reordered_public_inputs(&[0, 1, 3])
+            .is_err());
+        assert!(proof
+            .clone()
+            .with_reordered_public_inputs(&[0, 1, 1])
+            .is_err());
+
+        data.verify(proof.with_reordered_public_inputs(&[0, 1, 2])?)
+    }
 }
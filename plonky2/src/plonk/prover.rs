@@ -12,8 +12,9 @@ use crate::field::zero_poly_coset::ZeroPolyOnCoset;
 use crate::fri::oracle::PolynomialBatch;
 use crate::hash::hash_types::RichField;
 use crate::iop::challenger::Challenger;
-use crate::iop::generator::generate_partial_witness;
-use crate::iop::witness::{MatrixWitness, PartialWitness, Witness};
+use crate::iop::generator::generate_partial_witness_with_oracle;
+use crate::iop::target::Target;
+use crate::iop::witness::{MatrixWitness, PartialWitness, PartitionWitness, Witness};
 use crate::plonk::circuit_data::{CommonCircuitData, ProverOnlyCircuitData};
 use crate::plonk::config::{GenericConfig, Hasher};
 use crate::plonk::plonk_common::PlonkOracle;
@@ -22,6 +23,9 @@ use crate::plonk::vanishing_poly::eval_vanishing_poly_base_batch;
 use crate::plonk::vars::EvaluationVarsBaseBatch;
 use crate::timed;
 use crate::util::partial_products::{partial_products_and_z_gx, quotient_chunk_products};
+use crate::util::serialization::Write;
+#[cfg(feature = "std")]
+use crate::util::serialization::{Buffer, Read, Remaining};
 use crate::util::timing::TimingTree;
 use crate::util::{ceil_div_usize, log2_ceil, transpose};
 
@@ -31,6 +35,226 @@ pub fn prove<F: RichField + Extendable<D>, C: GenericConfig<D, F = F>, const D:
     inputs: PartialWitness<F>,
     timing: &mut TimingTree,
 ) -> Result<ProofWithPublicInputs<F, C, D>> {
+    prove_with_options(prover_data, common_data, inputs, &|_| None, None, timing)
+        .map(|(proof, _)| proof)
+}
+
+/// Like `prove`, but also returns the witness generated for the proof, so that tools which need it
+/// don't have to run witness generation a second time.
+pub fn prove_and_return_witness<
+    'a,
+    F: RichField + Extendable<D>,
+    C: GenericConfig<D, F = F>,
+    const D: usize,
+>(
+    prover_data: &'a ProverOnlyCircuitData<F, C, D>,
+    common_data: &'a CommonCircuitData<F, D>,
+    inputs: PartialWitness<F>,
+    timing: &mut TimingTree,
+) -> Result<(ProofWithPublicInputs<F, C, D>, PartitionWitness<'a, F>)> {
+    prove_with_options(prover_data, common_data, inputs, &|_| None, None, timing)
+}
+
+/// Like `prove`, but targets which are neither set in `inputs` nor computed by a generator are
+/// resolved lazily by calling `oracle`, e.g. to fetch them from an external service; see
+/// `generate_partial_witness_with_oracle`. The oracle is only called on targets that witness
+/// generation is waiting on, and at most once per target.
+pub fn prove_with_oracle<
+    F: RichField + Extendable<D>,
+    C: GenericConfig<D, F = F>,
+    const D: usize,
+>(
+    prover_data: &ProverOnlyCircuitData<F, C, D>,
+    common_data: &CommonCircuitData<F, D>,
+    inputs: PartialWitness<F>,
+    oracle: impl Fn(Target) -> Option<F>,
+    timing: &mut TimingTree,
+) -> Result<ProofWithPublicInputs<F, C, D>> {
+    prove_with_options(prover_data, common_data, inputs, &oracle, None, timing)
+        .map(|(proof, _)| proof)
+}
+
+/// Like `prove`, but the FRI query rounds open `fixed_query_indices` instead of indices derived
+/// via Fiat-Shamir.
+///
+/// WARNING: The resulting proof is NOT sound, since the prover knows the queried positions in
+/// advance. This is only meant for benchmarks which need an identical query set across runs, and
+/// such proofs are only accepted by `verify_with_fixed_query_indices`.
+#[cfg(any(feature = "fixed_fri_queries", test))]
+pub fn prove_with_fixed_query_indices<
+    F: RichField + Extendable<D>,
+    C: GenericConfig<D, F = F>,
+    const D: usize,
+>(
+    prover_data: &ProverOnlyCircuitData<F, C, D>,
+    common_data: &CommonCircuitData<F, D>,
+    inputs: PartialWitness<F>,
+    fixed_query_indices: &[usize],
+    timing: &mut TimingTree,
+) -> Result<ProofWithPublicInputs<F, C, D>> {
+    ensure!(
+        fixed_query_indices.len() == common_data.config.fri_config.num_query_rounds,
+        "Expected one query index per FRI query round."
+    );
+    let lde_size = common_data.lde_size();
+    ensure!(
+        fixed_query_indices
+            .iter()
+            .all(|&x_index| x_index < lde_size),
+        "Query index out of range of the LDE domain."
+    );
+    prove_with_options(
+        prover_data,
+        common_data,
+        inputs,
+        &|_| None,
+        Some(fixed_query_indices),
+        timing,
+    )
+    .map(|(proof, _)| proof)
+}
+
+/// The coefficients of the polynomials committed to by the prover, recorded by `prove_with_dump`,
+/// e.g. to compare a proof generation against a reference run.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct PolynomialDump<F: Field> {
+    /// The wire polynomials, which interpolate the witness over the subgroup.
+    pub wires: Vec<PolynomialCoeffs<F>>,
+    /// The `Z` polynomials, followed by the partial products polynomials.
+    pub plonk_zs_partial_products: Vec<PolynomialCoeffs<F>>,
+    /// The quotient polynomials, split into chunks of degree less than the circuit's degree.
+    pub quotient_polys: Vec<PolynomialCoeffs<F>>,
+}
+
+/// Like `prove`, but also returns the committed polynomials; see `PolynomialDump`.
+pub fn prove_with_dump<F: RichField + Extendable<D>, C: GenericConfig<D, F = F>, const D: usize>(
+    prover_data: &ProverOnlyCircuitData<F, C, D>,
+    common_data: &CommonCircuitData<F, D>,
+    inputs: PartialWitness<F>,
+    timing: &mut TimingTree,
+) -> Result<(ProofWithPublicInputs<F, C, D>, PolynomialDump<F>)> {
+    let (checkpoint, _) = commit_phase(prover_data, common_data, inputs, &|_| None, timing);
+    let dump = PolynomialDump {
+        wires: checkpoint.wires_commitment.polynomials.clone(),
+        plonk_zs_partial_products: checkpoint
+            .partial_products_and_zs_commitment
+            .polynomials
+            .clone(),
+        quotient_polys: checkpoint.quotient_polys_commitment.polynomials.clone(),
+    };
+    let proof_with_pis = opening_phase(prover_data, common_data, checkpoint, None, timing)?;
+    Ok((proof_with_pis, dump))
+}
+
+/// The state of the prover once the wire, permutation and quotient polynomials are committed, but
+/// before any openings are computed. Proving can be split at this point, e.g. to run the FRI phase
+/// later or on another machine; see `prove_until_commit` and `resume_from_checkpoint`.
+#[derive(Clone)]
+pub struct ProverCheckpoint<
+    F: RichField + Extendable<D>,
+    C: GenericConfig<D, F = F>,
+    const D: usize,
+> {
+    pub public_inputs: Vec<F>,
+    pub wires_commitment: PolynomialBatch<F, C, D>,
+    pub partial_products_and_zs_commitment: PolynomialBatch<F, C, D>,
+    pub quotient_polys_commitment: PolynomialBatch<F, C, D>,
+    /// The transcript, having observed the instance and all three commitments.
+    pub challenger: Challenger<F, C::Hasher>,
+}
+
+impl<F: RichField + Extendable<D>, C: GenericConfig<D, F = F>, const D: usize>
+    ProverCheckpoint<F, C, D>
+{
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut buffer = Vec::new();
+        buffer
+            .write_prover_checkpoint(self)
+            .expect("Writing to a byte-vector cannot fail.");
+        buffer
+    }
+
+    #[cfg(feature = "std")]
+    pub fn from_bytes(
+        bytes: Vec<u8>,
+        common_data: &CommonCircuitData<F, D>,
+    ) -> anyhow::Result<Self> {
+        let mut buffer = Buffer::new(bytes);
+        let checkpoint = buffer
+            .read_prover_checkpoint(common_data)
+            .map_err(anyhow::Error::msg)?;
+        ensure!(
+            buffer.is_empty(),
+            "Trailing bytes after the prover checkpoint"
+        );
+        Ok(checkpoint)
+    }
+}
+
+/// Runs the prover up to and including the commitment to the quotient polynomials. The proof can
+/// then be completed with `resume_from_checkpoint`.
+pub fn prove_until_commit<
+    F: RichField + Extendable<D>,
+    C: GenericConfig<D, F = F>,
+    const D: usize,
+>(
+    prover_data: &ProverOnlyCircuitData<F, C, D>,
+    common_data: &CommonCircuitData<F, D>,
+    inputs: PartialWitness<F>,
+    timing: &mut TimingTree,
+) -> ProverCheckpoint<F, C, D> {
+    commit_phase(prover_data, common_data, inputs, &|_| None, timing).0
+}
+
+/// Completes a proof started by `prove_until_commit`, as `prove` would have. Resuming is
+/// deterministic, so the same checkpoint always yields the same proof.
+pub fn resume_from_checkpoint<
+    F: RichField + Extendable<D>,
+    C: GenericConfig<D, F = F>,
+    const D: usize,
+>(
+    prover_data: &ProverOnlyCircuitData<F, C, D>,
+    common_data: &CommonCircuitData<F, D>,
+    checkpoint: ProverCheckpoint<F, C, D>,
+    timing: &mut TimingTree,
+) -> Result<ProofWithPublicInputs<F, C, D>> {
+    opening_phase(prover_data, common_data, checkpoint, None, timing)
+}
+
+fn prove_with_options<
+    'a,
+    F: RichField + Extendable<D>,
+    C: GenericConfig<D, F = F>,
+    const D: usize,
+>(
+    prover_data: &'a ProverOnlyCircuitData<F, C, D>,
+    common_data: &'a CommonCircuitData<F, D>,
+    inputs: PartialWitness<F>,
+    oracle: &dyn Fn(Target) -> Option<F>,
+    fixed_query_indices: Option<&[usize]>,
+    timing: &mut TimingTree,
+) -> Result<(ProofWithPublicInputs<F, C, D>, PartitionWitness<'a, F>)> {
+    let (checkpoint, partition_witness) =
+        commit_phase(prover_data, common_data, inputs, oracle, timing);
+    let proof_with_pis = opening_phase(
+        prover_data,
+        common_data,
+        checkpoint,
+        fixed_query_indices,
+        timing,
+    )?;
+    Ok((proof_with_pis, partition_witness))
+}
+
+/// Runs witness generation and commits to the wire, permutation and quotient polynomials,
+/// returning the state needed to compute the opening proofs.
+fn commit_phase<'a, F: RichField + Extendable<D>, C: GenericConfig<D, F = F>, const D: usize>(
+    prover_data: &'a ProverOnlyCircuitData<F, C, D>,
+    common_data: &'a CommonCircuitData<F, D>,
+    inputs: PartialWitness<F>,
+    oracle: &dyn Fn(Target) -> Option<F>,
+    timing: &mut TimingTree,
+) -> (ProverCheckpoint<F, C, D>, PartitionWitness<'a, F>) {
     let config = &common_data.config;
     let num_challenges = config.num_challenges;
     let quotient_degree = common_data.quotient_degree();
@@ -39,7 +263,7 @@ pub fn prove<F: RichField + Extendable<D>, C: GenericConfig<D, F = F>, const D:
     let partition_witness = timed!(
         timing,
         &format!("run {} generators", prover_data.generators.len()),
-        generate_partial_witness(inputs, prover_data, common_data)
+        generate_partial_witness_with_oracle(inputs, prover_data, common_data, oracle)
     );
 
     let public_inputs = partition_witness.get_targets(&prover_data.public_inputs);
@@ -164,6 +388,32 @@ pub fn prove<F: RichField + Extendable<D>, C: GenericConfig<D, F = F>, const D:
 
     challenger.observe_cap(&quotient_polys_commitment.merkle_tree.cap);
 
+    let checkpoint = ProverCheckpoint {
+        public_inputs,
+        wires_commitment,
+        partial_products_and_zs_commitment,
+        quotient_polys_commitment,
+        challenger,
+    };
+    (checkpoint, partition_witness)
+}
+
+/// Computes the openings at `zeta` and their FRI proofs, given the committed state.
+fn opening_phase<F: RichField + Extendable<D>, C: GenericConfig<D, F = F>, const D: usize>(
+    prover_data: &ProverOnlyCircuitData<F, C, D>,
+    common_data: &CommonCircuitData<F, D>,
+    checkpoint: ProverCheckpoint<F, C, D>,
+    fixed_query_indices: Option<&[usize]>,
+    timing: &mut TimingTree,
+) -> Result<ProofWithPublicInputs<F, C, D>> {
+    let ProverCheckpoint {
+        public_inputs,
+        wires_commitment,
+        partial_products_and_zs_commitment,
+        quotient_polys_commitment,
+        mut challenger,
+    } = checkpoint;
+
     let zeta = challenger.get_extension_challenge::<D>();
     // To avoid leaking witness data, we want to ensure that our opening locations, `zeta` and
     // `g * zeta`, are not in our subgroup `H`. It suffices to check `zeta` only, since
@@ -192,7 +442,7 @@ pub fn prove<F: RichField + Extendable<D>, C: GenericConfig<D, F = F>, const D:
     let opening_proof = timed!(
         timing,
         "compute opening proofs",
-        PolynomialBatch::prove_openings(
+        PolynomialBatch::prove_openings_with_query_indices(
             &common_data.get_fri_instance(zeta),
             &[
                 &prover_data.constants_sigmas_commitment,
@@ -202,6 +452,7 @@ pub fn prove<F: RichField + Extendable<D>, C: GenericConfig<D, F = F>, const D:
             ],
             &mut challenger,
             &common_data.fri_params,
+            fixed_query_indices,
             timing,
         )
     );
@@ -1,6 +1,8 @@
 use anyhow::ensure;
 
 use crate::field::extension::Extendable;
+use crate::field::types::Field;
+use crate::fri::validate_shape::validate_fri_proof_shape;
 use crate::hash::hash_types::RichField;
 use crate::plonk::circuit_data::CommonCircuitData;
 use crate::plonk::config::GenericConfig;
@@ -26,6 +28,34 @@ where
     Ok(())
 }
 
+/// Like `validate_proof_with_pis_shape`, but also checks the shape of the FRI opening proof,
+/// including the number of commit phase caps and query rounds, so that no later step of
+/// verification can fail because of a malformed proof.
+pub(crate) fn validate_proof_with_pis_structure<F, C, const D: usize>(
+    proof_with_pis: &ProofWithPublicInputs<F, C, D>,
+    common_data: &CommonCircuitData<F, D>,
+) -> anyhow::Result<()>
+where
+    F: RichField + Extendable<D>,
+    C: GenericConfig<D, F = F>,
+{
+    validate_proof_with_pis_shape(proof_with_pis, common_data)?;
+
+    let fri_proof = &proof_with_pis.proof.opening_proof;
+    let params = &common_data.fri_params;
+    ensure!(
+        fri_proof.commit_phase_merkle_caps.len() == params.reduction_arity_bits.len(),
+        "Number of commit phase Merkle caps does not match FRI parameters."
+    );
+    ensure!(
+        fri_proof.query_round_proofs.len() == params.config.num_query_rounds,
+        "Number of query rounds does not match config."
+    );
+    // The opening points do not affect the shape of the FRI proof, so any point will do.
+    let instance = common_data.get_fri_instance(F::Extension::ZERO);
+    validate_fri_proof_shape::<F, C, D>(fri_proof, &instance, params)
+}
+
 fn validate_proof_shape<F, C, const D: usize>(
     proof: &Proof<F, C, D>,
     common_data: &CommonCircuitData<F, D>,
@@ -1,8 +1,13 @@
+use alloc::string::{String, ToString};
+use alloc::vec;
+use alloc::vec::Vec;
+use core::fmt::{Display, Formatter};
+
 use anyhow::{ensure, Result};
 
 use crate::field::extension::Extendable;
 use crate::field::types::Field;
-use crate::fri::verifier::verify_fri_proof;
+use crate::fri::verifier::{trace_fri_proof, verify_fri_proof, FriTrace};
 use crate::hash::hash_types::RichField;
 use crate::plonk::circuit_data::{CommonCircuitData, VerifierOnlyCircuitData};
 use crate::plonk::config::{GenericConfig, Hasher};
@@ -36,17 +41,235 @@ pub(crate) fn verify<F: RichField + Extendable<D>, C: GenericConfig<D, F = F>, c
     )
 }
 
-pub(crate) fn verify_with_challenges<
+/// Verifies a proof produced by `prove_with_fixed_query_indices`, checking the FRI query rounds
+/// against `fixed_query_indices` rather than indices derived via Fiat-Shamir.
+///
+/// WARNING: This is NOT a sound verifier; see `prove_with_fixed_query_indices`.
+#[cfg(any(feature = "fixed_fri_queries", test))]
+pub(crate) fn verify_with_fixed_query_indices<
     F: RichField + Extendable<D>,
     C: GenericConfig<D, F = F>,
     const D: usize,
 >(
-    proof: Proof<F, C, D>,
-    public_inputs_hash: <<C as GenericConfig<D>>::InnerHasher as Hasher<F>>::Hash,
-    challenges: ProofChallenges<F, D>,
+    proof_with_pis: ProofWithPublicInputs<F, C, D>,
+    fixed_query_indices: &[usize],
     verifier_data: &VerifierOnlyCircuitData<C, D>,
     common_data: &CommonCircuitData<F, D>,
 ) -> Result<()> {
+    validate_proof_with_pis_shape(&proof_with_pis, common_data)?;
+
+    let public_inputs_hash = proof_with_pis.get_public_inputs_hash();
+
+    let mut challenges = proof_with_pis.get_challenges(
+        public_inputs_hash,
+        &verifier_data.circuit_digest,
+        common_data,
+    )?;
+    ensure!(
+        fixed_query_indices.len() == challenges.fri_challenges.fri_query_indices.len(),
+        "Expected one query index per FRI query round."
+    );
+    let lde_size = common_data.lde_size();
+    ensure!(
+        fixed_query_indices
+            .iter()
+            .all(|&x_index| x_index < lde_size),
+        "Query index out of range of the LDE domain."
+    );
+    challenges.fri_challenges.fri_query_indices = fixed_query_indices.to_vec();
+
+    verify_with_challenges(
+        proof_with_pis.proof,
+        public_inputs_hash,
+        challenges,
+        verifier_data,
+        common_data,
+    )
+}
+
+/// Verifies a proof as `verify` does, but records the intermediate values of each check instead of
+/// stopping at the first failing one, e.g. to compare against another verifier implementation.
+/// Only a malformed proof results in an error; whether the proof is valid is reported by
+/// `VerificationTrace::passed`.
+pub(crate) fn verify_with_trace<
+    F: RichField + Extendable<D>,
+    C: GenericConfig<D, F = F>,
+    const D: usize,
+>(
+    proof_with_pis: ProofWithPublicInputs<F, C, D>,
+    verifier_data: &VerifierOnlyCircuitData<C, D>,
+    common_data: &CommonCircuitData<F, D>,
+) -> Result<VerificationTrace<F, D>> {
+    validate_proof_with_pis_shape(&proof_with_pis, common_data)?;
+
+    let public_inputs_hash = proof_with_pis.get_public_inputs_hash();
+    let challenges = proof_with_pis.get_challenges(
+        public_inputs_hash,
+        &verifier_data.circuit_digest,
+        common_data,
+    )?;
+    let proof = proof_with_pis.proof;
+
+    let constraint_checks =
+        eval_constraint_checks::<F, C, D>(&proof, public_inputs_hash, &challenges, common_data);
+
+    let merkle_caps = &[
+        verifier_data.constants_sigmas_cap.clone(),
+        proof.wires_cap,
+        proof.plonk_zs_partial_products_cap,
+        proof.quotient_polys_cap,
+    ];
+    let fri = trace_fri_proof::<F, C, D>(
+        &common_data.get_fri_instance(challenges.plonk_zeta),
+        &proof.openings.to_fri_openings(),
+        &challenges.fri_challenges,
+        merkle_caps,
+        &proof.opening_proof,
+        &common_data.fri_params,
+    )?;
+
+    Ok(VerificationTrace {
+        plonk_zeta: challenges.plonk_zeta,
+        constraint_checks,
+        fri,
+    })
+}
+
+/// The intermediate values of a proof's verification, as recorded by `verify_with_trace`.
+#[derive(Clone, Debug)]
+pub struct VerificationTrace<F: RichField + Extendable<D>, const D: usize> {
+    /// The challenge point at which constraints are checked.
+    pub plonk_zeta: F::Extension,
+    /// The check of the vanishing polynomial at `zeta`, for each challenge.
+    pub constraint_checks: Vec<ConstraintCheckTrace<F, D>>,
+    pub fri: FriTrace<F, D>,
+}
+
+impl<F: RichField + Extendable<D>, const D: usize> VerificationTrace<F, D> {
+    /// Whether every recorded check passed, i.e. whether the proof is valid.
+    pub fn passed(&self) -> bool {
+        self.constraint_checks.iter().all(|check| check.passed()) && self.fri.passed()
+    }
+
+    /// Lists every check which failed, in the order the verifier performs them.
+    pub fn failures(&self) -> Vec<VerificationFailure> {
+        let mut failures = Vec::new();
+        for (challenge, check) in self.constraint_checks.iter().enumerate() {
+            if !check.passed() {
+                failures.push(VerificationFailure::Constraint { challenge });
+            }
+        }
+        if !self.fri.proof_of_work_valid {
+            failures.push(VerificationFailure::ProofOfWork);
+        }
+        for (round, round_trace) in self.fri.query_rounds.iter().enumerate() {
+            if !round_trace.initial_merkle_proofs_valid {
+                failures.push(VerificationFailure::InitialMerkleProof { round });
+            }
+            for (step, step_trace) in round_trace.steps.iter().enumerate() {
+                if !step_trace.merkle_proof_valid {
+                    failures.push(VerificationFailure::FoldingStepMerkleProof { round, step });
+                }
+                if step_trace.expected_eval != step_trace.committed_eval {
+                    failures.push(VerificationFailure::FoldingStepEvaluation { round, step });
+                }
+            }
+            if round_trace.folded_eval != round_trace.final_poly_eval {
+                failures.push(VerificationFailure::FinalPolynomial { round });
+            }
+        }
+        failures
+    }
+}
+
+/// A failed check, as reported by `verify_all`.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum VerificationFailure {
+    /// The proof is malformed, e.g. has the wrong shape, so no checks could be performed.
+    Malformed(String),
+    /// The identity `vanishing(zeta) = Z_H(zeta) quotient(zeta)` fails for this challenge.
+    Constraint { challenge: usize },
+    /// The FRI proof-of-work witness doesn't have enough leading zeros.
+    ProofOfWork,
+    /// A Merkle proof of the initial openings fails in this FRI query round.
+    InitialMerkleProof { round: usize },
+    /// The Merkle proof of the opened coset fails in this folding step.
+    FoldingStepMerkleProof { round: usize, step: usize },
+    /// The opened coset disagrees with the evaluation derived in the previous step.
+    FoldingStepEvaluation { round: usize, step: usize },
+    /// The last folded evaluation disagrees with the final polynomial.
+    FinalPolynomial { round: usize },
+}
+
+impl Display for VerificationFailure {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        match self {
+            Self::Malformed(reason) => write!(f, "Malformed proof: {}", reason),
+            Self::Constraint { challenge } => {
+                write!(f, "Constraint check failed for challenge {}", challenge)
+            }
+            Self::ProofOfWork => write!(f, "Invalid proof of work"),
+            Self::InitialMerkleProof { round } => {
+                write!(f, "Invalid initial Merkle proof in query round {}", round)
+            }
+            Self::FoldingStepMerkleProof { round, step } => write!(
+                f,
+                "Invalid Merkle proof in query round {}, folding step {}",
+                round, step
+            ),
+            Self::FoldingStepEvaluation { round, step } => write!(
+                f,
+                "Inconsistent evaluation in query round {}, folding step {}",
+                round, step
+            ),
+            Self::FinalPolynomial { round } => {
+                write!(f, "Final polynomial mismatch in query round {}", round)
+            }
+        }
+    }
+}
+
+/// Verifies a proof, reporting every failing check rather than stopping at the first one. The
+/// proof is valid if and only if the result is empty.
+pub(crate) fn verify_all<
+    F: RichField + Extendable<D>,
+    C: GenericConfig<D, F = F>,
+    const D: usize,
+>(
+    proof_with_pis: ProofWithPublicInputs<F, C, D>,
+    verifier_data: &VerifierOnlyCircuitData<C, D>,
+    common_data: &CommonCircuitData<F, D>,
+) -> Vec<VerificationFailure> {
+    match verify_with_trace(proof_with_pis, verifier_data, common_data) {
+        Ok(trace) => trace.failures(),
+        Err(e) => vec![VerificationFailure::Malformed(e.to_string())],
+    }
+}
+
+/// A check of the identity `vanishing(zeta) = Z_H(zeta) quotient(zeta)` for one challenge.
+#[derive(Clone, Debug)]
+pub struct ConstraintCheckTrace<F: RichField + Extendable<D>, const D: usize> {
+    pub vanishing_zeta: F::Extension,
+    pub z_h_quotient_zeta: F::Extension,
+}
+
+impl<F: RichField + Extendable<D>, const D: usize> ConstraintCheckTrace<F, D> {
+    pub fn passed(&self) -> bool {
+        self.vanishing_zeta == self.z_h_quotient_zeta
+    }
+}
+
+/// Evaluates both sides of each polynomial identity `vanishing(x) = Z_H(x) quotient(x)` at zeta.
+fn eval_constraint_checks<
+    F: RichField + Extendable<D>,
+    C: GenericConfig<D, F = F>,
+    const D: usize,
+>(
+    proof: &Proof<F, C, D>,
+    public_inputs_hash: <<C as GenericConfig<D>>::InnerHasher as Hasher<F>>::Hash,
+    challenges: &ProofChallenges<F, D>,
+    common_data: &CommonCircuitData<F, D>,
+) -> Vec<ConstraintCheckTrace<F, D>> {
     let local_constants = &proof.openings.constants;
     let local_wires = &proof.openings.wires;
     let vars = EvaluationVars {
@@ -73,7 +296,6 @@ pub(crate) fn verify_with_challenges<
         &challenges.plonk_alphas,
     );
 
-    // Check each polynomial identity, of the form `vanishing(x) = Z_H(x) quotient(x)`, at zeta.
     let quotient_polys_zeta = &proof.openings.quotient_polys;
     let zeta_pow_deg = challenges
         .plonk_zeta
@@ -84,11 +306,32 @@ pub(crate) fn verify_with_challenges<
     // where the "real" quotient polynomial is `t(X) = t_0(X) + t_1(X)*X^n + t_2(X)*X^{2n} + ...`.
     // So to reconstruct `t(zeta)` we can compute `reduce_with_powers(chunk, zeta^n)` for each
     // `quotient_degree_factor`-sized chunk of the original evaluations.
-    for (i, chunk) in quotient_polys_zeta
+    quotient_polys_zeta
         .chunks(common_data.quotient_degree_factor)
-        .enumerate()
+        .zip(vanishing_polys_zeta)
+        .map(|(chunk, vanishing_zeta)| ConstraintCheckTrace {
+            vanishing_zeta,
+            z_h_quotient_zeta: z_h_zeta * reduce_with_powers(chunk, zeta_pow_deg),
+        })
+        .collect()
+}
+
+pub(crate) fn verify_with_challenges<
+    F: RichField + Extendable<D>,
+    C: GenericConfig<D, F = F>,
+    const D: usize,
+>(
+    proof: Proof<F, C, D>,
+    public_inputs_hash: <<C as GenericConfig<D>>::InnerHasher as Hasher<F>>::Hash,
+    challenges: ProofChallenges<F, D>,
+    verifier_data: &VerifierOnlyCircuitData<C, D>,
+    common_data: &CommonCircuitData<F, D>,
+) -> Result<()> {
+    // Check each polynomial identity, of the form `vanishing(x) = Z_H(x) quotient(x)`, at zeta.
+    for check in
+        eval_constraint_checks::<F, C, D>(&proof, public_inputs_hash, &challenges, common_data)
     {
-        ensure!(vanishing_polys_zeta[i] == z_h_zeta * reduce_with_powers(chunk, zeta_pow_deg));
+        ensure!(check.passed());
     }
 
     let merkle_caps = &[
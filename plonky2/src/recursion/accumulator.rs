@@ -0,0 +1,191 @@
+use alloc::vec::Vec;
+
+use anyhow::{ensure, Result};
+
+use crate::field::extension::Extendable;
+use crate::hash::hash_types::RichField;
+use crate::iop::witness::{PartialWitness, WitnessWrite};
+use crate::plonk::circuit_builder::CircuitBuilder;
+use crate::plonk::circuit_data::{CommonCircuitData, VerifierCircuitData, VerifierOnlyCircuitData};
+use crate::plonk::config::{AlgebraicHasher, GenericConfig};
+use crate::plonk::proof::ProofWithPublicInputs;
+use crate::plonk::verifier::verify;
+
+/// Aggregates proofs of a single inner circuit, one at a time, into one proof.
+///
+/// Each call to `fold` verifies the new inner proof, along with the current aggregate proof if
+/// there is one, in a fresh recursive circuit. The public inputs of an aggregate proof are a running
+/// hash of the public inputs of all folded proofs: `h_1 = H(pis_1)`, `h_k = H(h_{k-1} || pis_k)`.
+pub struct Accumulator<F: RichField + Extendable<D>, C: GenericConfig<D, F = F>, const D: usize> {
+    inner_verifier_only: VerifierOnlyCircuitData<C, D>,
+    inner_common: CommonCircuitData<F, D>,
+    aggregate: Option<Aggregate<F, C, D>>,
+    num_folded: usize,
+}
+
+struct Aggregate<F: RichField + Extendable<D>, C: GenericConfig<D, F = F>, const D: usize> {
+    proof: ProofWithPublicInputs<F, C, D>,
+    verifier_only: VerifierOnlyCircuitData<C, D>,
+    common: CommonCircuitData<F, D>,
+}
+
+impl<F: RichField + Extendable<D>, C: GenericConfig<D, F = F>, const D: usize> Accumulator<F, C, D>
+where
+    C::Hasher: AlgebraicHasher<F>,
+{
+    /// Creates an empty accumulator for proofs of the circuit described by `verifier_data`.
+    pub fn new(verifier_data: VerifierCircuitData<F, C, D>) -> Self {
+        Self {
+            inner_verifier_only: verifier_data.verifier_only,
+            inner_common: verifier_data.common,
+            aggregate: None,
+            num_folded: 0,
+        }
+    }
+
+    /// The number of proofs folded so far.
+    pub fn num_folded(&self) -> usize {
+        self.num_folded
+    }
+
+    /// Verifies `proof` and folds it into the aggregate proof.
+    pub fn fold(&mut self, proof: ProofWithPublicInputs<F, C, D>) -> Result<()> {
+        verify(proof.clone(), &self.inner_verifier_only, &self.inner_common)?;
+
+        let mut builder = CircuitBuilder::<F, D>::new(self.inner_common.config.clone());
+        let mut pw = PartialWitness::new();
+
+        let inner_pt = builder.add_virtual_proof_with_pis::<C>(&self.inner_common);
+        pw.set_proof_with_pis_target(&inner_pt, &proof);
+        let inner_data = builder.constant_verifier_data(&self.inner_verifier_only);
+        builder.verify_proof::<C>(&inner_pt, &inner_data, &self.inner_common);
+
+        let mut hash_inputs = Vec::new();
+        if let Some(aggregate) = &self.aggregate {
+            let aggregate_pt = builder.add_virtual_proof_with_pis::<C>(&aggregate.common);
+            pw.set_proof_with_pis_target(&aggregate_pt, &aggregate.proof);
+            let aggregate_data = builder.constant_verifier_data(&aggregate.verifier_only);
+            builder.verify_proof::<C>(&aggregate_pt, &aggregate_data, &aggregate.common);
+            hash_inputs.extend(aggregate_pt.public_inputs);
+        }
+        hash_inputs.extend(inner_pt.public_inputs);
+        let digest = builder.hash_n_to_hash_no_pad::<C::Hasher>(hash_inputs);
+        builder.register_public_inputs(&digest.elements);
+
+        let data = builder.build::<C>();
+        let aggregate_proof = data.prove(pw)?;
+        self.aggregate = Some(Aggregate {
+            proof: aggregate_proof,
+            verifier_only: data.verifier_only,
+            common: data.common,
+        });
+        self.num_folded += 1;
+
+        Ok(())
+    }
+
+    /// The verifier data of the current aggregate proof, or `None` if nothing was folded yet.
+    pub fn aggregate_verifier_data(&self) -> Option<VerifierCircuitData<F, C, D>> {
+        self.aggregate
+            .as_ref()
+            .map(|aggregate| VerifierCircuitData {
+                verifier_only: aggregate.verifier_only.clone(),
+                common: aggregate.common.clone(),
+            })
+    }
+
+    /// Returns the proof attesting to all folded proofs. Panics if nothing was folded.
+    pub fn finalize(self) -> ProofWithPublicInputs<F, C, D> {
+        self.aggregate
+            .expect("At least one proof must be folded before finalizing.")
+            .proof
+    }
+
+    /// Like `finalize`, but errors instead of panicking if nothing was folded.
+    pub fn try_finalize(self) -> Result<ProofWithPublicInputs<F, C, D>> {
+        ensure!(
+            self.aggregate.is_some(),
+            "At least one proof must be folded before finalizing."
+        );
+        Ok(self.finalize())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use alloc::vec::Vec;
+
+    use anyhow::Result;
+
+    use crate::field::types::Field;
+    use crate::iop::witness::{PartialWitness, WitnessWrite};
+    use crate::plonk::circuit_builder::CircuitBuilder;
+    use crate::plonk::circuit_data::{CircuitConfig, CircuitData};
+    use crate::plonk::config::{GenericConfig, GenericHashOut, Hasher, PoseidonGoldilocksConfig};
+    use crate::recursion::accumulator::Accumulator;
+
+    const D: usize = 2;
+    type C = PoseidonGoldilocksConfig;
+    type F = <C as GenericConfig<D>>::F;
+
+    /// A circuit proving that the 100th Fibonacci number starting from `(x0, x1)` is some `y`, with
+    /// public inputs `x0, x1, y`.
+    fn fibonacci_circuit() -> CircuitData<F, C, D> {
+        let config = CircuitConfig::standard_recursion_config();
+        let mut builder = CircuitBuilder::<F, D>::new(config);
+        let initial_a = builder.add_virtual_target();
+        let initial_b = builder.add_virtual_target();
+        let cur_target = builder.fibonacci_100(initial_a, initial_b);
+        builder.register_public_input(initial_a);
+        builder.register_public_input(initial_b);
+        builder.register_public_input(cur_target);
+        builder.build::<C>()
+    }
+
+    fn fibonacci_witness(data: &CircuitData<F, C, D>, x0: u64, x1: u64) -> PartialWitness<F> {
+        let mut pw = PartialWitness::new();
+        pw.set_target(data.prover_only.public_inputs[0], F::from_canonical_u64(x0));
+        pw.set_target(data.prover_only.public_inputs[1], F::from_canonical_u64(x1));
+        pw
+    }
+
+    #[test]
+    fn test_fold_fibonacci_proofs() -> Result<()> {
+        let data = fibonacci_circuit();
+        let proofs = [(0, 1), (1, 1), (2, 3)]
+            .into_iter()
+            .map(|(x0, x1)| data.prove(fibonacci_witness(&data, x0, x1)))
+            .collect::<Result<Vec<_>>>()?;
+
+        let mut expected_digest = Vec::new();
+        for proof in &proofs {
+            let inputs = [expected_digest.clone(), proof.public_inputs.clone()].concat();
+            expected_digest = <C as GenericConfig<D>>::Hasher::hash_no_pad(&inputs).to_vec();
+        }
+
+        let mut accumulator = Accumulator::new(data.verifier_data());
+        for proof in proofs {
+            accumulator.fold(proof)?;
+        }
+        assert_eq!(accumulator.num_folded(), 3);
+
+        let aggregate_data = accumulator.aggregate_verifier_data().unwrap();
+        let aggregate_proof = accumulator.finalize();
+        assert_eq!(aggregate_proof.public_inputs, expected_digest);
+        aggregate_data.verify(aggregate_proof)
+    }
+
+    #[test]
+    fn test_fold_invalid_proof() -> Result<()> {
+        let data = fibonacci_circuit();
+        let mut proof = data.prove(fibonacci_witness(&data, 0, 1))?;
+        proof.public_inputs[2] += F::ONE;
+
+        let mut accumulator = Accumulator::new(data.verifier_data());
+        assert!(accumulator.fold(proof).is_err());
+        assert_eq!(accumulator.num_folded(), 0);
+        assert!(accumulator.try_finalize().is_err());
+
+        Ok(())
+    }
+}
@@ -0,0 +1,163 @@
+use alloc::vec::Vec;
+
+use anyhow::{ensure, Result};
+
+use crate::field::extension::Extendable;
+use crate::hash::hash_types::RichField;
+use crate::iop::witness::{PartialWitness, WitnessWrite};
+use crate::plonk::circuit_builder::CircuitBuilder;
+use crate::plonk::circuit_data::{
+    CircuitData, CommonCircuitData, VerifierCircuitTarget, VerifierOnlyCircuitData,
+};
+use crate::plonk::config::{AlgebraicHasher, GenericConfig};
+use crate::plonk::proof::{ProofWithPublicInputs, ProofWithPublicInputsTarget};
+
+/// A circuit verifying a fixed number of proofs of one inner circuit, built by
+/// [`build_batch_verifier`].
+///
+/// The public inputs of a batch proof are the inner verifier data, i.e. the elements of the
+/// constants and sigmas cap followed by the circuit digest, then the public inputs of each inner
+/// proof in order.
+pub struct BatchVerifierCircuit<
+    F: RichField + Extendable<D>,
+    C: GenericConfig<D, F = F>,
+    const D: usize,
+> {
+    pub data: CircuitData<F, C, D>,
+    pub inner_verifier_data: VerifierCircuitTarget,
+    pub inner_proofs: Vec<ProofWithPublicInputsTarget<D>>,
+}
+
+/// Builds a circuit verifying `n` proofs of the inner circuit described by `common`, against
+/// verifier data supplied by the prover and exposed as public inputs.
+///
+/// The verifier data is shared by all inner proofs, but each proof's vanishing polynomial and FRI
+/// checks are still performed in full: independent proofs commit to separate oracles, so their FRI
+/// arguments cannot be merged into a single random linear combination without the inner provers
+/// cooperating.
+pub fn build_batch_verifier<
+    F: RichField + Extendable<D>,
+    C: GenericConfig<D, F = F>,
+    const D: usize,
+>(
+    common: &CommonCircuitData<F, D>,
+    n: usize,
+) -> BatchVerifierCircuit<F, C, D>
+where
+    C::Hasher: AlgebraicHasher<F>,
+{
+    let mut builder = CircuitBuilder::<F, D>::new(common.config.clone());
+    let inner_verifier_data = VerifierCircuitTarget {
+        constants_sigmas_cap: builder.add_virtual_cap(common.config.fri_config.cap_height),
+        circuit_digest: builder.add_virtual_hash(),
+    };
+    for hash in &inner_verifier_data.constants_sigmas_cap.0 {
+        builder.register_public_inputs(&hash.elements);
+    }
+    builder.register_public_inputs(&inner_verifier_data.circuit_digest.elements);
+
+    let inner_proofs = (0..n)
+        .map(|_| {
+            let proof = builder.add_virtual_proof_with_pis::<C>(common);
+            builder.register_public_inputs(&proof.public_inputs);
+            builder.verify_proof::<C>(&proof, &inner_verifier_data, common);
+            proof
+        })
+        .collect();
+
+    BatchVerifierCircuit {
+        data: builder.build::<C>(),
+        inner_verifier_data,
+        inner_proofs,
+    }
+}
+
+impl<F: RichField + Extendable<D>, C: GenericConfig<D, F = F>, const D: usize>
+    BatchVerifierCircuit<F, C, D>
+where
+    C::Hasher: AlgebraicHasher<F>,
+{
+    /// The number of inner proofs verified by the circuit.
+    pub fn num_inner_proofs(&self) -> usize {
+        self.inner_proofs.len()
+    }
+
+    /// Proves that every proof in `proofs` verifies against `inner_verifier_data`.
+    pub fn prove(
+        &self,
+        inner_verifier_data: &VerifierOnlyCircuitData<C, D>,
+        proofs: &[ProofWithPublicInputs<F, C, D>],
+    ) -> Result<ProofWithPublicInputs<F, C, D>> {
+        ensure!(
+            proofs.len() == self.num_inner_proofs(),
+            "Expected {} proofs, got {}",
+            self.num_inner_proofs(),
+            proofs.len()
+        );
+        let mut pw = PartialWitness::new();
+        pw.set_verifier_data_target(&self.inner_verifier_data, inner_verifier_data);
+        for (target, proof) in self.inner_proofs.iter().zip(proofs) {
+            pw.set_proof_with_pis_target(target, proof);
+        }
+        self.data.prove(pw)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use alloc::vec::Vec;
+
+    use anyhow::Result;
+
+    use crate::field::types::Field;
+    use crate::hash::hash_types::NUM_HASH_OUT_ELTS;
+    use crate::iop::witness::{PartialWitness, WitnessWrite};
+    use crate::plonk::circuit_builder::CircuitBuilder;
+    use crate::plonk::circuit_data::CircuitConfig;
+    use crate::plonk::config::{GenericConfig, PoseidonGoldilocksConfig};
+    use crate::recursion::batch_verifier::build_batch_verifier;
+
+    #[test]
+    fn test_batch_verifier() -> Result<()> {
+        const D: usize = 2;
+        type C = PoseidonGoldilocksConfig;
+        type F = <C as GenericConfig<D>>::F;
+
+        // Proves the 100th element of the Fibonacci-like sequence with the given first elements.
+        let config = CircuitConfig::standard_recursion_config();
+        let mut builder = CircuitBuilder::<F, D>::new(config);
+        let initial_a = builder.add_virtual_public_input();
+        let initial_b = builder.add_virtual_public_input();
+        let cur_target = builder.fibonacci_100(initial_a, initial_b);
+        builder.register_public_input(cur_target);
+        let data = builder.build::<C>();
+
+        let mut proofs = Vec::new();
+        for i in 0..2 {
+            let mut pw = PartialWitness::new();
+            pw.set_target(initial_a, F::from_canonical_u64(i));
+            pw.set_target(initial_b, F::from_canonical_u64(i + 1));
+            proofs.push(data.prove(pw)?);
+        }
+
+        let batch = build_batch_verifier::<F, C, D>(&data.common, 2);
+        assert!(batch.prove(&data.verifier_only, &proofs[..1]).is_err());
+        let batch_proof = batch.prove(&data.verifier_only, &proofs)?;
+
+        let digest_start = NUM_HASH_OUT_ELTS * data.common.config.fri_config.num_cap_elements();
+        let digest_end = digest_start + NUM_HASH_OUT_ELTS;
+        let inner_pis = &batch_proof.public_inputs[digest_end..];
+        let expected_pis = proofs
+            .iter()
+            .flat_map(|proof| proof.public_inputs.clone())
+            .collect::<Vec<_>>();
+        assert_eq!(inner_pis, expected_pis.as_slice());
+        let digest = &batch_proof.public_inputs[digest_start..digest_end];
+        assert_eq!(
+            digest,
+            data.verifier_only.circuit_digest.elements.as_slice()
+        );
+
+        batch.data.verify(batch_proof)
+    }
+}
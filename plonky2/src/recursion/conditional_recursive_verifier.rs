@@ -11,12 +11,82 @@ use crate::hash::hash_types::{HashOutTarget, MerkleCapTarget, RichField};
 use crate::hash::merkle_proofs::MerkleProofTarget;
 use crate::iop::ext_target::ExtensionTarget;
 use crate::iop::target::{BoolTarget, Target};
+use crate::iop::witness::{PartialWitness, WitnessWrite};
 use crate::plonk::circuit_builder::CircuitBuilder;
-use crate::plonk::circuit_data::{CommonCircuitData, VerifierCircuitTarget};
+use crate::plonk::circuit_data::{
+    CircuitConfig, CircuitData, CommonCircuitData, VerifierCircuitTarget, VerifierOnlyCircuitData,
+};
 use crate::plonk::config::{AlgebraicHasher, GenericConfig};
-use crate::plonk::proof::{OpeningSetTarget, ProofTarget, ProofWithPublicInputsTarget};
+use crate::plonk::proof::{
+    OpeningSetTarget, ProofTarget, ProofWithPublicInputs, ProofWithPublicInputsTarget,
+};
 use crate::with_context;
 
+/// A circuit verifying a proof under either of two inner circuits, as built by
+/// `build_or_verifier`. Its public inputs are those of the inner proof.
+pub struct OrVerifierCircuit<
+    F: RichField + Extendable<D>,
+    C: GenericConfig<D, F = F>,
+    const D: usize,
+> {
+    pub data: CircuitData<F, C, D>,
+    /// Whether the inner proof is verified under the first circuit, rather than the second.
+    pub selector: BoolTarget,
+    pub inner_proof: ProofWithPublicInputsTarget<D>,
+}
+
+impl<F: RichField + Extendable<D>, C: GenericConfig<D, F = F>, const D: usize>
+    OrVerifierCircuit<F, C, D>
+{
+    /// Proves that `inner_proof` is valid under the first inner circuit if `use_first`, or under
+    /// the second otherwise.
+    pub fn prove(
+        &self,
+        use_first: bool,
+        inner_proof: &ProofWithPublicInputs<F, C, D>,
+    ) -> anyhow::Result<ProofWithPublicInputs<F, C, D>>
+    where
+        C::Hasher: AlgebraicHasher<F>,
+    {
+        let mut pw = PartialWitness::new();
+        pw.set_bool_target(self.selector, use_first);
+        pw.set_proof_with_pis_target(&self.inner_proof, inner_proof);
+        self.data.prove(pw)
+    }
+}
+
+/// Builds a circuit which verifies that a proof is valid under the circuit described by
+/// `verifier_data0` or the one described by `verifier_data1`. As with
+/// `conditionally_verify_proof`, both inner circuits must share `inner_common_data`.
+pub fn build_or_verifier<F, C, const D: usize>(
+    config: CircuitConfig,
+    verifier_data0: &VerifierOnlyCircuitData<C, D>,
+    verifier_data1: &VerifierOnlyCircuitData<C, D>,
+    inner_common_data: &CommonCircuitData<F, D>,
+) -> OrVerifierCircuit<F, C, D>
+where
+    F: RichField + Extendable<D>,
+    C: GenericConfig<D, F = F>,
+    C::Hasher: AlgebraicHasher<F>,
+{
+    let mut builder = CircuitBuilder::<F, D>::new(config);
+    let selector = builder.add_virtual_bool_target_safe();
+    let inner_proof = builder.add_virtual_proof_with_pis::<C>(inner_common_data);
+    builder.register_public_inputs(&inner_proof.public_inputs);
+
+    let verifier_data0 = builder.constant_verifier_data(verifier_data0);
+    let verifier_data1 = builder.constant_verifier_data(verifier_data1);
+    let selected_verifier_data =
+        builder.select_verifier_data(selector, &verifier_data0, &verifier_data1);
+    builder.verify_proof::<C>(&inner_proof, &selected_verifier_data, inner_common_data);
+
+    OrVerifierCircuit {
+        data: builder.build::<C>(),
+        selector,
+        inner_proof,
+    }
+}
+
 impl<F: RichField + Extendable<D>, const D: usize> CircuitBuilder<F, D> {
     /// Verify `proof0` if `condition` else verify `proof1`.
     /// `proof0` and `proof1` are assumed to use the same `CommonCircuitData`.
@@ -33,18 +103,8 @@ impl<F: RichField + Extendable<D>, const D: usize> CircuitBuilder<F, D> {
     {
         let selected_proof =
             self.select_proof_with_pis(condition, proof_with_pis0, proof_with_pis1);
-        let selected_verifier_data = VerifierCircuitTarget {
-            constants_sigmas_cap: self.select_cap(
-                condition,
-                &inner_verifier_data0.constants_sigmas_cap,
-                &inner_verifier_data1.constants_sigmas_cap,
-            ),
-            circuit_digest: self.select_hash(
-                condition,
-                inner_verifier_data0.circuit_digest,
-                inner_verifier_data1.circuit_digest,
-            ),
-        };
+        let selected_verifier_data =
+            self.select_verifier_data(condition, inner_verifier_data0, inner_verifier_data1);
 
         self.verify_proof::<C>(&selected_proof, &selected_verifier_data, inner_common_data);
     }
@@ -73,6 +133,27 @@ impl<F: RichField + Extendable<D>, const D: usize> CircuitBuilder<F, D> {
         Ok(())
     }
 
+    /// Computes `if b { verifier_data0 } else { verifier_data1 }`.
+    fn select_verifier_data(
+        &mut self,
+        b: BoolTarget,
+        verifier_data0: &VerifierCircuitTarget,
+        verifier_data1: &VerifierCircuitTarget,
+    ) -> VerifierCircuitTarget {
+        VerifierCircuitTarget {
+            constants_sigmas_cap: self.select_cap(
+                b,
+                &verifier_data0.constants_sigmas_cap,
+                &verifier_data1.constants_sigmas_cap,
+            ),
+            circuit_digest: self.select_hash(
+                b,
+                verifier_data0.circuit_digest,
+                verifier_data1.circuit_digest,
+            ),
+        }
+    }
+
     /// Computes `if b { proof_with_pis0 } else { proof_with_pis1 }`.
     fn select_proof_with_pis(
         &mut self,
@@ -338,11 +419,10 @@ mod tests {
     use hashbrown::HashMap;
 
     use super::*;
+    use crate::field::goldilocks_field::GoldilocksField;
     use crate::field::types::Sample;
     use crate::gates::noop::NoopGate;
-    use crate::iop::witness::{PartialWitness, WitnessWrite};
-    use crate::plonk::circuit_data::CircuitConfig;
-    use crate::plonk::config::{GenericConfig, PoseidonGoldilocksConfig};
+    use crate::plonk::config::PoseidonGoldilocksConfig;
     use crate::recursion::dummy_circuit::{dummy_circuit, dummy_proof};
 
     #[test]
@@ -404,6 +484,68 @@ mod tests {
         data.verify(proof)
     }
 
+    type OrVerifierFixture = (
+        OrVerifierCircuit<GoldilocksField, PoseidonGoldilocksConfig, 2>,
+        ProofWithPublicInputs<GoldilocksField, PoseidonGoldilocksConfig, 2>,
+        ProofWithPublicInputs<GoldilocksField, PoseidonGoldilocksConfig, 2>,
+    );
+
+    /// Builds an OR verifier over a squaring circuit and a dummy circuit with the same common
+    /// data, and returns it with a proof for each.
+    fn or_verifier_fixture() -> Result<OrVerifierFixture> {
+        const D: usize = 2;
+        type C = PoseidonGoldilocksConfig;
+        type F = <C as GenericConfig<D>>::F;
+        let config = CircuitConfig::standard_recursion_config();
+
+        let mut builder = CircuitBuilder::<F, D>::new(config.clone());
+        let mut pw = PartialWitness::new();
+        let t = builder.add_virtual_target();
+        pw.set_target(t, F::rand());
+        let t2 = builder.square(t);
+        builder.register_public_input(t2);
+        for _ in 0..64 {
+            builder.add_gate(NoopGate, vec![]);
+        }
+        let data = builder.build::<C>();
+        let proof = data.prove(pw)?;
+
+        let dummy_data = dummy_circuit(&data.common);
+        let dummy_proof = dummy_proof(&dummy_data, HashMap::new())?;
+        dummy_data.verify(dummy_proof.clone())?;
+
+        let or_verifier = build_or_verifier(
+            config,
+            &data.verifier_only,
+            &dummy_data.verifier_only,
+            &data.common,
+        );
+        Ok((or_verifier, proof, dummy_proof))
+    }
+
+    #[test]
+    fn test_or_verifier() -> Result<()> {
+        init_logger();
+        let (or_verifier, proof, dummy_proof) = or_verifier_fixture()?;
+
+        let or_proof = or_verifier.prove(true, &proof)?;
+        assert_eq!(or_proof.public_inputs, proof.public_inputs);
+        or_verifier.data.verify(or_proof)?;
+
+        let or_proof = or_verifier.prove(false, &dummy_proof)?;
+        or_verifier.data.verify(or_proof)
+    }
+
+    #[test]
+    #[should_panic(expected = "was set twice with different values")]
+    fn test_or_verifier_wrong_circuit() {
+        init_logger();
+        let (or_verifier, proof, _) = or_verifier_fixture().unwrap();
+        // The proof is only valid under the first circuit, so it doesn't satisfy the second
+        // branch; the unselected first branch must not be enforced.
+        let _ = or_verifier.prove(false, &proof);
+    }
+
     fn init_logger() {
         let _ = env_logger::builder().format_timestamp(None).try_init();
     }
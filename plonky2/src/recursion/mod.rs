@@ -1,5 +1,8 @@
+pub mod accumulator;
+pub mod batch_verifier;
 pub mod conditional_recursive_verifier;
 pub mod cyclic_recursion;
 pub mod dummy_circuit;
 pub mod recursive_verifier;
+pub mod segmented_prover;
 //pub mod tree_recursion;
@@ -1,5 +1,7 @@
 use crate::field::extension::Extendable;
+use crate::gates::gate::Gate;
 use crate::hash::hash_types::{HashOutTarget, RichField};
+use crate::iop::ext_target::ExtensionTarget;
 use crate::plonk::circuit_builder::CircuitBuilder;
 use crate::plonk::circuit_data::{CommonCircuitData, VerifierCircuitTarget};
 use crate::plonk::config::{AlgebraicHasher, GenericConfig};
@@ -44,6 +46,34 @@ impl<F: RichField + Extendable<D>, const D: usize> CircuitBuilder<F, D> {
         );
     }
 
+    /// Derives the Plonk challenge point zeta of an inner proof in-circuit, from the same
+    /// Fiat-Shamir transcript as `verify_proof`, but without verifying the proof. This is meant for
+    /// debugging recursive verifiers, by comparing the result against the zeta derived natively.
+    pub fn derive_zeta<C: GenericConfig<D, F = F>>(
+        &mut self,
+        proof_with_pis: &ProofWithPublicInputsTarget<D>,
+        inner_verifier_data: &VerifierCircuitTarget,
+        inner_common_data: &CommonCircuitData<F, D>,
+    ) -> ExtensionTarget<D>
+    where
+        C::Hasher: AlgebraicHasher<F>,
+    {
+        assert_eq!(
+            proof_with_pis.public_inputs.len(),
+            inner_common_data.num_public_inputs
+        );
+        let public_inputs_hash =
+            self.hash_n_to_hash_no_pad::<C::InnerHasher>(proof_with_pis.public_inputs.clone());
+        proof_with_pis
+            .get_challenges::<F, C>(
+                self,
+                public_inputs_hash,
+                inner_verifier_data.circuit_digest,
+                inner_common_data,
+            )
+            .plonk_zeta
+    }
+
     /// Recursively verifies an inner proof.
     fn verify_proof_with_challenges<C: GenericConfig<D, F = F>>(
         &mut self,
@@ -126,6 +156,30 @@ impl<F: RichField + Extendable<D>, const D: usize> CircuitBuilder<F, D> {
         );
     }
 
+    /// Evaluates the constraints of `gate` at `vars`, without selector filtering. This is a
+    /// building block for custom recursive verifiers, e.g. ones which only check some gates.
+    pub fn eval_gate_constraints(
+        &mut self,
+        gate: &dyn Gate<F, D>,
+        vars: EvaluationTargets<D>,
+    ) -> Vec<ExtensionTarget<D>> {
+        assert!(
+            vars.local_wires.len() >= gate.num_wires(),
+            "{} reads {} wires, but only {} were given",
+            gate.id(),
+            gate.num_wires(),
+            vars.local_wires.len()
+        );
+        assert!(
+            vars.local_constants.len() >= gate.num_constants(),
+            "{} reads {} constants, but only {} were given",
+            gate.id(),
+            gate.num_constants(),
+            vars.local_constants.len()
+        );
+        gate.eval_unfiltered_circuit(self, vars)
+    }
+
     pub fn add_virtual_proof_with_pis<InnerC: GenericConfig<D, F = F>>(
         &mut self,
         common_data: &CommonCircuitData<F, D>,
@@ -188,16 +242,100 @@ mod tests {
     use log::{info, Level};
 
     use super::*;
+    use crate::field::types::Field;
     use crate::fri::reduction_strategies::FriReductionStrategy;
     use crate::fri::FriConfig;
+    use crate::gates::arithmetic_extension::ArithmeticExtensionGate;
     use crate::gates::noop::NoopGate;
-    use crate::iop::witness::{PartialWitness, WitnessWrite};
+    use crate::iop::generator::generate_partial_witness;
+    use crate::iop::witness::{PartialWitness, Witness, WitnessWrite};
     use crate::plonk::circuit_data::{CircuitConfig, VerifierOnlyCircuitData};
     use crate::plonk::config::{GenericConfig, KeccakGoldilocksConfig, PoseidonGoldilocksConfig};
     use crate::plonk::proof::{CompressedProofWithPublicInputs, ProofWithPublicInputs};
     use crate::plonk::prover::prove;
     use crate::util::timing::TimingTree;
 
+    #[test]
+    fn test_eval_gate_constraints() {
+        const D: usize = 2;
+        type C = PoseidonGoldilocksConfig;
+        type F = <C as GenericConfig<D>>::F;
+        let config = CircuitConfig::standard_recursion_config();
+        let gate: &dyn Gate<F, D> = &ArithmeticExtensionGate::new_from_config(&config);
+        let mut builder = CircuitBuilder::<F, D>::new(config);
+
+        let local_constants = builder.add_virtual_extension_targets(gate.num_constants());
+        let local_wires = builder.add_virtual_extension_targets(gate.num_wires());
+        let public_inputs_hash = builder.add_virtual_hash();
+        let vars = EvaluationTargets {
+            local_constants: &local_constants,
+            local_wires: &local_wires,
+            public_inputs_hash: &public_inputs_hash,
+        };
+        let constraints = builder.eval_gate_constraints(gate, vars);
+        assert_eq!(constraints.len(), gate.num_constraints());
+    }
+
+    #[test]
+    #[should_panic(expected = "wires, but only")]
+    fn test_eval_gate_constraints_too_few_wires() {
+        const D: usize = 2;
+        type C = PoseidonGoldilocksConfig;
+        type F = <C as GenericConfig<D>>::F;
+        let config = CircuitConfig::standard_recursion_config();
+        let gate: &dyn Gate<F, D> = &ArithmeticExtensionGate::new_from_config(&config);
+        let mut builder = CircuitBuilder::<F, D>::new(config);
+
+        let local_constants = builder.add_virtual_extension_targets(gate.num_constants());
+        let local_wires = builder.add_virtual_extension_targets(gate.num_wires() - 1);
+        let public_inputs_hash = builder.add_virtual_hash();
+        let vars = EvaluationTargets {
+            local_constants: &local_constants,
+            local_wires: &local_wires,
+            public_inputs_hash: &public_inputs_hash,
+        };
+        builder.eval_gate_constraints(gate, vars);
+    }
+
+    #[test]
+    fn test_derive_zeta() -> Result<()> {
+        const D: usize = 2;
+        type C = PoseidonGoldilocksConfig;
+        type F = <C as GenericConfig<D>>::F;
+        let config = CircuitConfig::standard_recursion_config();
+
+        // A proof of the 100th Fibonacci number.
+        let mut builder = CircuitBuilder::<F, D>::new(config.clone());
+        let initial_a = builder.add_virtual_public_input();
+        let initial_b = builder.add_virtual_public_input();
+        let cur_target = builder.fibonacci_100(initial_a, initial_b);
+        builder.register_public_input(cur_target);
+        let inner_data = builder.build::<C>();
+        let mut pw = PartialWitness::new();
+        pw.set_target(initial_a, F::ZERO);
+        pw.set_target(initial_b, F::ONE);
+        let inner_proof = inner_data.prove(pw)?;
+        let expected_zeta = inner_proof
+            .get_challenges(
+                inner_proof.get_public_inputs_hash(),
+                &inner_data.verifier_only.circuit_digest,
+                &inner_data.common,
+            )?
+            .plonk_zeta;
+
+        let mut builder = CircuitBuilder::<F, D>::new(config);
+        let proof_t = builder.add_virtual_proof_with_pis::<C>(&inner_data.common);
+        let verifier_data_t = builder.constant_verifier_data(&inner_data.verifier_only);
+        let zeta = builder.derive_zeta::<C>(&proof_t, &verifier_data_t, &inner_data.common);
+        let data = builder.build::<C>();
+
+        let mut pw = PartialWitness::new();
+        pw.set_proof_with_pis_target(&proof_t, &inner_proof);
+        let witness = generate_partial_witness(pw, &data.prover_only, &data.common);
+        assert_eq!(witness.get_extension_target(zeta), expected_zeta);
+        Ok(())
+    }
+
     #[test]
     fn test_recursive_verifier() -> Result<()> {
         init_logger();
@@ -0,0 +1,207 @@
+//! Proving a long computation in segments, which are combined by recursion.
+//!
+//! The permutation argument and the polynomial commitments of a circuit span its whole trace, so a
+//! range of rows cannot be proven on its own. Instead, the computation is split into segments which
+//! are each proven with the same segment circuit. The public inputs of the segment circuit are its
+//! boundaries: the state it starts from, followed by the state it ends at. A combining circuit
+//! then verifies every segment proof and links the end of each segment to the start of the next.
+
+use alloc::vec::Vec;
+
+use anyhow::{ensure, Result};
+
+use crate::field::extension::Extendable;
+use crate::hash::hash_types::RichField;
+use crate::iop::witness::{PartialWitness, WitnessWrite};
+use crate::plonk::circuit_builder::CircuitBuilder;
+use crate::plonk::circuit_data::CircuitData;
+use crate::plonk::config::{AlgebraicHasher, GenericConfig};
+use crate::plonk::proof::{ProofWithPublicInputs, ProofWithPublicInputsTarget};
+
+/// Proves a computation split into a fixed number of segments, and combines the segment proofs
+/// into one proof.
+///
+/// The public inputs of the combined proof are the boundary the first segment starts from,
+/// followed by the boundary the last segment ends at.
+pub struct SegmentedProver<F: RichField + Extendable<D>, C: GenericConfig<D, F = F>, const D: usize>
+{
+    segment: CircuitData<F, C, D>,
+    boundary_len: usize,
+    combined: CircuitData<F, C, D>,
+    segment_proofs: Vec<ProofWithPublicInputsTarget<D>>,
+}
+
+impl<F: RichField + Extendable<D>, C: GenericConfig<D, F = F>, const D: usize>
+    SegmentedProver<F, C, D>
+where
+    C::Hasher: AlgebraicHasher<F>,
+{
+    /// Builds the circuit combining `num_segments` proofs of `segment`, whose public inputs must be
+    /// an input boundary and an output boundary of `boundary_len` elements each.
+    pub fn new(segment: CircuitData<F, C, D>, boundary_len: usize, num_segments: usize) -> Self {
+        assert_eq!(
+            segment.common.num_public_inputs,
+            2 * boundary_len,
+            "The public inputs of a segment must be its input and output boundaries"
+        );
+        assert!(num_segments > 0, "There must be at least one segment");
+
+        let common = &segment.common;
+        let mut builder = CircuitBuilder::<F, D>::new(common.config.clone());
+        let verifier_data = builder.constant_verifier_data(&segment.verifier_only);
+        let segment_proofs = (0..num_segments)
+            .map(|_| {
+                let proof = builder.add_virtual_proof_with_pis::<C>(common);
+                builder.verify_proof::<C>(&proof, &verifier_data, common);
+                proof
+            })
+            .collect::<Vec<_>>();
+        for pair in segment_proofs.windows(2) {
+            let outputs = &pair[0].public_inputs[boundary_len..];
+            let inputs = &pair[1].public_inputs[..boundary_len];
+            for (&output, &input) in outputs.iter().zip(inputs) {
+                builder.connect(output, input);
+            }
+        }
+        builder.register_public_inputs(&segment_proofs[0].public_inputs[..boundary_len]);
+        builder.register_public_inputs(
+            &segment_proofs[num_segments - 1].public_inputs[boundary_len..],
+        );
+
+        Self {
+            combined: builder.build::<C>(),
+            segment,
+            boundary_len,
+            segment_proofs,
+        }
+    }
+
+    /// The number of segments combined into one proof.
+    pub fn num_segments(&self) -> usize {
+        self.segment_proofs.len()
+    }
+
+    /// Proves one segment, whose witness is given by `inputs`. The boundaries of the segment are
+    /// the public inputs of the resulting proof.
+    pub fn prove_segment(
+        &self,
+        inputs: PartialWitness<F>,
+    ) -> Result<ProofWithPublicInputs<F, C, D>> {
+        self.segment.prove(inputs)
+    }
+
+    /// Combines proofs of consecutive segments into one proof. Each segment must have the public
+    /// inputs of the segment circuit, and start at the boundary the previous one ends at.
+    pub fn combine_segments(
+        &self,
+        segments: &[ProofWithPublicInputs<F, C, D>],
+    ) -> Result<ProofWithPublicInputs<F, C, D>> {
+        ensure!(
+            segments.len() == self.num_segments(),
+            "Expected {} segments, got {}",
+            self.num_segments(),
+            segments.len()
+        );
+        for (i, segment) in segments.iter().enumerate() {
+            ensure!(
+                segment.public_inputs.len() == 2 * self.boundary_len,
+                "Segment {} has {} public inputs, expected {}",
+                i,
+                segment.public_inputs.len(),
+                2 * self.boundary_len
+            );
+        }
+        for (i, pair) in segments.windows(2).enumerate() {
+            ensure!(
+                pair[0].public_inputs[self.boundary_len..]
+                    == pair[1].public_inputs[..self.boundary_len],
+                "Segment {} does not start where segment {} ends",
+                i + 1,
+                i
+            );
+        }
+
+        let mut pw = PartialWitness::new();
+        for (target, proof) in self.segment_proofs.iter().zip(segments) {
+            pw.set_proof_with_pis_target(target, proof);
+        }
+        self.combined.prove(pw)
+    }
+
+    /// Verifies a proof produced by `combine_segments`.
+    pub fn verify(&self, proof_with_pis: ProofWithPublicInputs<F, C, D>) -> Result<()> {
+        self.combined.verify(proof_with_pis)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use anyhow::Result;
+
+    use crate::field::types::Field;
+    use crate::iop::target::Target;
+    use crate::iop::witness::{PartialWitness, WitnessWrite};
+    use crate::plonk::circuit_builder::CircuitBuilder;
+    use crate::plonk::circuit_data::{CircuitConfig, CircuitData};
+    use crate::plonk::config::{GenericConfig, PoseidonGoldilocksConfig};
+    use crate::recursion::segmented_prover::SegmentedProver;
+
+    const D: usize = 2;
+    type C = PoseidonGoldilocksConfig;
+    type F = <C as GenericConfig<D>>::F;
+
+    /// A circuit advancing a Fibonacci-like sequence by `num_steps`, from the public pair `(a, b)`
+    /// to the public pair it ends at.
+    fn fibonacci_circuit(num_steps: usize) -> (CircuitData<F, C, D>, [Target; 2]) {
+        let config = CircuitConfig::standard_recursion_config();
+        let mut builder = CircuitBuilder::<F, D>::new(config);
+        let initial_a = builder.add_virtual_public_input();
+        let initial_b = builder.add_virtual_public_input();
+        let mut prev_target = initial_a;
+        let mut cur_target = initial_b;
+        for _ in 0..num_steps {
+            let temp = builder.add(prev_target, cur_target);
+            prev_target = cur_target;
+            cur_target = temp;
+        }
+        builder.register_public_input(prev_target);
+        builder.register_public_input(cur_target);
+        (builder.build::<C>(), [initial_a, initial_b])
+    }
+
+    fn witness(initial: [Target; 2], values: &[F]) -> PartialWitness<F> {
+        let mut pw = PartialWitness::new();
+        pw.set_target(initial[0], values[0]);
+        pw.set_target(initial[1], values[1]);
+        pw
+    }
+
+    #[test]
+    fn test_segmented_prover() -> Result<()> {
+        let start = [F::ZERO, F::ONE];
+        let (monolithic, initial) = fibonacci_circuit(2000);
+        let monolithic_proof = monolithic.prove(witness(initial, &start))?;
+        monolithic.verify(monolithic_proof.clone())?;
+
+        let (segment, initial) = fibonacci_circuit(1000);
+        let prover = SegmentedProver::new(segment, 2, 2);
+        let first = prover.prove_segment(witness(initial, &start))?;
+        let second = prover.prove_segment(witness(initial, &first.public_inputs[2..]))?;
+
+        // Segments which do not chain are rejected.
+        assert!(prover
+            .combine_segments(&[first.clone(), first.clone()])
+            .is_err());
+        assert!(prover.combine_segments(&[first.clone()]).is_err());
+        // So are segments with the wrong number of public inputs.
+        let mut truncated = first.clone();
+        truncated.public_inputs.truncate(1);
+        assert!(prover
+            .combine_segments(&[truncated, second.clone()])
+            .is_err());
+
+        let combined = prover.combine_segments(&[first, second])?;
+        assert_eq!(combined.public_inputs, monolithic_proof.public_inputs);
+        prover.verify(combined)
+    }
+}
@@ -0,0 +1,311 @@
+use alloc::vec::Vec;
+
+use crate::field::extension::Extendable;
+use crate::gates::arithmetic_base::ArithmeticGate;
+use crate::gates::arithmetic_extension::ArithmeticExtensionGate;
+use crate::gates::base_inverse::BaseInverseGate;
+use crate::gates::base_sum::BaseSumGate;
+use crate::gates::booleanity::BooleanityGate;
+use crate::gates::constant::ConstantGate;
+use crate::gates::cube_add::CubeAddGate;
+use crate::gates::diff_of_squares::DiffOfSquaresGate;
+use crate::gates::dot_product::DotProductGate;
+use crate::gates::exponentiation::ExponentiationGate;
+use crate::gates::gate::GateRef;
+use crate::gates::hash_select::HashSelectGate;
+use crate::gates::high_degree_interpolation::HighDegreeInterpolationGate;
+use crate::gates::inv_sum::InvSumGate;
+use crate::gates::ladder_step::LadderStepGate;
+use crate::gates::low_degree_interpolation::LowDegreeInterpolationGate;
+use crate::gates::multiplication_extension::MulExtensionGate;
+use crate::gates::mux4::Mux4Gate;
+use crate::gates::noop::NoopGate;
+use crate::gates::poseidon::PoseidonGate;
+use crate::gates::poseidon_mds::PoseidonMdsGate;
+use crate::gates::public_input::PublicInputGate;
+use crate::gates::quadratic::QuadraticGate;
+use crate::gates::random_access::RandomAccessGate;
+use crate::gates::reducing::ReducingGate;
+use crate::gates::reducing_extension::ReducingExtensionGate;
+use crate::gates::running_product::RunningProductGate;
+use crate::gates::shared_scalar_mul::SharedScalarMulGate;
+use crate::gates::small_range::SmallRangeGate;
+use crate::gates::sum::SumGate;
+use crate::hash::hash_types::RichField;
+use crate::util::serialization::{IoError, IoResult, Read, Write};
+
+/// Serializes and deserializes the gates of a circuit. Gates are trait objects, so a serializer
+/// needs to know the set of concrete gate types it may encounter.
+pub trait GateSerializer<F: RichField + Extendable<D>, const D: usize> {
+    /// Reads a gate from `src`.
+    fn read_gate<R: Read + ?Sized>(&self, src: &mut R) -> IoResult<GateRef<F, D>>;
+
+    /// Writes `gate` to `dst`.
+    fn write_gate<W: Write + ?Sized>(&self, dst: &mut W, gate: &GateRef<F, D>) -> IoResult<()>;
+}
+
+/// Identifies the type of a gate in serialized form. Each variant's discriminant is the tag
+/// written for it, so variants must keep their discriminants once released.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum GateId {
+    Arithmetic = 0,
+    ArithmeticExtension = 1,
+    BaseInverse = 2,
+    BaseSum = 3,
+    Booleanity = 4,
+    Constant = 5,
+    CubeAdd = 6,
+    DiffOfSquares = 7,
+    DotProduct = 8,
+    Exponentiation = 9,
+    HashSelect = 10,
+    HighDegreeInterpolation = 11,
+    InvSum = 12,
+    LadderStep = 13,
+    LowDegreeInterpolation = 14,
+    MulExtension = 15,
+    Mux4 = 16,
+    Noop = 17,
+    Poseidon = 18,
+    PoseidonMds = 19,
+    PublicInput = 20,
+    Quadratic = 21,
+    RandomAccess = 22,
+    Reducing = 23,
+    ReducingExtension = 24,
+    RunningProduct = 25,
+    SharedScalarMul = 26,
+    SmallRange = 27,
+    Sum = 28,
+}
+
+impl GateId {
+    const ALL: [Self; 29] = [
+        Self::Arithmetic,
+        Self::ArithmeticExtension,
+        Self::BaseInverse,
+        Self::BaseSum,
+        Self::Booleanity,
+        Self::Constant,
+        Self::CubeAdd,
+        Self::DiffOfSquares,
+        Self::DotProduct,
+        Self::Exponentiation,
+        Self::HashSelect,
+        Self::HighDegreeInterpolation,
+        Self::InvSum,
+        Self::LadderStep,
+        Self::LowDegreeInterpolation,
+        Self::MulExtension,
+        Self::Mux4,
+        Self::Noop,
+        Self::Poseidon,
+        Self::PoseidonMds,
+        Self::PublicInput,
+        Self::Quadratic,
+        Self::RandomAccess,
+        Self::Reducing,
+        Self::ReducingExtension,
+        Self::RunningProduct,
+        Self::SharedScalarMul,
+        Self::SmallRange,
+        Self::Sum,
+    ];
+
+    fn from_tag(tag: u8) -> IoResult<Self> {
+        Self::ALL
+            .into_iter()
+            .find(|&id| id as u8 == tag)
+            .ok_or(IoError)
+    }
+}
+
+/// A [`GateSerializer`] supporting all gates defined in this crate. A gate is written as the tag
+/// of its [`GateId`], followed by its parameters.
+#[derive(Copy, Clone, Debug, Default)]
+pub struct DefaultGateSerializer;
+
+impl<F: RichField + Extendable<D>, const D: usize> GateSerializer<F, D> for DefaultGateSerializer {
+    fn read_gate<R: Read + ?Sized>(&self, src: &mut R) -> IoResult<GateRef<F, D>> {
+        Ok(match GateId::from_tag(src.read_u8()?)? {
+            GateId::Arithmetic => GateRef::new(ArithmeticGate::deserialize(src)?),
+            GateId::ArithmeticExtension => {
+                GateRef::new(ArithmeticExtensionGate::<D>::deserialize(src)?)
+            }
+            GateId::BaseInverse => GateRef::new(BaseInverseGate::deserialize(src)?),
+            GateId::BaseSum => match src.read_u32()? {
+                2 => GateRef::new(BaseSumGate::<2>::deserialize(src)?),
+                4 => GateRef::new(BaseSumGate::<4>::deserialize(src)?),
+                _ => return Err(IoError),
+            },
+            GateId::Booleanity => GateRef::new(BooleanityGate::deserialize(src)?),
+            GateId::Constant => GateRef::new(ConstantGate::deserialize(src)?),
+            GateId::CubeAdd => GateRef::new(CubeAddGate::deserialize(src)?),
+            GateId::DiffOfSquares => GateRef::new(DiffOfSquaresGate::deserialize(src)?),
+            GateId::DotProduct => GateRef::new(DotProductGate::deserialize(src)?),
+            GateId::Exponentiation => GateRef::new(ExponentiationGate::<F, D>::deserialize(src)?),
+            GateId::HashSelect => GateRef::new(HashSelectGate::deserialize(src)?),
+            GateId::HighDegreeInterpolation => {
+                GateRef::new(HighDegreeInterpolationGate::<F, D>::deserialize(src)?)
+            }
+            GateId::InvSum => GateRef::new(InvSumGate::deserialize(src)?),
+            GateId::LadderStep => GateRef::new(LadderStepGate::deserialize(src)?),
+            GateId::LowDegreeInterpolation => {
+                GateRef::new(LowDegreeInterpolationGate::<F, D>::deserialize(src)?)
+            }
+            GateId::MulExtension => GateRef::new(MulExtensionGate::<D>::deserialize(src)?),
+            GateId::Mux4 => GateRef::new(Mux4Gate::deserialize(src)?),
+            GateId::Noop => GateRef::new(NoopGate),
+            GateId::Poseidon => GateRef::new(PoseidonGate::<F, D>::new()),
+            GateId::PoseidonMds => GateRef::new(PoseidonMdsGate::<F, D>::new()),
+            GateId::PublicInput => GateRef::new(PublicInputGate),
+            GateId::Quadratic => GateRef::new(QuadraticGate::deserialize(src)?),
+            GateId::RandomAccess => GateRef::new(RandomAccessGate::<F, D>::deserialize(src)?),
+            GateId::Reducing => GateRef::new(ReducingGate::<D>::deserialize(src)?),
+            GateId::ReducingExtension => {
+                GateRef::new(ReducingExtensionGate::<D>::deserialize(src)?)
+            }
+            GateId::RunningProduct => GateRef::new(RunningProductGate::deserialize(src)?),
+            GateId::SharedScalarMul => GateRef::new(SharedScalarMulGate::deserialize(src)?),
+            GateId::SmallRange => GateRef::new(SmallRangeGate::deserialize(src)?),
+            GateId::Sum => GateRef::new(SumGate::deserialize(src)?),
+        })
+    }
+
+    fn write_gate<W: Write + ?Sized>(&self, dst: &mut W, gate: &GateRef<F, D>) -> IoResult<()> {
+        let id = gate.0.serialization_id().ok_or(IoError)?;
+        let mut payload = Vec::new();
+        gate.0.serialize(&mut payload)?;
+        dst.write_u8(id as u8)?;
+        dst.write_all(&payload)
+    }
+}
+
+/// Writes a gate parameter, failing rather than truncating values which do not fit in a `u32`.
+pub(crate) fn write_gate_param(dst: &mut Vec<u8>, value: usize) -> IoResult<()> {
+    dst.write_u32(u32::try_from(value).map_err(|_| IoError)?)
+}
+
+/// Reads a gate parameter written by `write_gate_param`.
+pub(crate) fn read_gate_param<R: Read + ?Sized>(src: &mut R) -> IoResult<usize> {
+    let value = src.read_u32()? as usize;
+    // No gate dimension comes anywhere near this, and it keeps derived sizes from overflowing.
+    if value > u16::MAX as usize {
+        return Err(IoError);
+    }
+    Ok(value)
+}
+
+/// Like `read_gate_param`, for parameters counting bits. These are exponents, so they are bounded
+/// more tightly to keep the resulting sizes from overflowing.
+pub(crate) fn read_gate_bits_param<R: Read + ?Sized>(src: &mut R) -> IoResult<usize> {
+    let bits = read_gate_param(src)?;
+    if bits >= u16::BITS as usize {
+        return Err(IoError);
+    }
+    Ok(bits)
+}
+
+#[cfg(test)]
+mod tests {
+    use alloc::vec::Vec;
+
+    use crate::gates::arithmetic_base::ArithmeticGate;
+    use crate::gates::arithmetic_extension::ArithmeticExtensionGate;
+    use crate::gates::base_inverse::BaseInverseGate;
+    use crate::gates::base_sum::BaseSumGate;
+    use crate::gates::booleanity::BooleanityGate;
+    use crate::gates::constant::ConstantGate;
+    use crate::gates::cube_add::CubeAddGate;
+    use crate::gates::diff_of_squares::DiffOfSquaresGate;
+    use crate::gates::dot_product::DotProductGate;
+    use crate::gates::exponentiation::ExponentiationGate;
+    use crate::gates::gate::GateRef;
+    use crate::gates::hash_select::HashSelectGate;
+    use crate::gates::high_degree_interpolation::HighDegreeInterpolationGate;
+    use crate::gates::interpolation::InterpolationGate;
+    use crate::gates::inv_sum::InvSumGate;
+    use crate::gates::ladder_step::LadderStepGate;
+    use crate::gates::low_degree_interpolation::LowDegreeInterpolationGate;
+    use crate::gates::multiplication_extension::MulExtensionGate;
+    use crate::gates::mux4::Mux4Gate;
+    use crate::gates::noop::NoopGate;
+    use crate::gates::poseidon::PoseidonGate;
+    use crate::gates::poseidon_mds::PoseidonMdsGate;
+    use crate::gates::public_input::PublicInputGate;
+    use crate::gates::quadratic::QuadraticGate;
+    use crate::gates::random_access::RandomAccessGate;
+    use crate::gates::reducing::ReducingGate;
+    use crate::gates::reducing_extension::ReducingExtensionGate;
+    use crate::gates::running_product::RunningProductGate;
+    use crate::gates::shared_scalar_mul::SharedScalarMulGate;
+    use crate::gates::small_range::SmallRangeGate;
+    use crate::gates::sum::SumGate;
+    use crate::plonk::circuit_data::CircuitConfig;
+    use crate::plonk::config::{GenericConfig, PoseidonGoldilocksConfig};
+    use crate::util::gate_serialization::{DefaultGateSerializer, GateSerializer};
+    use crate::util::serialization::Buffer;
+
+    const D: usize = 2;
+    type C = PoseidonGoldilocksConfig;
+    type F = <C as GenericConfig<D>>::F;
+
+    #[test]
+    fn test_gate_round_trip() {
+        let config = CircuitConfig::standard_recursion_config();
+        let gates: Vec<GateRef<F, D>> = vec![
+            GateRef::new(ArithmeticGate::new_from_config(&config)),
+            GateRef::new(ArithmeticExtensionGate::new_from_config(&config)),
+            GateRef::new(BaseInverseGate::new_from_config(&config)),
+            GateRef::new(BaseSumGate::<2>::new(63)),
+            GateRef::new(BaseSumGate::<4>::new(32)),
+            GateRef::new(BooleanityGate::new_from_config(&config)),
+            GateRef::new(ConstantGate { num_consts: 2 }),
+            GateRef::new(CubeAddGate::new_from_config(&config)),
+            GateRef::new(DiffOfSquaresGate::new_from_config(&config)),
+            GateRef::new(DotProductGate::new_from_config(&config)),
+            GateRef::new(ExponentiationGate::<F, D>::new_from_config(&config)),
+            GateRef::new(HashSelectGate::new_from_config(&config)),
+            GateRef::new(<HighDegreeInterpolationGate<F, D> as InterpolationGate<
+                F,
+                D,
+            >>::new(2)),
+            GateRef::new(InvSumGate::new_from_config(&config)),
+            GateRef::new(LadderStepGate::new(2)),
+            GateRef::new(<LowDegreeInterpolationGate<F, D> as InterpolationGate<
+                F,
+                D,
+            >>::new(4)),
+            GateRef::new(MulExtensionGate::new_from_config(&config)),
+            GateRef::new(Mux4Gate::new_from_config(&config)),
+            GateRef::new(NoopGate),
+            GateRef::new(PoseidonGate::<F, D>::new()),
+            GateRef::new(PoseidonMdsGate::<F, D>::new()),
+            GateRef::new(PublicInputGate),
+            GateRef::new(QuadraticGate::new_from_config(&config)),
+            GateRef::new(RandomAccessGate::<F, D>::new_from_config(&config, 4)),
+            GateRef::new(ReducingGate::<D>::new(13)),
+            GateRef::new(ReducingExtensionGate::<D>::new(11)),
+            GateRef::new(RunningProductGate::new_from_config(&config)),
+            GateRef::new(SharedScalarMulGate::new_from_config(&config)),
+            GateRef::new(SmallRangeGate::new_u8(&config)),
+            GateRef::new(SumGate::new_from_config(&config)),
+        ];
+
+        for gate in gates {
+            let mut bytes = Vec::new();
+            DefaultGateSerializer.write_gate(&mut bytes, &gate).unwrap();
+            let read: GateRef<F, D> = DefaultGateSerializer
+                .read_gate(&mut Buffer::new(bytes))
+                .unwrap();
+            assert_eq!(read, gate);
+        }
+    }
+
+    #[test]
+    fn test_unknown_gate() {
+        let read: Result<GateRef<F, D>, _> =
+            DefaultGateSerializer.read_gate(&mut Buffer::new(vec![u8::MAX, 3, 0, 0, 0]));
+        assert!(read.is_err());
+    }
+}
@@ -0,0 +1,180 @@
+use alloc::boxed::Box;
+use alloc::vec::Vec;
+
+use crate::field::extension::Extendable;
+use crate::gadgets::arithmetic_extension::QuotientGeneratorExtension;
+use crate::gadgets::split_join::WireSplitGenerator;
+use crate::gates::arithmetic_base::ArithmeticBaseGenerator;
+use crate::gates::arithmetic_extension::ArithmeticExtensionGenerator;
+use crate::gates::base_inverse::BaseInverseGenerator;
+use crate::gates::base_sum::BaseSplitGenerator;
+use crate::gates::cube_add::CubeAddGenerator;
+use crate::gates::diff_of_squares::DiffOfSquaresGenerator;
+use crate::gates::dot_product::DotProductGenerator;
+use crate::gates::hash_select::HashSelectGenerator;
+use crate::gates::inv_sum::InvSumGenerator;
+use crate::gates::multiplication_extension::MulExtensionGenerator;
+use crate::gates::mux4::Mux4Generator;
+use crate::gates::poseidon::PoseidonGenerator;
+use crate::gates::running_product::RunningProductGenerator;
+use crate::gates::shared_scalar_mul::SharedScalarMulGenerator;
+use crate::gates::small_range::SmallRangeGenerator;
+use crate::gates::sum::SumGenerator;
+use crate::hash::hash_types::RichField;
+use crate::iop::generator::{
+    ConstantGenerator, CopyGenerator, NonzeroTestGenerator, RandomValueGenerator, SimpleGenerator,
+    WitnessGenerator,
+};
+use crate::plonk::circuit_data::CommonCircuitData;
+use crate::util::serialization::{IoError, IoResult, Read, Write};
+
+/// Serializes and deserializes the witness generators of a circuit. Generators are trait objects,
+/// so a serializer needs to know the set of concrete generator types it may encounter.
+pub trait WitnessGeneratorSerializer<F: RichField + Extendable<D>, const D: usize> {
+    /// Reads a generator from `src`, for a circuit described by `common_data`.
+    fn read_generator<R: Read + ?Sized>(
+        &self,
+        src: &mut R,
+        common_data: &CommonCircuitData<F, D>,
+    ) -> IoResult<Box<dyn WitnessGenerator<F>>>;
+
+    /// Writes `generator` to `dst`.
+    fn write_generator<W: Write + ?Sized>(
+        &self,
+        dst: &mut W,
+        generator: &dyn WitnessGenerator<F>,
+    ) -> IoResult<()>;
+}
+
+/// Identifies the type of a witness generator in serialized form. Each variant's discriminant is
+/// the tag written for it, so variants must keep their discriminants once released.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum GeneratorId {
+    ArithmeticBase = 0,
+    ArithmeticExtension = 1,
+    BaseInverse = 2,
+    BaseSplit = 3,
+    Constant = 4,
+    Copy = 5,
+    CubeAdd = 6,
+    DiffOfSquares = 7,
+    HashSelect = 8,
+    InvSum = 9,
+    MulExtension = 10,
+    Mux4 = 11,
+    NonzeroTest = 12,
+    Poseidon = 13,
+    QuotientExtension = 14,
+    RandomValue = 15,
+    RunningProduct = 16,
+    SharedScalarMul = 17,
+    SmallRange = 18,
+    Sum = 19,
+    WireSplit = 20,
+    DotProduct = 21,
+}
+
+impl GeneratorId {
+    const ALL: [Self; 22] = [
+        Self::ArithmeticBase,
+        Self::ArithmeticExtension,
+        Self::BaseInverse,
+        Self::BaseSplit,
+        Self::Constant,
+        Self::Copy,
+        Self::CubeAdd,
+        Self::DiffOfSquares,
+        Self::HashSelect,
+        Self::InvSum,
+        Self::MulExtension,
+        Self::Mux4,
+        Self::NonzeroTest,
+        Self::Poseidon,
+        Self::QuotientExtension,
+        Self::RandomValue,
+        Self::RunningProduct,
+        Self::SharedScalarMul,
+        Self::SmallRange,
+        Self::Sum,
+        Self::WireSplit,
+        Self::DotProduct,
+    ];
+
+    fn from_tag(tag: u8) -> IoResult<Self> {
+        Self::ALL
+            .into_iter()
+            .find(|&id| id as u8 == tag)
+            .ok_or(IoError)
+    }
+}
+
+/// A [`WitnessGeneratorSerializer`] supporting every generator with a [`GeneratorId`], i.e. those
+/// of the arithmetic, base sum, constant, Poseidon and packed gates, the extension division and
+/// wire splitting generators, and the copy, nonzero test and random value generators added by the
+/// circuit builder.
+/// A generator is written as the tag of its id, followed by its parameters.
+#[derive(Copy, Clone, Debug, Default)]
+pub struct DefaultGeneratorSerializer;
+
+impl<F: RichField + Extendable<D>, const D: usize> WitnessGeneratorSerializer<F, D>
+    for DefaultGeneratorSerializer
+{
+    fn read_generator<R: Read + ?Sized>(
+        &self,
+        src: &mut R,
+        _common_data: &CommonCircuitData<F, D>,
+    ) -> IoResult<Box<dyn WitnessGenerator<F>>> {
+        fn adapt<F: RichField, SG: SimpleGenerator<F> + Clone>(
+            generator: SG,
+        ) -> Box<dyn WitnessGenerator<F>> {
+            Box::new(generator.adapter())
+        }
+
+        Ok(match GeneratorId::from_tag(src.read_u8()?)? {
+            GeneratorId::ArithmeticBase => {
+                adapt(ArithmeticBaseGenerator::<F, D>::deserialize(src)?)
+            }
+            GeneratorId::ArithmeticExtension => {
+                adapt(ArithmeticExtensionGenerator::<F, D>::deserialize(src)?)
+            }
+            GeneratorId::BaseInverse => adapt(BaseInverseGenerator::deserialize(src)?),
+            GeneratorId::BaseSplit => match src.read_u32()? {
+                2 => adapt(BaseSplitGenerator::<2>::deserialize(src)?),
+                4 => adapt(BaseSplitGenerator::<4>::deserialize(src)?),
+                _ => return Err(IoError),
+            },
+            GeneratorId::Constant => adapt(ConstantGenerator::<F>::deserialize(src)?),
+            GeneratorId::Copy => adapt(CopyGenerator::deserialize(src)?),
+            GeneratorId::CubeAdd => adapt(CubeAddGenerator::<F>::deserialize(src)?),
+            GeneratorId::DiffOfSquares => adapt(DiffOfSquaresGenerator::deserialize(src)?),
+            GeneratorId::HashSelect => adapt(HashSelectGenerator::deserialize(src)?),
+            GeneratorId::InvSum => adapt(InvSumGenerator::deserialize(src)?),
+            GeneratorId::MulExtension => adapt(MulExtensionGenerator::<F, D>::deserialize(src)?),
+            GeneratorId::Mux4 => adapt(Mux4Generator::deserialize(src)?),
+            GeneratorId::NonzeroTest => adapt(NonzeroTestGenerator::deserialize(src)?),
+            GeneratorId::Poseidon => adapt(PoseidonGenerator::<F, D>::deserialize(src)?),
+            GeneratorId::QuotientExtension => {
+                adapt(QuotientGeneratorExtension::<D>::deserialize(src)?)
+            }
+            GeneratorId::RandomValue => adapt(RandomValueGenerator::deserialize(src)?),
+            GeneratorId::RunningProduct => adapt(RunningProductGenerator::deserialize(src)?),
+            GeneratorId::SharedScalarMul => adapt(SharedScalarMulGenerator::deserialize(src)?),
+            GeneratorId::SmallRange => adapt(SmallRangeGenerator::deserialize(src)?),
+            GeneratorId::Sum => adapt(SumGenerator::deserialize(src)?),
+            GeneratorId::WireSplit => adapt(WireSplitGenerator::deserialize(src)?),
+            GeneratorId::DotProduct => adapt(DotProductGenerator::deserialize(src)?),
+        })
+    }
+
+    fn write_generator<W: Write + ?Sized>(
+        &self,
+        dst: &mut W,
+        generator: &dyn WitnessGenerator<F>,
+    ) -> IoResult<()> {
+        let id = generator.id().ok_or(IoError)?;
+        let mut payload = Vec::new();
+        generator.serialize(&mut payload)?;
+        dst.write_u8(id as u8)?;
+        dst.write_all(&payload)
+    }
+}
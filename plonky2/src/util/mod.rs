@@ -10,6 +10,8 @@ use crate::field::types::Field;
 pub(crate) mod context_tree;
 pub(crate) mod partial_products;
 
+pub mod gate_serialization;
+pub mod generator_serialization;
 pub mod reducing;
 pub mod serialization;
 pub mod strided_view;
@@ -5,23 +5,37 @@ use core::fmt::{Debug, Display, Formatter};
 use core::mem::size_of;
 
 use hashbrown::HashMap;
+use itertools::Itertools;
 
 use crate::field::extension::{Extendable, FieldExtension};
 use crate::field::polynomial::PolynomialCoeffs;
 use crate::field::types::{Field64, PrimeField64};
+use crate::fri::oracle::PolynomialBatch;
 use crate::fri::proof::{
     CompressedFriProof, CompressedFriQueryRounds, FriInitialTreeProof, FriProof, FriQueryRound,
     FriQueryStep,
 };
+use crate::fri::reduction_strategies::FriReductionStrategy;
+use crate::fri::{FriConfig, FriParams};
+use crate::gates::selectors::SelectorsInfo;
 use crate::hash::hash_types::RichField;
+use crate::hash::hashing::PlonkyPermutation;
 use crate::hash::merkle_proofs::MerkleProof;
-use crate::hash::merkle_tree::MerkleCap;
-use crate::plonk::circuit_data::CommonCircuitData;
+use crate::hash::merkle_tree::{MerkleCap, MerkleTree};
+use crate::iop::challenger::Challenger;
+use crate::iop::ext_target::ExtensionTarget;
+use crate::iop::target::Target;
+use crate::iop::wire::Wire;
+use crate::iop::witness::PartialWitness;
+use crate::plonk::circuit_data::{CircuitConfig, CommonCircuitData, VerifierOnlyCircuitData};
 use crate::plonk::config::{GenericConfig, GenericHashOut, Hasher};
 use crate::plonk::plonk_common::salt_size;
 use crate::plonk::proof::{
     CompressedProof, CompressedProofWithPublicInputs, OpeningSet, Proof, ProofWithPublicInputs,
 };
+use crate::plonk::prover::ProverCheckpoint;
+use crate::util::gate_serialization::GateSerializer;
+use crate::util::partial_products;
 
 /// A no_std compatible variant of `std::io::Error`
 #[derive(Debug)]
@@ -36,6 +50,25 @@ impl Display for IoError {
 /// A no_std compatible variant of `std::io::Result`
 pub type IoResult<T> = Result<T, IoError>;
 
+/// Marks a proof with public inputs serialized as tagged sections. Proofs in the legacy layout,
+/// from before sections were introduced, start with the first hash of their wires cap instead.
+/// For hashes over the Goldilocks field, those bytes are a canonical field element and so never
+/// `u64::MAX`; for byte-oriented hashes, a collision has negligible probability.
+const PROOF_FORMAT_MARKER: [u8; 8] = [0xff; 8];
+/// The version of the sectioned proof format, written after `PROOF_FORMAT_MARKER`.
+const PROOF_FORMAT_VERSION: u8 = 1;
+
+/// Returns whether `bytes` hold a proof with public inputs in the sectioned format, rather than
+/// the legacy layout.
+pub(crate) fn is_sectioned_proof(bytes: &[u8]) -> bool {
+    bytes.starts_with(&PROOF_FORMAT_MARKER)
+}
+
+/// Tag of the section holding the proof itself, in a serialized proof with public inputs.
+const PROOF_SECTION: u8 = 0;
+/// Tag of the section holding the public inputs, in a serialized proof with public inputs.
+const PUBLIC_INPUTS_SECTION: u8 = 1;
+
 /// A `Read` which is able to report how many bytes are remaining.
 pub trait Remaining: Read {
     /// Returns the number of bytes remaining in the buffer.
@@ -76,7 +109,11 @@ pub trait Read {
     {
         let mut buf = [0; size_of::<u64>()];
         self.read_exact(&mut buf)?;
-        Ok(F::from_canonical_u64(u64::from_le_bytes(buf)))
+        let x = u64::from_le_bytes(buf);
+        if x >= F::ORDER {
+            return Err(IoError);
+        }
+        Ok(F::from_canonical_u64(x))
     }
 
     /// Reads a vector of elements from the field `F` from `self`.
@@ -117,6 +154,53 @@ pub trait Read {
         (0..length).map(|_| self.read_field_ext::<F, D>()).collect()
     }
 
+    /// Reads a value of type [`Target`] from `self`.
+    #[inline]
+    fn read_target(&mut self) -> IoResult<Target> {
+        match self.read_u8()? {
+            0 => {
+                let row = self.read_u32()? as usize;
+                let column = self.read_u32()? as usize;
+                Ok(Target::Wire(Wire { row, column }))
+            }
+            1 => {
+                let index = self.read_u32()? as usize;
+                Ok(Target::VirtualTarget { index })
+            }
+            _ => Err(IoError),
+        }
+    }
+
+    /// Reads a value of type [`ExtensionTarget`] from `self`.
+    #[inline]
+    fn read_target_ext<const D: usize>(&mut self) -> IoResult<ExtensionTarget<D>> {
+        let mut targets = [Target::VirtualTarget { index: 0 }; D];
+        for target in &mut targets {
+            *target = self.read_target()?;
+        }
+        Ok(ExtensionTarget(targets))
+    }
+
+    /// Reads a value of type [`PartialWitness`] from `self`.
+    #[inline]
+    fn read_partial_witness<F>(&mut self) -> IoResult<PartialWitness<F>>
+    where
+        F: Field64,
+    {
+        let length = self.read_u32()? as usize;
+        let mut pw = PartialWitness::new();
+        for _ in 0..length {
+            let target = self.read_target()?;
+            let value = self.read_field()?;
+            // A canonical encoding assigns each target once, and `set_target` would panic on a
+            // conflicting reassignment.
+            if pw.target_values.insert(target, value).is_some() {
+                return Err(IoError);
+            }
+        }
+        Ok(pw)
+    }
+
     /// Reads a hash value from `self`.
     #[inline]
     fn read_hash<F, H>(&mut self) -> IoResult<H::Hash>
@@ -126,7 +210,12 @@ pub trait Read {
     {
         let mut buf = vec![0; H::HASH_SIZE];
         self.read_exact(&mut buf)?;
-        Ok(H::Hash::from_bytes(&buf))
+        let hash = H::Hash::from_bytes(&buf);
+        // Reject non-canonical encodings, which would otherwise be silently reduced.
+        if hash.to_bytes() != buf {
+            return Err(IoError);
+        }
+        Ok(hash)
     }
 
     /// Reads a value of type [`MerkleCap`] from `self` with the given `cap_height`.
@@ -136,7 +225,10 @@ pub trait Read {
         F: RichField,
         H: Hasher<F>,
     {
-        let cap_length = 1 << cap_height;
+        let cap_length = u32::try_from(cap_height)
+            .ok()
+            .and_then(|h| 1usize.checked_shl(h))
+            .ok_or(IoError)?;
         Ok(MerkleCap(
             (0..cap_length)
                 .map(|_| self.read_hash::<F, H>())
@@ -240,7 +332,8 @@ pub trait Read {
         F: RichField + Extendable<D>,
         C: GenericConfig<D, F = F>,
     {
-        let evals = self.read_field_ext_vec::<F, D>(arity - usize::from(compressed))?;
+        let num_evals = arity.checked_sub(usize::from(compressed)).ok_or(IoError)?;
+        let evals = self.read_field_ext_vec::<F, D>(num_evals)?;
         let merkle_proof = self.read_merkle_proof()?;
         Ok(FriQueryStep {
             evals,
@@ -259,7 +352,7 @@ pub trait Read {
         C: GenericConfig<D, F = F>,
     {
         let config = &common_data.config;
-        let mut fqrs = Vec::with_capacity(config.fri_config.num_query_rounds);
+        let mut fqrs = Vec::new();
         for _ in 0..config.fri_config.num_query_rounds {
             let initial_trees_proof = self.read_fri_initial_proof::<F, C, D>(common_data)?;
             let steps = common_data
@@ -328,12 +421,96 @@ pub trait Read {
         })
     }
 
+    /// Reads the marker and version of the sectioned proof format, failing on versions we don't
+    /// know of.
+    #[inline]
+    fn read_proof_format_header(&mut self) -> IoResult<()> {
+        let mut marker = [0; PROOF_FORMAT_MARKER.len()];
+        self.read_exact(&mut marker)?;
+        if marker != PROOF_FORMAT_MARKER || self.read_u8()? != PROOF_FORMAT_VERSION {
+            return Err(IoError);
+        }
+        Ok(())
+    }
+
+    /// Reads the header of a section, which must be tagged `tag`, returning its length in bytes.
+    #[inline]
+    fn read_section_header(&mut self, tag: u8) -> IoResult<usize> {
+        if self.read_u8()? != tag {
+            return Err(IoError);
+        }
+        Ok(self.read_u32()? as usize)
+    }
+
+    /// Reads the public inputs section, which holds a vector of field elements.
+    #[inline]
+    fn read_public_inputs_section<F>(&mut self) -> IoResult<Vec<F>>
+    where
+        F: Field64,
+    {
+        let length = self.read_section_header(PUBLIC_INPUTS_SECTION)?;
+        if length % size_of::<u64>() != 0 {
+            return Err(IoError);
+        }
+        self.read_field_vec(length / size_of::<u64>())
+    }
+
+    /// Skips any sections following the ones we know of. Later format versions may append
+    /// non-critical sections, which older readers can safely ignore.
+    #[inline]
+    fn skip_trailing_sections(&mut self) -> IoResult<()>
+    where
+        Self: Remaining,
+    {
+        while !self.is_empty() {
+            let _tag = self.read_u8()?;
+            let length = self.read_u32()? as usize;
+            if self.remaining() < length {
+                return Err(IoError);
+            }
+            self.read_exact(&mut vec![0; length])?;
+        }
+        Ok(())
+    }
+
     /// Reads a value of type [`ProofWithPublicInputs`] from `self` with `common_data`.
+    ///
+    /// After the format marker and version, the proof and its public inputs are stored in
+    /// mandatory, length-prefixed sections, which may be followed by further sections that are
+    /// skipped.
     #[inline]
     fn read_proof_with_public_inputs<F, C, const D: usize>(
         &mut self,
         common_data: &CommonCircuitData<F, D>,
     ) -> IoResult<ProofWithPublicInputs<F, C, D>>
+    where
+        Self: Remaining,
+        F: RichField + Extendable<D>,
+        C: GenericConfig<D, F = F>,
+    {
+        self.read_proof_format_header()?;
+        let length = self.read_section_header(PROOF_SECTION)?;
+        let remaining = self.remaining();
+        let proof = self.read_proof(common_data)?;
+        if remaining - self.remaining() != length {
+            return Err(IoError);
+        }
+        let public_inputs = self.read_public_inputs_section()?;
+        self.skip_trailing_sections()?;
+        Ok(ProofWithPublicInputs {
+            proof,
+            public_inputs,
+        })
+    }
+
+    /// Reads a value of type [`ProofWithPublicInputs`] from `self` with `common_data`, in the
+    /// legacy layout without a format marker: the proof, followed by the public inputs filling the
+    /// rest of `self`.
+    #[inline]
+    fn read_legacy_proof_with_public_inputs<F, C, const D: usize>(
+        &mut self,
+        common_data: &CommonCircuitData<F, D>,
+    ) -> IoResult<ProofWithPublicInputs<F, C, D>>
     where
         Self: Remaining,
         F: RichField + Extendable<D>,
@@ -447,12 +624,40 @@ pub trait Read {
         })
     }
 
-    /// Reads a value of type [`CompressedProofWithPublicInputs`] from `self` with `common_data`.
+    /// Reads a value of type [`CompressedProofWithPublicInputs`] from `self` with `common_data`,
+    /// in the sectioned format of [`ProofWithPublicInputs`].
     #[inline]
     fn read_compressed_proof_with_public_inputs<F, C, const D: usize>(
         &mut self,
         common_data: &CommonCircuitData<F, D>,
     ) -> IoResult<CompressedProofWithPublicInputs<F, C, D>>
+    where
+        Self: Remaining,
+        F: RichField + Extendable<D>,
+        C: GenericConfig<D, F = F>,
+    {
+        self.read_proof_format_header()?;
+        let length = self.read_section_header(PROOF_SECTION)?;
+        let remaining = self.remaining();
+        let proof = self.read_compressed_proof(common_data)?;
+        if remaining - self.remaining() != length {
+            return Err(IoError);
+        }
+        let public_inputs = self.read_public_inputs_section()?;
+        self.skip_trailing_sections()?;
+        Ok(CompressedProofWithPublicInputs {
+            proof,
+            public_inputs,
+        })
+    }
+
+    /// Reads a value of type [`CompressedProofWithPublicInputs`] from `self` with `common_data`, in
+    /// the legacy layout of [`ProofWithPublicInputs`].
+    #[inline]
+    fn read_legacy_compressed_proof_with_public_inputs<F, C, const D: usize>(
+        &mut self,
+        common_data: &CommonCircuitData<F, D>,
+    ) -> IoResult<CompressedProofWithPublicInputs<F, C, D>>
     where
         Self: Remaining,
         F: RichField + Extendable<D>,
@@ -465,6 +670,368 @@ pub trait Read {
             public_inputs,
         })
     }
+
+    /// Reads a value of type [`FriConfig`] from `self`.
+    #[inline]
+    fn read_fri_config(&mut self) -> IoResult<FriConfig> {
+        let rate_bits = self.read_u32()? as usize;
+        let cap_height = self.read_u32()? as usize;
+        let proof_of_work_bits = self.read_u32()?;
+        let reduction_strategy = match self.read_u8()? {
+            0 => {
+                let length = self.read_u32()? as usize;
+                let arity_bits = (0..length)
+                    .map(|_| self.read_u32().map(|b| b as usize))
+                    .collect::<Result<_, _>>()?;
+                FriReductionStrategy::Fixed(arity_bits)
+            }
+            1 => {
+                let arity_bits = self.read_u32()? as usize;
+                let final_poly_bits = self.read_u32()? as usize;
+                FriReductionStrategy::ConstantArityBits(arity_bits, final_poly_bits)
+            }
+            2 => {
+                let max_arity_bits = match self.read_u8()? {
+                    0 => None,
+                    1 => Some(self.read_u32()? as usize),
+                    _ => return Err(IoError),
+                };
+                FriReductionStrategy::MinSize(max_arity_bits)
+            }
+            _ => return Err(IoError),
+        };
+        let num_query_rounds = self.read_u32()? as usize;
+        Ok(FriConfig {
+            rate_bits,
+            cap_height,
+            proof_of_work_bits,
+            reduction_strategy,
+            num_query_rounds,
+        })
+    }
+
+    /// Reads a value of type [`CircuitConfig`] from `self`.
+    #[inline]
+    fn read_circuit_config(&mut self) -> IoResult<CircuitConfig> {
+        let num_wires = self.read_u32()? as usize;
+        let num_routed_wires = self.read_u32()? as usize;
+        let num_constants = self.read_u32()? as usize;
+        let use_base_arithmetic_gate = self.read_bool()?;
+        let security_bits = self.read_u32()? as usize;
+        let num_challenges = self.read_u32()? as usize;
+        let zero_knowledge = self.read_bool()?;
+        let max_quotient_degree_factor = self.read_u32()? as usize;
+        let max_arithmetic_ops_per_row = if self.read_bool()? {
+            Some(self.read_u32()? as usize)
+        } else {
+            None
+        };
+        let fri_config = self.read_fri_config()?;
+        Ok(CircuitConfig {
+            num_wires,
+            num_routed_wires,
+            num_constants,
+            use_base_arithmetic_gate,
+            security_bits,
+            num_challenges,
+            zero_knowledge,
+            max_quotient_degree_factor,
+            max_arithmetic_ops_per_row,
+            fri_config,
+        })
+    }
+
+    /// Reads a `bool` value from `self`.
+    #[inline]
+    fn read_bool(&mut self) -> IoResult<bool> {
+        match self.read_u8()? {
+            0 => Ok(false),
+            1 => Ok(true),
+            _ => Err(IoError),
+        }
+    }
+
+    /// Reads a vector of `usize` values, prefixed by its length, from `self`.
+    #[inline]
+    fn read_usize_vec(&mut self) -> IoResult<Vec<usize>> {
+        let length = self.read_u32()? as usize;
+        (0..length)
+            .map(|_| self.read_u32().map(|x| x as usize))
+            .collect()
+    }
+
+    /// Reads a vector of field elements, prefixed by its length, from `self`.
+    #[inline]
+    fn read_length_prefixed_field_vec<F>(&mut self) -> IoResult<Vec<F>>
+    where
+        F: Field64,
+    {
+        let length = self.read_u32()? as usize;
+        self.read_field_vec(length)
+    }
+
+    /// Reads a value of type [`MerkleTree`] from `self` with the given `cap_height`.
+    #[inline]
+    fn read_merkle_tree<F, H>(&mut self, cap_height: usize) -> IoResult<MerkleTree<F, H>>
+    where
+        F: RichField,
+        H: Hasher<F>,
+    {
+        let num_leaves = self.read_u32()? as usize;
+        let leaves = (0..num_leaves)
+            .map(|_| self.read_length_prefixed_field_vec())
+            .collect::<Result<Vec<_>, _>>()?;
+        let num_digests = self.read_u32()? as usize;
+        let digests = (0..num_digests)
+            .map(|_| self.read_hash::<F, H>())
+            .collect::<Result<Vec<_>, _>>()?;
+        let cap = self.read_merkle_cap(cap_height)?;
+        Ok(MerkleTree {
+            leaves,
+            digests,
+            cap,
+        })
+    }
+
+    /// Reads a value of type [`PolynomialBatch`] from `self` with the given `cap_height`.
+    #[inline]
+    fn read_polynomial_batch<F, C, const D: usize>(
+        &mut self,
+        cap_height: usize,
+    ) -> IoResult<PolynomialBatch<F, C, D>>
+    where
+        F: RichField + Extendable<D>,
+        C: GenericConfig<D, F = F>,
+    {
+        let degree_log = self.read_u32()? as usize;
+        let rate_bits = self.read_u32()? as usize;
+        let blinding = self.read_bool()?;
+        let num_polynomials = self.read_u32()? as usize;
+        let polynomials = (0..num_polynomials)
+            .map(|_| {
+                self.read_length_prefixed_field_vec()
+                    .map(PolynomialCoeffs::new)
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        let merkle_tree = self.read_merkle_tree(cap_height)?;
+        Ok(PolynomialBatch {
+            polynomials,
+            merkle_tree,
+            degree_log,
+            rate_bits,
+            blinding,
+        })
+    }
+
+    /// Reads a value of type [`Challenger`] from `self`.
+    #[inline]
+    fn read_challenger<F, H>(&mut self) -> IoResult<Challenger<F, H>>
+    where
+        F: RichField,
+        H: Hasher<F>,
+    {
+        let sponge_state = H::Permutation::new(self.read_field_vec(H::Permutation::WIDTH)?);
+        let input_buffer = self.read_length_prefixed_field_vec()?;
+        let output_buffer = self.read_length_prefixed_field_vec()?;
+        Ok(Challenger {
+            sponge_state,
+            input_buffer,
+            output_buffer,
+        })
+    }
+
+    /// Reads a value of type [`ProverCheckpoint`] from `self` with the given `common_data`.
+    #[inline]
+    fn read_prover_checkpoint<F, C, const D: usize>(
+        &mut self,
+        common_data: &CommonCircuitData<F, D>,
+    ) -> IoResult<ProverCheckpoint<F, C, D>>
+    where
+        F: RichField + Extendable<D>,
+        C: GenericConfig<D, F = F>,
+    {
+        let cap_height = common_data.config.fri_config.cap_height;
+        let public_inputs = self.read_field_vec(common_data.num_public_inputs)?;
+        let wires_commitment = self.read_polynomial_batch(cap_height)?;
+        let partial_products_and_zs_commitment = self.read_polynomial_batch(cap_height)?;
+        let quotient_polys_commitment = self.read_polynomial_batch(cap_height)?;
+        let challenger = self.read_challenger()?;
+        Ok(ProverCheckpoint {
+            public_inputs,
+            wires_commitment,
+            partial_products_and_zs_commitment,
+            quotient_polys_commitment,
+            challenger,
+        })
+    }
+
+    /// Reads a value of type [`VerifierOnlyCircuitData`] from `self` with the given `common_data`.
+    #[inline]
+    fn read_verifier_only_circuit_data<F, C, const D: usize>(
+        &mut self,
+        common_data: &CommonCircuitData<F, D>,
+    ) -> IoResult<VerifierOnlyCircuitData<C, D>>
+    where
+        F: RichField + Extendable<D>,
+        C: GenericConfig<D, F = F>,
+    {
+        let cap_height = common_data.config.fri_config.cap_height;
+        let constants_sigmas_cap = self.read_merkle_cap(cap_height)?;
+        let circuit_digest = self.read_hash::<F, C::Hasher>()?;
+        Ok(VerifierOnlyCircuitData {
+            constants_sigmas_cap,
+            circuit_digest,
+        })
+    }
+
+    /// Reads a value of type [`CommonCircuitData`] from `self`, using `gate_serializer` to read
+    /// the circuit's gates.
+    #[inline]
+    fn read_common_circuit_data<F, G, const D: usize>(
+        &mut self,
+        gate_serializer: &G,
+    ) -> IoResult<CommonCircuitData<F, D>>
+    where
+        F: RichField + Extendable<D>,
+        G: GateSerializer<F, D>,
+    {
+        let config = self.read_circuit_config()?;
+
+        let hiding = self.read_bool()?;
+        let degree_bits = self.read_u32()? as usize;
+        let reduction_arity_bits = self.read_usize_vec()?;
+        let fri_params = FriParams {
+            config: config.fri_config.clone(),
+            hiding,
+            degree_bits,
+            reduction_arity_bits,
+        };
+
+        let num_gates = self.read_u32()? as usize;
+        let gates = (0..num_gates)
+            .map(|_| gate_serializer.read_gate(self))
+            .collect::<Result<_, _>>()?;
+
+        let selector_indices = self.read_usize_vec()?;
+        let num_groups = self.read_u32()? as usize;
+        let groups = (0..num_groups)
+            .map(|_| Ok(self.read_u32()? as usize..self.read_u32()? as usize))
+            .collect::<IoResult<_>>()?;
+        let selectors_info = SelectorsInfo {
+            selector_indices,
+            groups,
+        };
+
+        let quotient_degree_factor = self.read_u32()? as usize;
+        let num_gate_constraints = self.read_u32()? as usize;
+        let num_constants = self.read_u32()? as usize;
+        let num_public_inputs = self.read_u32()? as usize;
+        let num_k_is = self.read_u32()? as usize;
+        let k_is = self.read_field_vec(num_k_is)?;
+        let num_partial_products = self.read_u32()? as usize;
+
+        let common_data = CommonCircuitData {
+            config,
+            fri_params,
+            gates,
+            selectors_info,
+            quotient_degree_factor,
+            num_gate_constraints,
+            num_constants,
+            num_public_inputs,
+            k_is,
+            num_partial_products,
+        };
+        validate_common_circuit_data(&common_data)?;
+        Ok(common_data)
+    }
+}
+
+/// An upper bound on the dimensions of a deserialized circuit, such as its number of wires or of
+/// query rounds. It is far above anything used in practice, and keeps the sizes derived from these
+/// dimensions from overflowing, even with 32-bit `usize`s.
+const MAX_CIRCUIT_DIMENSION: usize = u16::MAX as usize;
+
+/// Checks that deserialized common data is consistent, i.e. that its dimensions agree with each
+/// other as they would for data built by a `CircuitBuilder`. Verification indexes proofs and
+/// evaluation points based on these dimensions, so this ensures that it can fail on adversarial
+/// circuit data, but never panic.
+fn validate_common_circuit_data<F, const D: usize>(
+    common_data: &CommonCircuitData<F, D>,
+) -> IoResult<()>
+where
+    F: RichField + Extendable<D>,
+{
+    let ensure = |condition: bool| if condition { Ok(()) } else { Err(IoError) };
+    let CommonCircuitData {
+        config,
+        fri_params,
+        gates,
+        selectors_info,
+        quotient_degree_factor,
+        num_gate_constraints,
+        num_constants,
+        num_public_inputs: _,
+        k_is,
+        num_partial_products,
+    } = common_data;
+    let fri_config = &config.fri_config;
+
+    for dimension in [
+        config.num_wires,
+        config.num_constants,
+        config.num_challenges,
+        *quotient_degree_factor,
+        *num_gate_constraints,
+        *num_constants,
+        fri_config.num_query_rounds,
+    ] {
+        ensure(dimension <= MAX_CIRCUIT_DIMENSION)?;
+    }
+    ensure(0 < config.num_routed_wires && config.num_routed_wires <= config.num_wires)?;
+    ensure(k_is.len() == config.num_routed_wires)?;
+    // Partial products are only defined for a quotient degree factor above one.
+    ensure(*quotient_degree_factor > 1)?;
+    ensure(
+        *num_partial_products
+            == partial_products::num_partial_products(
+                config.num_routed_wires,
+                *quotient_degree_factor,
+            ),
+    )?;
+
+    // The FRI domains must be subgroups of the field, and the reductions must fit within them.
+    ensure(fri_config.proof_of_work_bits <= u64::BITS)?;
+    let lde_bits = fri_params
+        .degree_bits
+        .checked_add(fri_config.rate_bits)
+        .ok_or(IoError)?;
+    ensure(lde_bits <= F::TWO_ADICITY)?;
+    let total_arities = fri_params
+        .reduction_arity_bits
+        .iter()
+        .try_fold(0usize, |acc, &bits| acc.checked_add(bits))
+        .ok_or(IoError)?;
+    ensure(total_arities <= fri_params.degree_bits)?;
+    ensure(fri_config.cap_height <= lde_bits - total_arities)?;
+
+    // Every gate must fit in a row, and its selector must be one of the constants.
+    let num_selectors = selectors_info.num_selectors();
+    ensure(selectors_info.selector_indices.len() == gates.len())?;
+    ensure(num_selectors <= *num_constants)?;
+    for (row, (gate, &selector_index)) in gates
+        .iter()
+        .zip(&selectors_info.selector_indices)
+        .enumerate()
+    {
+        let group = selectors_info.groups.get(selector_index).ok_or(IoError)?;
+        ensure(group.contains(&row) && group.end <= gates.len())?;
+        ensure(gate.0.num_wires() <= config.num_wires)?;
+        ensure(gate.0.num_constants() <= *num_constants - num_selectors)?;
+        ensure(gate.0.num_constraints() <= *num_gate_constraints)?;
+    }
+
+    Ok(())
 }
 
 /// Writing
@@ -532,6 +1099,47 @@ pub trait Write {
         Ok(())
     }
 
+    /// Writes a target `x` to `self`.
+    #[inline]
+    fn write_target(&mut self, x: Target) -> IoResult<()> {
+        match x {
+            Target::Wire(Wire { row, column }) => {
+                self.write_u8(0)?;
+                self.write_u32(u32::try_from(row).map_err(|_| IoError)?)?;
+                self.write_u32(u32::try_from(column).map_err(|_| IoError)?)
+            }
+            Target::VirtualTarget { index } => {
+                self.write_u8(1)?;
+                self.write_u32(u32::try_from(index).map_err(|_| IoError)?)
+            }
+        }
+    }
+
+    /// Writes an extension target `x` to `self`.
+    #[inline]
+    fn write_target_ext<const D: usize>(&mut self, x: ExtensionTarget<D>) -> IoResult<()> {
+        for target in x.0 {
+            self.write_target(target)?;
+        }
+        Ok(())
+    }
+
+    /// Writes a value `pw` of type [`PartialWitness`] to `self`. Targets are written in sorted
+    /// order, so that witnesses assigning the same values produce the same bytes regardless of
+    /// insertion order.
+    #[inline]
+    fn write_partial_witness<F>(&mut self, pw: &PartialWitness<F>) -> IoResult<()>
+    where
+        F: PrimeField64,
+    {
+        self.write_u32(u32::try_from(pw.target_values.len()).map_err(|_| IoError)?)?;
+        for (&target, &value) in pw.target_values.iter().sorted_by_key(|(&t, _)| t) {
+            self.write_target(target)?;
+            self.write_field(value)?;
+        }
+        Ok(())
+    }
+
     /// Writes a hash `h` to `self`.
     #[inline]
     fn write_hash<F, H>(&mut self, h: H::Hash) -> IoResult<()>
@@ -671,6 +1279,32 @@ pub trait Write {
         self.write_fri_proof::<F, C, D>(&proof.opening_proof)
     }
 
+    /// Writes the marker and version of the sectioned proof format to `self`.
+    #[inline]
+    fn write_proof_format_header(&mut self) -> IoResult<()> {
+        self.write_all(&PROOF_FORMAT_MARKER)?;
+        self.write_u8(PROOF_FORMAT_VERSION)
+    }
+
+    /// Writes a section tagged `tag`, holding `payload`, to `self`.
+    #[inline]
+    fn write_section(&mut self, tag: u8, payload: &[u8]) -> IoResult<()> {
+        self.write_u8(tag)?;
+        self.write_u32(payload.len() as u32)?;
+        self.write_all(payload)
+    }
+
+    /// Writes the public inputs section, holding `public_inputs`, to `self`.
+    #[inline]
+    fn write_public_inputs_section<F>(&mut self, public_inputs: &[F]) -> IoResult<()>
+    where
+        F: PrimeField64,
+    {
+        let mut payload = Vec::new();
+        payload.write_field_vec(public_inputs)?;
+        self.write_section(PUBLIC_INPUTS_SECTION, &payload)
+    }
+
     /// Writes a value `proof_with_pis` of type [`ProofWithPublicInputs`] to `self.`
     #[inline]
     fn write_proof_with_public_inputs<F, C, const D: usize>(
@@ -685,8 +1319,11 @@ pub trait Write {
             proof,
             public_inputs,
         } = proof_with_pis;
-        self.write_proof(proof)?;
-        self.write_field_vec(public_inputs)
+        self.write_proof_format_header()?;
+        let mut payload = Vec::new();
+        payload.write_proof(proof)?;
+        self.write_section(PROOF_SECTION, &payload)?;
+        self.write_public_inputs_section(public_inputs)
     }
 
     /// Writes a value `cfqrs` of type [`CompressedFriQueryRounds`] to `self.`
@@ -766,8 +1403,219 @@ pub trait Write {
             proof,
             public_inputs,
         } = proof_with_pis;
-        self.write_compressed_proof(proof)?;
-        self.write_field_vec(public_inputs)
+        self.write_proof_format_header()?;
+        let mut payload = Vec::new();
+        payload.write_compressed_proof(proof)?;
+        self.write_section(PROOF_SECTION, &payload)?;
+        self.write_public_inputs_section(public_inputs)
+    }
+
+    /// Writes a `bool` value `x` to `self`.
+    #[inline]
+    fn write_bool(&mut self, x: bool) -> IoResult<()> {
+        self.write_u8(u8::from(x))
+    }
+
+    /// Writes a vector `v` of `usize` values, prefixed by its length, to `self`.
+    #[inline]
+    fn write_usize_vec(&mut self, v: &[usize]) -> IoResult<()> {
+        self.write_u32(v.len() as u32)?;
+        for &x in v {
+            self.write_u32(x as u32)?;
+        }
+        Ok(())
+    }
+
+    /// Writes a vector `v` of field elements, prefixed by its length, to `self`.
+    #[inline]
+    fn write_length_prefixed_field_vec<F>(&mut self, v: &[F]) -> IoResult<()>
+    where
+        F: PrimeField64,
+    {
+        self.write_u32(v.len() as u32)?;
+        self.write_field_vec(v)
+    }
+
+    /// Writes `tree`, a value of type [`MerkleTree`], to `self`.
+    #[inline]
+    fn write_merkle_tree<F, H>(&mut self, tree: &MerkleTree<F, H>) -> IoResult<()>
+    where
+        F: RichField,
+        H: Hasher<F>,
+    {
+        self.write_u32(tree.leaves.len() as u32)?;
+        for leaf in &tree.leaves {
+            self.write_length_prefixed_field_vec(leaf)?;
+        }
+        self.write_u32(tree.digests.len() as u32)?;
+        for &digest in &tree.digests {
+            self.write_hash::<F, H>(digest)?;
+        }
+        self.write_merkle_cap(&tree.cap)
+    }
+
+    /// Writes `batch`, a value of type [`PolynomialBatch`], to `self`.
+    #[inline]
+    fn write_polynomial_batch<F, C, const D: usize>(
+        &mut self,
+        batch: &PolynomialBatch<F, C, D>,
+    ) -> IoResult<()>
+    where
+        F: RichField + Extendable<D>,
+        C: GenericConfig<D, F = F>,
+    {
+        self.write_u32(batch.degree_log as u32)?;
+        self.write_u32(batch.rate_bits as u32)?;
+        self.write_bool(batch.blinding)?;
+        self.write_u32(batch.polynomials.len() as u32)?;
+        for polynomial in &batch.polynomials {
+            self.write_length_prefixed_field_vec(&polynomial.coeffs)?;
+        }
+        self.write_merkle_tree(&batch.merkle_tree)
+    }
+
+    /// Writes `challenger`, a value of type [`Challenger`], to `self`.
+    #[inline]
+    fn write_challenger<F, H>(&mut self, challenger: &Challenger<F, H>) -> IoResult<()>
+    where
+        F: RichField,
+        H: Hasher<F>,
+    {
+        self.write_field_vec(challenger.sponge_state.as_ref())?;
+        self.write_length_prefixed_field_vec(&challenger.input_buffer)?;
+        self.write_length_prefixed_field_vec(&challenger.output_buffer)
+    }
+
+    /// Writes `checkpoint`, a value of type [`ProverCheckpoint`], to `self`.
+    #[inline]
+    fn write_prover_checkpoint<F, C, const D: usize>(
+        &mut self,
+        checkpoint: &ProverCheckpoint<F, C, D>,
+    ) -> IoResult<()>
+    where
+        F: RichField + Extendable<D>,
+        C: GenericConfig<D, F = F>,
+    {
+        self.write_field_vec(&checkpoint.public_inputs)?;
+        self.write_polynomial_batch(&checkpoint.wires_commitment)?;
+        self.write_polynomial_batch(&checkpoint.partial_products_and_zs_commitment)?;
+        self.write_polynomial_batch(&checkpoint.quotient_polys_commitment)?;
+        self.write_challenger(&checkpoint.challenger)
+    }
+
+    /// Writes a value `config` of type [`FriConfig`] to `self`.
+    #[inline]
+    fn write_fri_config(&mut self, config: &FriConfig) -> IoResult<()> {
+        self.write_u32(config.rate_bits as u32)?;
+        self.write_u32(config.cap_height as u32)?;
+        self.write_u32(config.proof_of_work_bits)?;
+        match &config.reduction_strategy {
+            FriReductionStrategy::Fixed(arity_bits) => {
+                self.write_u8(0)?;
+                self.write_usize_vec(arity_bits)?;
+            }
+            FriReductionStrategy::ConstantArityBits(arity_bits, final_poly_bits) => {
+                self.write_u8(1)?;
+                self.write_u32(*arity_bits as u32)?;
+                self.write_u32(*final_poly_bits as u32)?;
+            }
+            FriReductionStrategy::MinSize(max_arity_bits) => {
+                self.write_u8(2)?;
+                match max_arity_bits {
+                    None => self.write_u8(0)?,
+                    Some(max_arity_bits) => {
+                        self.write_u8(1)?;
+                        self.write_u32(*max_arity_bits as u32)?;
+                    }
+                }
+            }
+        }
+        self.write_u32(config.num_query_rounds as u32)
+    }
+
+    /// Writes a value `config` of type [`CircuitConfig`] to `self`.
+    #[inline]
+    fn write_circuit_config(&mut self, config: &CircuitConfig) -> IoResult<()> {
+        self.write_u32(config.num_wires as u32)?;
+        self.write_u32(config.num_routed_wires as u32)?;
+        self.write_u32(config.num_constants as u32)?;
+        self.write_bool(config.use_base_arithmetic_gate)?;
+        self.write_u32(config.security_bits as u32)?;
+        self.write_u32(config.num_challenges as u32)?;
+        self.write_bool(config.zero_knowledge)?;
+        self.write_u32(config.max_quotient_degree_factor as u32)?;
+        self.write_bool(config.max_arithmetic_ops_per_row.is_some())?;
+        if let Some(max_ops) = config.max_arithmetic_ops_per_row {
+            self.write_u32(max_ops as u32)?;
+        }
+        self.write_fri_config(&config.fri_config)
+    }
+
+    /// Writes a value `verifier_data` of type [`VerifierOnlyCircuitData`] to `self`.
+    #[inline]
+    fn write_verifier_only_circuit_data<F, C, const D: usize>(
+        &mut self,
+        verifier_data: &VerifierOnlyCircuitData<C, D>,
+    ) -> IoResult<()>
+    where
+        F: RichField + Extendable<D>,
+        C: GenericConfig<D, F = F>,
+    {
+        self.write_merkle_cap(&verifier_data.constants_sigmas_cap)?;
+        self.write_hash::<F, C::Hasher>(verifier_data.circuit_digest)
+    }
+
+    /// Writes a value `common_data` of type [`CommonCircuitData`] to `self`, using
+    /// `gate_serializer` to write the circuit's gates.
+    #[inline]
+    fn write_common_circuit_data<F, G, const D: usize>(
+        &mut self,
+        common_data: &CommonCircuitData<F, D>,
+        gate_serializer: &G,
+    ) -> IoResult<()>
+    where
+        F: RichField + Extendable<D>,
+        G: GateSerializer<F, D>,
+    {
+        let CommonCircuitData {
+            config,
+            fri_params,
+            gates,
+            selectors_info,
+            quotient_degree_factor,
+            num_gate_constraints,
+            num_constants,
+            num_public_inputs,
+            k_is,
+            num_partial_products,
+        } = common_data;
+
+        self.write_circuit_config(config)?;
+
+        // The FRI config is already part of the circuit config.
+        self.write_bool(fri_params.hiding)?;
+        self.write_u32(fri_params.degree_bits as u32)?;
+        self.write_usize_vec(&fri_params.reduction_arity_bits)?;
+
+        self.write_u32(gates.len() as u32)?;
+        for gate in gates {
+            gate_serializer.write_gate(self, gate)?;
+        }
+
+        self.write_usize_vec(&selectors_info.selector_indices)?;
+        self.write_u32(selectors_info.groups.len() as u32)?;
+        for group in &selectors_info.groups {
+            self.write_u32(group.start as u32)?;
+            self.write_u32(group.end as u32)?;
+        }
+
+        self.write_u32(*quotient_degree_factor as u32)?;
+        self.write_u32(*num_gate_constraints as u32)?;
+        self.write_u32(*num_constants as u32)?;
+        self.write_u32(*num_public_inputs as u32)?;
+        self.write_u32(k_is.len() as u32)?;
+        self.write_field_vec(k_is)?;
+        self.write_u32(*num_partial_products as u32)
     }
 }
 